@@ -0,0 +1,11 @@
+use std::process::Command;
+
+fn main() {
+    // recorded in run results so numbers can be tied to the compiler that produced them
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Ok(out) = Command::new(rustc).arg("--version").output() {
+        let version = String::from_utf8_lossy(&out.stdout);
+        println!("cargo:rustc-env=KVBENCHER_RUSTC_VERSION={}", version.trim());
+    }
+    println!("cargo:rerun-if-env-changed=RUSTC");
+}
@@ -0,0 +1,175 @@
+use clap::ValueEnum;
+use hdrhistogram::Histogram;
+use kvbencher::bench;
+use kvbencher::database::{DbOptions, get_db, get_db_name};
+use kvbencher::environment::Environment;
+use kvbencher::properties::Properties;
+use kvbencher::workload::preset;
+use kvbencher::workload::{self, WorkloadStats};
+use kvbencher::{DatabaseType, WorkloadType};
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict};
+
+/// Runs `workload` against `database` and returns the stats as a dict.
+///
+/// `properties` override workload parameters the same way `-p key=value` does on the CLI.
+#[pyfunction]
+#[pyo3(signature = (workload, database, properties = None))]
+fn run<'py>(
+    py: Python<'py>,
+    workload: &str,
+    database: &str,
+    properties: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let wl_type = WorkloadType::from_str(workload, true).map_err(PyValueError::new_err)?;
+    let db_type = DatabaseType::from_str(database, true).map_err(PyValueError::new_err)?;
+
+    let mut args = vec![];
+    if let Some(properties) = properties {
+        for (k, v) in properties.iter() {
+            let v = if v.is_instance_of::<PyBool>() {
+                v.extract::<bool>()?.to_string()
+            } else {
+                v.str()?.to_string()
+            };
+            args.push(format!("{}={v}", k.str()?));
+        }
+    }
+    let props = Properties::parse(&args).map_err(value_err)?;
+    let spec = preset::get(wl_type)
+        .and_then(|spec| spec.with_overrides(&props))
+        .map_err(value_err)?;
+
+    let (environment, stats) = py
+        .detach(|| {
+            let db = get_db(db_type, &DbOptions::default())?;
+            let environment = Environment::collect(db.data_dir());
+            Ok::<_, anyhow::Error>((environment, bench::run(db, &spec)?))
+        })
+        .map_err(|e| PyRuntimeError::new_err(format!("{e:#}")))?;
+
+    let d = stats_to_dict(py, &stats)?;
+    d.set_item("environment", environment_dict(py, &environment)?)?;
+    d.set_item("workload", &spec.name)?;
+    d.set_item("database", get_db_name(db_type))?;
+    Ok(d)
+}
+
+/// Names accepted for the `workload` argument of `run`
+#[pyfunction]
+fn workloads() -> Vec<String> {
+    WorkloadType::value_variants()
+        .iter()
+        .filter_map(|v| v.to_possible_value())
+        .map(|v| v.get_name().to_string())
+        .collect()
+}
+
+/// Names accepted for the `database` argument of `run`
+#[pyfunction]
+fn databases() -> Vec<String> {
+    DatabaseType::value_variants()
+        .iter()
+        .filter_map(|v| v.to_possible_value())
+        .map(|v| v.get_name().to_string())
+        .collect()
+}
+
+fn stats_to_dict<'py>(py: Python<'py>, stats: &WorkloadStats) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("load_ops", stats.load_ops)?;
+    d.set_item("load_time_s", stats.load_time.as_secs_f64())?;
+    d.set_item("run_wall_time_s", stats.run_wall_time.as_secs_f64())?;
+    d.set_item("read_ops", stats.run_read_ops)?;
+    d.set_item("read_time_s", stats.run_read_time.as_secs_f64())?;
+    d.set_item(
+        "read_latency_us",
+        latency_dict(py, &stats.run_read_hist_nano_sec)?,
+    )?;
+    d.set_item("write_ops", stats.run_write_ops)?;
+    d.set_item("write_time_s", stats.run_write_time.as_secs_f64())?;
+    d.set_item(
+        "write_latency_us",
+        latency_dict(py, &stats.run_write_hist_nano_sec)?,
+    )?;
+    for (kind, s) in stats.run_extra_ops() {
+        if let Some(s) = s {
+            let kind = kind.replace('-', "_");
+            d.set_item(format!("{kind}_ops"), s.ops)?;
+            d.set_item(format!("{kind}_time_s"), s.time.as_secs_f64())?;
+            d.set_item(
+                format!("{kind}_latency_us"),
+                latency_dict(py, &s.hist_nano_sec)?,
+            )?;
+        }
+    }
+    if stats.run_scan.is_some() {
+        d.set_item("scan_records", stats.run_scan_records)?;
+    }
+    if !stats.run_tenant_ops.is_empty() {
+        d.set_item("tenant_ops", stats.run_tenant_ops.clone())?;
+    }
+    d.set_item("load_energy_j", stats.load_energy_joules)?;
+    d.set_item("run_energy_j", stats.run_energy_joules)?;
+    if let Some((database, harness, idle)) = stats.run_utilization() {
+        let u = PyDict::new(py);
+        u.set_item("database", database)?;
+        u.set_item("harness", harness)?;
+        u.set_item("idle", idle)?;
+        d.set_item("run_utilization", u)?;
+    }
+    Ok(d)
+}
+
+fn environment_dict<'py>(py: Python<'py>, env: &Environment) -> PyResult<Bound<'py, PyDict>> {
+    let d = PyDict::new(py);
+    d.set_item("kvbencher_version", &env.kvbencher_version)?;
+    d.set_item("os", &env.os)?;
+    d.set_item("arch", &env.arch)?;
+    d.set_item("cpus", env.cpus)?;
+    d.set_item("os_release", &env.os_release)?;
+    d.set_item("kernel", &env.kernel)?;
+    d.set_item("cpu_model", &env.cpu_model)?;
+    d.set_item("memory_bytes", env.memory_bytes)?;
+    d.set_item("rustc_version", &env.rustc_version)?;
+    let storage = match &env.storage {
+        Some(s) => {
+            let sd = PyDict::new(py);
+            sd.set_item("filesystem", &s.filesystem)?;
+            sd.set_item("device", &s.device)?;
+            sd.set_item("device_model", &s.device_model)?;
+            Some(sd)
+        }
+        None => None,
+    };
+    d.set_item("storage", storage)?;
+    Ok(d)
+}
+
+fn latency_dict<'py>(py: Python<'py>, h: &Histogram<u64>) -> PyResult<Option<Bound<'py, PyDict>>> {
+    if h.is_empty() {
+        return Ok(None);
+    }
+    let d = PyDict::new(py);
+    // the histograms hold nanoseconds, the dict microseconds
+    d.set_item("mean", h.mean() / 1_000.0)?;
+    d.set_item("min", h.min() as f64 / 1_000.0)?;
+    d.set_item("max", h.max() as f64 / 1_000.0)?;
+    for (name, q) in [("p50", 0.50), ("p95", 0.95), ("p99", 0.99), ("p999", 0.999)] {
+        d.set_item(name, workload::quantile_micros(h, q))?;
+    }
+    Ok(Some(d))
+}
+
+fn value_err(e: anyhow::Error) -> PyErr {
+    PyValueError::new_err(format!("{e:#}"))
+}
+
+#[pymodule(name = "kvbencher")]
+fn kvbencher_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(run, m)?)?;
+    m.add_function(wrap_pyfunction!(workloads, m)?)?;
+    m.add_function(wrap_pyfunction!(databases, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::cache;
+use crate::cpufreq;
+use crate::database::Database;
+use crate::energy::EnergyMeter;
+use crate::profile::{PhaseProfiler, ProfileOptions};
+use crate::sampler::Sampler;
+use crate::trace::{TraceOptions, Tracing};
+use crate::workload::spec::WorkloadSpec;
+use crate::workload::{self, Workload, WorkloadStats};
+use anyhow::Result;
+use hdrhistogram::Histogram;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The handful of numbers a macro-benchmark wrapper usually tracks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Headline {
+    pub load_ops_per_sec: f64,
+    pub run_ops_per_sec: f64,
+    pub read_p50_micro_sec: Option<f64>,
+    pub read_p99_micro_sec: Option<f64>,
+    pub write_p50_micro_sec: Option<f64>,
+    pub write_p99_micro_sec: Option<f64>,
+    /// Run phase operations per joule used by the cpu packages, where RAPL is readable
+    #[serde(default)]
+    pub run_ops_per_joule: Option<f64>,
+}
+
+impl From<&WorkloadStats> for Headline {
+    fn from(stats: &WorkloadStats) -> Self {
+        let per_sec = |ops: u64, d: Duration| {
+            if d.is_zero() {
+                0.0
+            } else {
+                ops as f64 / d.as_secs_f64()
+            }
+        };
+        let quantile =
+            |h: &Histogram<u64>, q: f64| (!h.is_empty()).then(|| workload::quantile_micros(h, q));
+        Headline {
+            load_ops_per_sec: per_sec(stats.load_ops, stats.load_time),
+            run_ops_per_sec: per_sec(stats.run_ops(), stats.run_wall_time),
+            read_p50_micro_sec: quantile(&stats.run_read_hist_nano_sec, 0.50),
+            read_p99_micro_sec: quantile(&stats.run_read_hist_nano_sec, 0.99),
+            write_p50_micro_sec: quantile(&stats.run_write_hist_nano_sec, 0.50),
+            write_p99_micro_sec: quantile(&stats.run_write_hist_nano_sec, 0.99),
+            run_ops_per_joule: stats
+                .run_energy_joules
+                .filter(|j| *j > 0.0)
+                .map(|j| stats.run_ops() as f64 / j),
+        }
+    }
+}
+
+/// Knobs for how `run_with` executes the phases of a workload
+#[derive(Clone, Debug, Default)]
+pub struct RunOptions {
+    /// Skip the load phase and read data left by an earlier run. The earlier run must have
+    /// used the same `record_count` and `key_size` for keys to match.
+    pub skip_load: bool,
+    /// Drop the OS page cache for the database's files before the run phase
+    pub drop_caches: bool,
+    /// Write a flamegraph of each phase
+    pub profile: Option<ProfileOptions>,
+    /// Export a sample of the run phase's operations as OTLP spans
+    pub trace: Option<TraceOptions>,
+    /// Record a time series of system activity during the run phase at this interval
+    pub report_interval: Option<Duration>,
+    /// Reserve this many bytes for the database's files before the load phase
+    pub preallocate_bytes: Option<u64>,
+}
+
+/// Runs the load and run phases of `spec` against `db`, returning the full stats
+pub fn run(db: Arc<dyn Database>, spec: &WorkloadSpec) -> Result<WorkloadStats> {
+    run_with(db, spec, &RunOptions::default())
+}
+
+pub fn run_with(
+    db: Arc<dyn Database>,
+    spec: &WorkloadSpec,
+    opts: &RunOptions,
+) -> Result<WorkloadStats> {
+    let mut stats = spec.init_stats()?;
+    // started up front so a misconfigured exporter fails before the load phase
+    let tracing = opts.trace.as_ref().map(Tracing::start).transpose()?;
+    if opts.skip_load {
+        spec.prepare(&db, &mut stats)?;
+    } else {
+        if let Some(bytes) = opts.preallocate_bytes {
+            db.preallocate(bytes)?;
+        }
+        let profiler = start_profiler(opts, "load")?;
+        let energy = EnergyMeter::start();
+        spec.exec_load(db.clone(), &mut stats)?;
+        stats.load_energy_joules = energy.and_then(|e| e.joules());
+        finish_profiler(profiler)?;
+    }
+    if opts.drop_caches {
+        cache::drop_caches(db.data_dir())?;
+    }
+    let db = match &tracing {
+        Some(t) => t.wrap(db),
+        None => db,
+    };
+    let sampler = opts
+        .report_interval
+        .map(|interval| Sampler::start(interval, db.clone(), spec.thread_count as usize));
+    let db = match &sampler {
+        Some(s) => s.probe(db),
+        None => db,
+    };
+    let profiler = start_profiler(opts, "run")?;
+    let energy = EnergyMeter::start();
+    let misses = db.read_misses();
+    spec.exec_run(db.clone(), &mut stats)?;
+    stats.run_read_misses = db
+        .read_misses()
+        .zip(misses)
+        .map(|(after, before)| after - before);
+    stats.run_energy_joules = energy.and_then(|e| e.joules());
+    finish_profiler(profiler)?;
+    if let Some(sampler) = sampler {
+        stats.run_time_series = sampler.finish();
+        if let Some(warning) = cpufreq::throttling_warning(&stats.run_time_series) {
+            stats.warnings.push(warning);
+        }
+    }
+    if let Some(t) = tracing {
+        t.shutdown()?;
+    }
+    Ok(stats)
+}
+
+fn start_profiler(opts: &RunOptions, phase: &str) -> Result<Option<PhaseProfiler>> {
+    opts.profile
+        .as_ref()
+        .map(|p| PhaseProfiler::start(p, phase))
+        .transpose()
+}
+
+fn finish_profiler(profiler: Option<PhaseProfiler>) -> Result<()> {
+    if let Some(profiler) = profiler {
+        profiler.finish()?;
+    }
+    Ok(())
+}
+
+/// Runs `spec` against `db` and returns only the headline metrics.
+///
+/// Intended for `cargo bench`/criterion wrappers in engine repositories, e.g.
+/// `kvbencher::bench::run_headline(Arc::new(MyEngine::new()), &preset::read_heavy())`.
+pub fn run_headline(db: Arc<dyn Database>, spec: &WorkloadSpec) -> Result<Headline> {
+    run(db, spec).map(|stats| Headline::from(&stats))
+}
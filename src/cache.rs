@@ -0,0 +1,52 @@
+use anyhow::Result;
+use std::path::Path;
+
+/// Evicts the database's files from the OS page cache.
+///
+/// Tries the global `/proc/sys/vm/drop_caches` first (needs root), then falls back to
+/// `posix_fadvise(DONTNEED)` on every file under `data_dir`. Caches kept inside the engine's
+/// own process memory are not affected.
+#[cfg(target_os = "linux")]
+pub fn drop_caches(data_dir: Option<&Path>) -> Result<()> {
+    use anyhow::{Context, bail};
+
+    // SAFETY: sync takes no arguments and cannot fail
+    unsafe { libc::sync() };
+    if std::fs::write("/proc/sys/vm/drop_caches", "3").is_ok() {
+        return Ok(());
+    }
+    let Some(dir) = data_dir else {
+        bail!(
+            "cannot drop caches: writing /proc/sys/vm/drop_caches needs root and the database has no data dir"
+        );
+    };
+    fadvise_dontneed(dir)
+        .with_context(|| format!("failed to drop page cache for {}", dir.display()))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn drop_caches(_data_dir: Option<&Path>) -> Result<()> {
+    anyhow::bail!("--drop-caches is only supported on Linux")
+}
+
+#[cfg(target_os = "linux")]
+fn fadvise_dontneed(path: &Path) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            fadvise_dontneed(&entry.path())?;
+            continue;
+        }
+        let f = std::fs::File::open(entry.path())?;
+        // dirty pages cannot be dropped, write them out first
+        f.sync_data()?;
+        // SAFETY: the fd is valid for the lifetime of `f`
+        let ret = unsafe { libc::posix_fadvise(f.as_raw_fd(), 0, 0, libc::POSIX_FADV_DONTNEED) };
+        if ret != 0 {
+            return Err(std::io::Error::from_raw_os_error(ret).into());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,163 @@
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+const CPU_PERIOD_MICRO_SEC: u64 = 100_000;
+
+/// Memory and cpu budget the benchmark process runs under
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ResourceLimits {
+    pub memory_bytes: Option<u64>,
+    /// Number of cpus worth of time per scheduling period, may be fractional
+    pub cpus: Option<f64>,
+}
+
+impl ResourceLimits {
+    pub fn is_empty(&self) -> bool {
+        self.memory_bytes.is_none() && self.cpus.is_none()
+    }
+}
+
+/// A cgroup the current process was moved into, left and removed again on drop
+pub struct Cgroup {
+    /// (cgroup dir, cgroup.procs of the group the process came from)
+    dirs: Vec<(PathBuf, PathBuf)>,
+}
+
+impl Cgroup {
+    /// Creates a cgroup with `limits` and moves the whole process (all threads) into it.
+    /// Supports the unified v2 hierarchy and the v1 memory/cpu controllers, needs root.
+    pub fn enter(limits: ResourceLimits) -> Result<Self> {
+        let name = format!("kvbencher-{}", std::process::id());
+        let root = Path::new(CGROUP_ROOT);
+        let mut cgroup = Cgroup { dirs: vec![] };
+        if root.join("cgroup.controllers").exists() {
+            // v2: one group under the root, whose subtree control has to enable the controllers
+            let controllers = [
+                limits.memory_bytes.map(|_| "+memory"),
+                limits.cpus.map(|_| "+cpu"),
+            ];
+            for c in controllers.into_iter().flatten() {
+                write(&root.join("cgroup.subtree_control"), c)?;
+            }
+            let dir = cgroup.create(root, None, &name)?;
+            if let Some(bytes) = limits.memory_bytes {
+                write(&dir.join("memory.max"), &bytes.to_string())?;
+            }
+            if let Some(cpus) = limits.cpus {
+                let cpu_max = format!("{} {CPU_PERIOD_MICRO_SEC}", cpu_quota(cpus));
+                write(&dir.join("cpu.max"), &cpu_max)?;
+            }
+            write(&dir.join("cgroup.procs"), &std::process::id().to_string())?;
+        } else {
+            // v1: one group per controller hierarchy
+            if let Some(bytes) = limits.memory_bytes {
+                let dir = cgroup.create(&root.join("memory"), Some("memory"), &name)?;
+                write(&dir.join("memory.limit_in_bytes"), &bytes.to_string())?;
+                write(&dir.join("cgroup.procs"), &std::process::id().to_string())?;
+            }
+            if let Some(cpus) = limits.cpus {
+                let dir = cgroup.create(&root.join("cpu"), Some("cpu"), &name)?;
+                write(
+                    &dir.join("cpu.cfs_period_us"),
+                    &CPU_PERIOD_MICRO_SEC.to_string(),
+                )?;
+                write(&dir.join("cpu.cfs_quota_us"), &cpu_quota(cpus).to_string())?;
+                write(&dir.join("cgroup.procs"), &std::process::id().to_string())?;
+            }
+        }
+        Ok(cgroup)
+    }
+
+    fn create(
+        &mut self,
+        hierarchy: &Path,
+        controller: Option<&str>,
+        name: &str,
+    ) -> Result<PathBuf> {
+        if !hierarchy.is_dir() {
+            bail!("cgroup hierarchy {} is not mounted", hierarchy.display());
+        }
+        let current = current_cgroup(controller)?;
+        let dir = hierarchy.join(name);
+        std::fs::create_dir(&dir).with_context(|| {
+            format!(
+                "failed to create cgroup {}, resource limits need root",
+                dir.display()
+            )
+        })?;
+        let current_procs = hierarchy
+            .join(current.trim_start_matches('/'))
+            .join("cgroup.procs");
+        self.dirs.push((dir.clone(), current_procs));
+        Ok(dir)
+    }
+}
+
+impl Drop for Cgroup {
+    fn drop(&mut self) {
+        for (dir, parent_procs) in self.dirs.drain(..) {
+            // a cgroup can only be removed once it has no processes left
+            let _ = std::fs::write(parent_procs, std::process::id().to_string());
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Path of the process's cgroup within the v1 `controller` hierarchy, or the v2 hierarchy
+fn current_cgroup(controller: Option<&str>) -> Result<String> {
+    let content = std::fs::read_to_string("/proc/self/cgroup")?;
+    content
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.splitn(3, ':');
+            let (_, controllers, path) = (parts.next()?, parts.next()?, parts.next()?);
+            let matches = match controller {
+                Some(c) => controllers.split(',').any(|x| x == c),
+                None => controllers.is_empty(),
+            };
+            matches.then(|| path.to_string())
+        })
+        .next()
+        .context("process is not in a cgroup of the requested hierarchy")
+}
+
+/// Microseconds of cpu time per period that `cpus` cpus amount to
+fn cpu_quota(cpus: f64) -> u64 {
+    (cpus * CPU_PERIOD_MICRO_SEC as f64) as u64
+}
+
+fn write(path: &Path, value: &str) -> Result<()> {
+    std::fs::write(path, value)
+        .with_context(|| format!("failed to write `{value}` to {}", path.display()))
+}
+
+/// Parses sizes like `512M`, `2G` or `1073741824` (binary units)
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (digits, unit) = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .map_or((s, ""), |i| s.split_at(i));
+    let shift = match unit
+        .trim()
+        .to_ascii_uppercase()
+        .trim_end_matches(['B', 'I'])
+    {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        "T" => 40,
+        _ => return Err(format!("unknown size unit in `{s}`, expected K, M, G or T")),
+    };
+    let value: f64 = digits.parse().map_err(|_| format!("invalid size `{s}`"))?;
+    Ok((value * (1u64 << shift) as f64) as u64)
+}
+
+/// Parses a positive, possibly fractional, cpu count
+pub fn parse_cpus(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(cpus) if cpus > 0.0 && cpus.is_finite() => Ok(cpus),
+        _ => Err(format!("expected a positive number of cpus, got `{s}`")),
+    }
+}
@@ -0,0 +1,861 @@
+use crate::bench::{self, Headline, RunOptions};
+use crate::cgroup::{self, Cgroup, ResourceLimits};
+use crate::compare;
+use crate::container::Container;
+use crate::database::{
+    Backend, BackendParser, DataDirKind, Database, DbOptions, is_enabled, is_networked,
+    registered_databases,
+};
+use crate::environment::Environment;
+use crate::fill::{self, FillLimits};
+use crate::history::{self, RunRecord};
+use crate::netem::{self, NetworkConditions};
+use crate::profile::ProfileOptions;
+use crate::properties::Properties;
+use crate::store::{self, Filter, Store};
+use crate::summary::{self, Verdict};
+use crate::trace::TraceOptions;
+use crate::upload::Destination;
+use crate::workload::spec::{Scale, WorkloadSpec};
+use crate::workload::{self, Workload, preset};
+use crate::{DatabaseType, WorkloadType, ycsb};
+use anyhow::{Context, Result, bail};
+use clap::builder::PossibleValue;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thousands::Separable;
+
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a workload against a database
+    Run(Box<RunArgs>),
+    /// List available databases and workloads
+    List,
+    /// Compare two result files (`run --output json` or history files)
+    Compare(CompareArgs),
+    /// Print every run stored in a history file
+    Report { file: PathBuf },
+    /// Run every combination of the given workloads and databases
+    Matrix(MatrixArgs),
+    /// Filter or aggregate runs kept in a result store
+    Query(QueryArgs),
+    /// Insert into a database until it reaches a size or the disk a fill level, measuring
+    /// how inserts degrade as it grows
+    Fill(FillArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(value_enum)]
+    workload: WorkloadType,
+
+    #[arg(value_parser = BackendParser)]
+    database: Backend,
+
+    /// Optional properties as `key=value`, overriding workload parameters (e.g. `-p thread_count=4`)
+    /// or configuring the database when prefixed with `db.` (e.g. `-p db.direct_io=true`)
+    #[arg(short = 'p')]
+    properties: Vec<String>,
+
+    /// YCSB workload file applied on top of the selected workload, before `-p` properties
+    #[arg(long)]
+    ycsb_properties: Option<PathBuf>,
+
+    /// Scale record and operation counts: smoke (0.01x), small (1x), medium (10x), large (100x) or a multiplier
+    #[arg(long, default_value = "small")]
+    scale: Scale,
+
+    /// Format of the printed results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// Print only the headline metrics as a single JSON line
+    #[arg(long, conflicts_with = "output")]
+    quiet_machine: bool,
+
+    /// Directory file-backed databases are created in, a temp dir by default
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+
+    /// Storage to place file-backed databases on, `tmpfs` uses a temp dir in /dev/shm unless
+    /// `--data-dir` (which must then be on tmpfs) is given
+    #[arg(long, value_enum, default_value_t = DataDirKind::Disk)]
+    data_dir_kind: DataDirKind,
+
+    /// Keep the database files after the run
+    #[arg(long)]
+    keep_data: bool,
+
+    /// Skip the load phase and run against data kept by an earlier run in `--data-dir`
+    #[arg(long, requires = "data_dir")]
+    run_only: bool,
+
+    /// Reserve disk space for the database files before loading, so file growth is not
+    /// measured. Defaults to the size of the loaded keys and values, e.g. `--preallocate=4G`
+    #[arg(long, value_name = "SIZE", num_args = 0..=1, require_equals = true, value_parser = cgroup::parse_size)]
+    preallocate: Option<Option<u64>>,
+
+    /// Drop the OS page cache between the load and run phases to measure cold reads (Linux only)
+    #[arg(long)]
+    drop_caches: bool,
+
+    /// Run inside a cgroup with this memory limit, e.g. `2G` (Linux only, needs root)
+    #[arg(long, value_parser = cgroup::parse_size)]
+    memory_limit: Option<u64>,
+
+    /// Run inside a cgroup limited to this many cpus, e.g. `4` or `0.5` (Linux only, needs root)
+    #[arg(long, value_parser = cgroup::parse_cpus)]
+    cpu_limit: Option<f64>,
+
+    /// Seconds between the samples of system activity (e.g. disk stats) recorded in the
+    /// run phase's time series
+    #[arg(long, default_value = "1", value_parser = parse_interval)]
+    report_interval: Duration,
+
+    /// Profile the load and run phases, writing one flamegraph per phase
+    #[arg(long, value_enum)]
+    profile: Option<Profiler>,
+
+    /// Directory the profiles of `--profile` are written to
+    #[arg(long, default_value = ".", requires = "profile")]
+    profile_dir: PathBuf,
+
+    /// Export a sample of the run phase's operations as spans to this OTLP/HTTP collector,
+    /// e.g. `http://localhost:4318` (needs the `otlp` feature)
+    #[arg(long, value_name = "URL")]
+    otlp_endpoint: Option<String>,
+
+    /// Fraction of operations exported with `--otlp-endpoint`
+    #[arg(long, default_value_t = 0.01, value_parser = parse_fraction, requires = "otlp_endpoint")]
+    trace_sample_rate: f64,
+
+    /// Round trip time added to every database operation, to model a remote database,
+    /// e.g. `20ms`
+    #[arg(long, value_parser = netem::parse_duration)]
+    net_latency: Option<Duration>,
+
+    /// Random deviation of `--net-latency` in either direction, e.g. `5ms`
+    #[arg(long, value_parser = netem::parse_duration)]
+    net_jitter: Option<Duration>,
+
+    /// Fraction of operations that suffer a 200ms retransmission, e.g. `0.01`
+    #[arg(long, value_parser = parse_fraction)]
+    net_loss: Option<f64>,
+
+    /// Where the networked database listens and how to log in, as
+    /// `[scheme://][user[:password]@]host[:port][,host[:port]...][/database][?tls=true&tls_ca=<file>]`,
+    /// short for `-p db.endpoint=...`
+    #[arg(long, value_name = "CONNECTION", conflicts_with = "container")]
+    endpoint: Option<String>,
+
+    /// Start the networked database in a docker container for the duration of the run,
+    /// optionally with a specific image such as `redis:7.2`
+    #[arg(long, value_name = "IMAGE")]
+    container: Option<Option<String>>,
+
+    /// Append a JSON record of this run to the given history file
+    #[arg(long)]
+    history: Option<PathBuf>,
+
+    /// Store this run in the given SQLite result store, see `query`
+    #[arg(long)]
+    store: Option<PathBuf>,
+
+    /// Write a Markdown summary of the run, e.g. for CI job summaries or PR comments
+    #[arg(long)]
+    summary_file: Option<PathBuf>,
+
+    /// Result file of an earlier run to judge this run against in `--summary-file`
+    #[arg(long, requires = "summary_file")]
+    baseline: Option<PathBuf>,
+
+    /// Changes for the worse beyond this many percent against `--baseline` are regressions
+    #[arg(long, default_value_t = 5.0, requires = "baseline")]
+    threshold: f64,
+
+    /// Upload the run's artifacts (json record, histograms) to `s3://bucket/prefix` or
+    /// `gs://bucket/prefix` with the aws or gcloud CLI
+    #[arg(long, value_name = "URL")]
+    upload: Option<Destination>,
+
+    /// Write every run phase operation to this file, replayable with the `replay` workload
+    /// (`-p replay_ops=<file>`)
+    #[arg(long, value_name = "FILE")]
+    record_ops: Option<PathBuf>,
+
+    /// Revision of the engine under test, stored in the history record
+    #[arg(long)]
+    engine_revision: Option<String>,
+
+    /// Tag the run as `name=value`, e.g. `cache=512MB` or `branch=feature-x`, carried into
+    /// every output, the history and the store; repeatable
+    #[arg(long = "label", value_name = "NAME=VALUE", value_parser = history::parse_label)]
+    labels: Vec<(String, String)>,
+}
+
+#[derive(Args)]
+struct MatrixArgs {
+    /// Workloads to run, defaults to every workload but custom and replay
+    #[arg(long, value_enum, value_delimiter = ',')]
+    workloads: Vec<WorkloadType>,
+
+    /// Databases to run against, defaults to every in-process database, networked ones need a
+    /// server and are only run when listed
+    #[arg(long, value_parser = BackendParser, value_delimiter = ',')]
+    databases: Vec<Backend>,
+
+    /// Optional properties as `key=value`, applied to every workload and database
+    #[arg(short = 'p')]
+    properties: Vec<String>,
+
+    /// Where the networked databases listen, short for `-p db.endpoint=...`
+    #[arg(long, value_name = "CONNECTION")]
+    endpoint: Option<String>,
+
+    /// Scale record and operation counts: smoke (0.01x), small (1x), medium (10x), large (100x) or a multiplier
+    #[arg(long, default_value = "small")]
+    scale: Scale,
+
+    /// Run every combination at each of these pipeline depths, e.g. `1,8,32`
+    #[arg(long, value_delimiter = ',')]
+    pipeline_depths: Vec<u32>,
+}
+
+#[derive(Args)]
+struct CompareArgs {
+    /// Result file of the baseline run
+    baseline: PathBuf,
+
+    /// Result file of the candidate run
+    candidate: PathBuf,
+
+    /// Changes for the worse beyond this many percent are flagged as regressions
+    #[arg(long, default_value_t = 5.0)]
+    threshold: f64,
+
+    /// Exit with an error when any metric regressed
+    #[arg(long)]
+    fail_on_regression: bool,
+
+    /// Also write the comparison as a Markdown summary
+    #[arg(long)]
+    summary_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct QueryArgs {
+    /// SQLite result store written by `run --store`
+    store: PathBuf,
+
+    /// Only runs against this database, e.g. `redb`
+    #[arg(long)]
+    database: Option<String>,
+
+    /// Only runs of this workload, e.g. `ReadHeavy`
+    #[arg(long)]
+    workload: Option<String>,
+
+    /// Only runs on or after this date (YYYY-MM-DD, UTC)
+    #[arg(long, value_parser = store::parse_date)]
+    since: Option<String>,
+
+    /// Only runs before this date (YYYY-MM-DD, UTC)
+    #[arg(long, value_parser = store::parse_date)]
+    until: Option<String>,
+
+    /// Only runs labeled `name=value` with `run --label`; repeatable, all must match
+    #[arg(long = "label", value_name = "NAME=VALUE", value_parser = history::parse_label)]
+    labels: Vec<(String, String)>,
+
+    /// Aggregate matching runs per database and workload instead of listing them
+    #[arg(long)]
+    aggregate: bool,
+}
+
+#[derive(Args)]
+#[command(group = clap::ArgGroup::new("limit").required(true).multiple(true))]
+struct FillArgs {
+    #[arg(value_parser = BackendParser)]
+    database: Backend,
+
+    /// Properties as `key=value`: `key_size`, `value_size_min` and `value_size_max` set the
+    /// inserted records, `db.*` properties configure the database
+    #[arg(short = 'p')]
+    properties: Vec<String>,
+
+    /// Stop once the data dir takes this much disk space, e.g. `10G`
+    #[arg(long, group = "limit", value_parser = cgroup::parse_size)]
+    target_size: Option<u64>,
+
+    /// Stop once the data dir's filesystem is this many percent full, e.g. `80`
+    #[arg(long, group = "limit")]
+    disk_full: Option<f64>,
+
+    /// Bytes of keys and values inserted between two measurements
+    #[arg(long, default_value = "64M", value_parser = cgroup::parse_size)]
+    step: u64,
+
+    /// Directory the database is created in, a temp dir by default
+    #[arg(long)]
+    data_dir: Option<PathBuf>,
+
+    /// Storage to place the database on
+    #[arg(long, value_enum, default_value_t = DataDirKind::Disk)]
+    data_dir_kind: DataDirKind,
+
+    /// Keep the database files after the fill
+    #[arg(long)]
+    keep_data: bool,
+
+    /// Print the steps as a JSON array instead of a table
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum OutputFormat {
+    Text,
+    /// YCSB's `[OVERALL]`/`[READ]`/`[UPDATE]` measurement format
+    Ycsb,
+    /// The full run record, readable by `compare`
+    Json,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+enum Profiler {
+    /// Sample the process with pprof and write `flamegraph-<phase>.svg`
+    Flamegraph,
+}
+
+/// The kvbencher command line, for binaries that register their own databases first
+pub fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Run(args) => run(*args),
+        Command::List => list(),
+        Command::Compare(args) => compare(args),
+        Command::Report { file } => report(&file),
+        Command::Matrix(args) => matrix(args),
+        Command::Query(args) => query(args),
+        Command::Fill(args) => fill(args),
+    }
+}
+
+fn run(args: RunArgs) -> Result<()> {
+    let props = Properties::parse(&args.properties)?;
+    if args.run_only && !args.database.is_file_backed() {
+        bail!(
+            "--run-only needs a file-backed database, {} keeps no data between runs",
+            args.database.name()
+        );
+    }
+    let limits = ResourceLimits {
+        memory_bytes: args.memory_limit,
+        cpus: args.cpu_limit,
+    };
+    // entered before the database is opened so its memory is accounted to the cgroup
+    let _cgroup = if limits.is_empty() {
+        None
+    } else {
+        Some(Cgroup::enter(limits)?)
+    };
+    let mut opts = DbOptions {
+        data_dir: args.data_dir,
+        data_dir_kind: args.data_dir_kind,
+        // a run-only invocation must not delete the data it was pointed at
+        keep_data: args.keep_data || args.run_only,
+        properties: props.db(),
+    };
+    if let Some(endpoint) = args.endpoint {
+        if opts.properties.contains_key("endpoint") {
+            bail!("--endpoint cannot be combined with -p db.endpoint");
+        }
+        opts.properties.insert("endpoint".to_string(), endpoint);
+    }
+    let _container = match &args.container {
+        Some(image) => {
+            let Some(spec) = args.database.container_spec() else {
+                // embedded, or a cluster (TiKV) that does not fit one container
+                bail!(
+                    "--container needs a networked database with a single-container server, {} has none",
+                    args.database.name()
+                );
+            };
+            let container = Container::start(&spec, image.as_deref())?;
+            eprintln!(
+                "started {} as {} on {}",
+                image.as_deref().unwrap_or(spec.default_image),
+                container.id_short(),
+                container.addr()
+            );
+            opts.properties
+                .insert("endpoint".to_string(), container.addr().to_string());
+            Some(container)
+        }
+        None => None,
+    };
+    let mut database = args.database.open(&opts)?;
+    note_kept_data(database.as_ref(), &opts);
+    let network = NetworkConditions {
+        latency: args.net_latency.unwrap_or_default(),
+        jitter: args.net_jitter.unwrap_or_default(),
+        loss: args.net_loss.unwrap_or_default(),
+    };
+    if !network.is_empty() {
+        database = network.wrap(database);
+    }
+
+    let mut wl = preset::get(args.workload)?;
+    if let Some(path) = &args.ycsb_properties {
+        wl = ycsb::apply_properties_file(&wl, path)?;
+    }
+    let mut wl = wl.scaled(args.scale).with_overrides(&props)?;
+    if let Some(path) = args.record_ops.clone() {
+        wl.record_ops = Some(path);
+    }
+    let run_opts = RunOptions {
+        skip_load: args.run_only,
+        drop_caches: args.drop_caches,
+        profile: args.profile.map(|Profiler::Flamegraph| ProfileOptions {
+            dir: args.profile_dir.clone(),
+        }),
+        trace: args.otlp_endpoint.clone().map(|endpoint| TraceOptions {
+            endpoint,
+            sample_rate: args.trace_sample_rate,
+            database: args.database.name(),
+        }),
+        report_interval: Some(args.report_interval),
+        preallocate_bytes: args
+            .preallocate
+            .map(|size| size.unwrap_or_else(|| wl.data_size_bytes())),
+    };
+    let environment = Environment::collect(database.data_dir());
+    let stats = bench::run_with(database, &wl, &run_opts)?;
+    if let Some(profile) = &run_opts.profile {
+        let phases = if run_opts.skip_load {
+            &["run"][..]
+        } else {
+            &["load", "run"]
+        };
+        for phase in phases {
+            eprintln!("wrote flamegraph {}", profile.path(phase).display());
+        }
+    }
+    // the text output ends with them, the others would bury them in the record
+    if args.quiet_machine || !matches!(args.output, OutputFormat::Text) {
+        for warning in &stats.warnings {
+            eprintln!("warning: {warning}");
+        }
+    }
+    let record = RunRecord::new(
+        args.database.name(),
+        wl,
+        args.engine_revision,
+        args.labels.into_iter().collect(),
+        environment,
+        stats,
+    );
+    if let Some(path) = &args.history {
+        history::append(path, &record)?;
+    }
+    if let Some(path) = &args.store {
+        Store::open(path)?.insert(&record)?;
+    }
+    if let Some(path) = &args.summary_file {
+        let baseline = args
+            .baseline
+            .as_deref()
+            .map(compare::load_record)
+            .transpose()?;
+        let verdict = baseline.as_ref().map(|baseline| Verdict {
+            baseline,
+            threshold_percent: args.threshold,
+        });
+        write_summary(path, &record, verdict.as_ref())?;
+    }
+    if let Some(dest) = &args.upload {
+        let url = dest.upload(&record)?;
+        eprintln!("uploaded results to {url}");
+    }
+
+    if args.quiet_machine {
+        let line = QuietLine {
+            headline: &record.headline,
+            labels: &record.labels,
+            environment: &record.environment,
+        };
+        println!("{}", serde_json::to_string(&line)?);
+        return Ok(());
+    }
+    match args.output {
+        OutputFormat::Text => print_text(&record),
+        OutputFormat::Ycsb => {
+            for (k, v) in &record.labels {
+                println!("[LABEL], {k}, {v}");
+            }
+            println!("{}", ycsb::YcsbReport(&record.stats))
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&record)?),
+    }
+    Ok(())
+}
+
+/// Single json line printed by `--quiet-machine`
+#[derive(Serialize)]
+struct QuietLine<'a> {
+    #[serde(flatten)]
+    headline: &'a Headline,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    labels: &'a BTreeMap<String, String>,
+    environment: &'a Environment,
+}
+
+fn print_text(record: &RunRecord) {
+    println!(
+        "database: {}, workload: {}",
+        record.database, record.workload.name
+    );
+    if !record.labels.is_empty() {
+        println!("labels: {}", history::format_labels(&record.labels));
+    }
+    println!("==============================");
+    println!("{}", record.stats);
+}
+
+fn report(path: &Path) -> Result<()> {
+    for (i, record) in history::read(path)?.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_text(record);
+    }
+    Ok(())
+}
+
+fn list() -> Result<()> {
+    println!("databases:");
+    for db in DatabaseType::value_variants() {
+        print_variant(db.to_possible_value());
+    }
+    for name in registered_databases() {
+        println!("  {name:<12}registered by this build");
+    }
+    println!("workloads:");
+    for &wl in WorkloadType::value_variants() {
+        print_variant(wl.to_possible_value());
+        if let Ok(spec) = preset::get(wl) {
+            println!(
+                "  {:<12}records: {} | ops/thread: {} | mix: {:.0}% read {:.0}% write {:.0}% insert {:.0}% read-modify-write {:.0}% delete {:.0}% scan | keys: {:?} | key: {} B | value: {}-{} B | threads: {}",
+                "",
+                spec.record_count.separate_with_underscores(),
+                spec.operation_count.separate_with_underscores(),
+                spec.read_percent * 100.0,
+                spec.write_percent * 100.0,
+                spec.insert_percent * 100.0,
+                spec.read_modify_write_percent * 100.0,
+                spec.delete_percent * 100.0,
+                spec.scan_percent * 100.0,
+                spec.request_distribution,
+                spec.key_size,
+                spec.value_size_min,
+                spec.value_size_max,
+                spec.thread_count
+            );
+        }
+    }
+    Ok(())
+}
+
+fn print_variant(value: Option<PossibleValue>) {
+    let value = value.expect("no skipped variants");
+    let help = value.get_help().map(|h| h.to_string()).unwrap_or_default();
+    println!("  {:<12}{}", value.get_name(), help);
+}
+
+fn matrix(args: MatrixArgs) -> Result<()> {
+    let props = Properties::parse(&args.properties)?;
+    let workloads = if args.workloads.is_empty() {
+        // custom and replay need a file to read from
+        WorkloadType::value_variants()
+            .iter()
+            .copied()
+            .filter(|wl| !matches!(wl, WorkloadType::Custom | WorkloadType::Replay))
+            .collect()
+    } else {
+        args.workloads
+    };
+    let databases = if args.databases.is_empty() {
+        DatabaseType::value_variants()
+            .iter()
+            .copied()
+            .filter(|&db| is_enabled(db) && !is_networked(db))
+            .map(Backend::BuiltIn)
+            .chain(registered_databases().into_iter().map(Backend::Registered))
+            .collect()
+    } else {
+        args.databases
+    };
+    let mut opts = DbOptions {
+        properties: props.db(),
+        ..DbOptions::default()
+    };
+    if let Some(endpoint) = args.endpoint {
+        if opts.properties.contains_key("endpoint") {
+            bail!("--endpoint cannot be combined with -p db.endpoint");
+        }
+        opts.properties.insert("endpoint".to_string(), endpoint);
+    }
+
+    let opt = |v: Option<f64>| v.map_or("-".to_string(), workload::format_micros);
+    println!(
+        "{:<12} {:<12} {:>6} {:>14} {:>14} {:>10} {:>10} {:>10} {:>10} {:>10}",
+        "database",
+        "workload",
+        "depth",
+        "load ops/s",
+        "run ops/s",
+        "r p50 µs",
+        "r p99 µs",
+        "w p50 µs",
+        "w p99 µs",
+        "run ops/J"
+    );
+    for &wl_type in &workloads {
+        let wl = preset::get(wl_type)?
+            .scaled(args.scale)
+            .with_overrides(&props)?;
+        let depths = if args.pipeline_depths.is_empty() {
+            vec![wl.pipeline_depth]
+        } else {
+            args.pipeline_depths.clone()
+        };
+        for backend in &databases {
+            for &depth in &depths {
+                let wl = WorkloadSpec {
+                    pipeline_depth: depth,
+                    ..wl.clone()
+                };
+                let h = bench::run_headline(backend.open(&opts)?, &wl)?;
+                println!(
+                    "{:<12} {:<12} {:>6} {:>14.0} {:>14.0} {:>10} {:>10} {:>10} {:>10} {:>10}",
+                    backend.name(),
+                    wl.get_name(),
+                    depth,
+                    h.load_ops_per_sec,
+                    h.run_ops_per_sec,
+                    opt(h.read_p50_micro_sec),
+                    opt(h.read_p99_micro_sec),
+                    opt(h.write_p50_micro_sec),
+                    opt(h.write_p99_micro_sec),
+                    h.run_ops_per_joule
+                        .map_or("-".to_string(), |v| format!("{v:.0}")),
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn query(args: QueryArgs) -> Result<()> {
+    let store = Store::open(&args.store)?;
+    let filter = Filter {
+        database: args.database,
+        workload: args.workload,
+        since: args.since,
+        until: args.until,
+        labels: args.labels,
+    };
+    let opt = |v: Option<f64>| v.map_or("-".to_string(), workload::format_micros);
+    if args.aggregate {
+        println!(
+            "{:<12} {:<12} {:>5} {:>14} {:>14} {:>14} {:>12} {:>12}",
+            "database",
+            "workload",
+            "runs",
+            "min ops/s",
+            "avg ops/s",
+            "max ops/s",
+            "r p99 µs",
+            "w p99 µs"
+        );
+        for row in store.aggregate(&filter)? {
+            println!(
+                "{:<12} {:<12} {:>5} {:>14.0} {:>14.0} {:>14.0} {:>12} {:>12}",
+                row.database,
+                row.workload,
+                row.runs,
+                row.min_ops_per_sec,
+                row.avg_ops_per_sec,
+                row.max_ops_per_sec,
+                opt(row.avg_read_p99_micro_sec),
+                opt(row.avg_write_p99_micro_sec),
+            );
+        }
+    } else {
+        println!(
+            "{:>5} {:<19} {:<12} {:<12} {:<12} {:>14} {:>10} {:>10}  labels",
+            "id", "date", "database", "workload", "revision", "run ops/s", "r p99 µs", "w p99 µs"
+        );
+        for row in store.query(&filter)? {
+            println!(
+                "{:>5} {:<19} {:<12} {:<12} {:<12} {:>14.0} {:>10} {:>10}  {}",
+                row.id,
+                row.date,
+                row.database,
+                row.workload,
+                row.engine_revision.as_deref().unwrap_or("-"),
+                row.run_ops_per_sec,
+                opt(row.read_p99_micro_sec),
+                opt(row.write_p99_micro_sec),
+                history::format_labels(&row.labels),
+            );
+        }
+    }
+    Ok(())
+}
+
+fn compare(args: CompareArgs) -> Result<()> {
+    let baseline = compare::load_record(&args.baseline)?;
+    let candidate = compare::load_record(&args.candidate)?;
+    for (name, record, path) in [
+        ("baseline: ", &baseline, &args.baseline),
+        ("candidate:", &candidate, &args.candidate),
+    ] {
+        let labels = history::format_labels(&record.labels);
+        println!(
+            "{name} {} {} ({}){}",
+            record.database,
+            record.workload.name,
+            path.display(),
+            if labels.is_empty() {
+                String::new()
+            } else {
+                format!(" [{labels}]")
+            }
+        );
+    }
+    println!(
+        "{:<24} {:>14} {:>14} {:>9}",
+        "metric", "baseline", "candidate", "change"
+    );
+
+    let mut regressions = 0;
+    for delta in compare::compare(&baseline.stats, &candidate.stats) {
+        let change = delta
+            .change_percent()
+            .map_or("-".to_string(), |c| format!("{c:+.1}%"));
+        let regressed = delta.is_regression(args.threshold);
+        regressions += usize::from(regressed);
+        println!(
+            "{:<24} {:>14} {:>14} {:>9}{}",
+            format!("{} ({})", delta.name, delta.unit),
+            compare::format_value(delta.unit, delta.baseline),
+            compare::format_value(delta.unit, delta.candidate),
+            change,
+            if regressed { "  << REGRESSION" } else { "" }
+        );
+    }
+    if let Some(path) = &args.summary_file {
+        let verdict = Verdict {
+            baseline: &baseline,
+            threshold_percent: args.threshold,
+        };
+        write_summary(path, &candidate, Some(&verdict))?;
+    }
+    for (name, record) in [("baseline", &baseline), ("candidate", &candidate)] {
+        for warning in &record.stats.warnings {
+            println!("warning: {name}: {warning}");
+        }
+    }
+    if args.fail_on_regression && regressions > 0 {
+        bail!(
+            "{regressions} metric(s) regressed by more than {}%",
+            args.threshold
+        );
+    }
+    Ok(())
+}
+
+fn fill(args: FillArgs) -> Result<()> {
+    if !args.database.is_file_backed() {
+        bail!("fill needs a file-backed database");
+    }
+    let props = Properties::parse(&args.properties)?;
+    let opts = DbOptions {
+        data_dir: args.data_dir,
+        data_dir_kind: args.data_dir_kind,
+        keep_data: args.keep_data,
+        properties: props.db(),
+    };
+    let database = args.database.open(&opts)?;
+    note_kept_data(database.as_ref(), &opts);
+    let spec = WorkloadSpec::default().with_overrides(&props)?;
+    let limits = FillLimits {
+        data_dir_bytes: args.target_size,
+        disk_used_percent: args.disk_full,
+        step_bytes: args.step,
+    };
+
+    if !args.json {
+        println!(
+            "{:>14} {:>12} {:>8} {:>12} {:>10} {:>10}",
+            "records", "data dir", "disk %", "insert/s", "p50 µs", "p99 µs"
+        );
+    }
+    let steps = fill::fill(database.as_ref(), &spec, &limits, |step| {
+        if !args.json {
+            println!(
+                "{:>14} {:>11}M {:>8.1} {:>12.0} {:>10} {:>10}",
+                step.records.separate_with_underscores(),
+                step.data_dir_bytes >> 20,
+                step.disk_used_percent,
+                step.ops_per_sec,
+                workload::format_micros(step.p50_micro_sec),
+                workload::format_micros(step.p99_micro_sec)
+            );
+        }
+    })?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&steps)?);
+    }
+    Ok(())
+}
+
+/// Tells where the data of a database opened with `--keep-data` stays
+fn note_kept_data(database: &dyn Database, opts: &DbOptions) {
+    if let Some(dir) = database.data_dir().filter(|_| opts.keep_data) {
+        eprintln!("keeping data in {}", dir.display());
+    }
+}
+
+fn write_summary(path: &Path, record: &RunRecord, verdict: Option<&Verdict>) -> Result<()> {
+    std::fs::write(path, summary::markdown(record, verdict))
+        .with_context(|| format!("failed to write summary {}", path.display()))
+}
+
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(f) if (0.0..=1.0).contains(&f) => Ok(f),
+        _ => Err(format!("expected a number between 0 and 1, got `{s}`")),
+    }
+}
+
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    match s
+        .parse::<f64>()
+        .ok()
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+    {
+        Some(d) if !d.is_zero() => Ok(d),
+        _ => Err(format!("expected a positive number of seconds, got `{s}`")),
+    }
+}
@@ -0,0 +1,137 @@
+use crate::history::{self, RunRecord};
+use crate::workload::{self, WorkloadStats};
+use anyhow::{Context, Result, bail};
+use hdrhistogram::Histogram;
+use std::path::Path;
+use std::time::Duration;
+use thousands::Separable;
+
+/// Whether a larger value of a metric is an improvement or a regression
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Better {
+    Higher,
+    Lower,
+}
+
+/// One metric of a baseline run and a candidate run
+#[derive(Debug)]
+pub struct MetricDelta {
+    pub name: &'static str,
+    pub unit: &'static str,
+    pub better: Better,
+    pub baseline: Option<f64>,
+    pub candidate: Option<f64>,
+}
+
+impl MetricDelta {
+    /// Relative change from baseline to candidate in percent
+    pub fn change_percent(&self) -> Option<f64> {
+        match (self.baseline, self.candidate) {
+            (Some(a), Some(b)) if a != 0.0 => Some((b - a) / a * 100.0),
+            _ => None,
+        }
+    }
+
+    /// Whether the candidate is worse than the baseline by more than `threshold_percent`
+    pub fn is_regression(&self, threshold_percent: f64) -> bool {
+        match (self.change_percent(), self.better) {
+            (Some(c), Better::Higher) => c < -threshold_percent,
+            (Some(c), Better::Lower) => c > threshold_percent,
+            (None, _) => false,
+        }
+    }
+}
+
+/// Loads a run from a result file, either a single json record or a history file, in which
+/// case its last record is used
+pub fn load_record(path: &Path) -> Result<RunRecord> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read result file {}", path.display()))?;
+    if let Ok(record) = serde_json::from_str::<RunRecord>(&content) {
+        return record.upgrade();
+    }
+    match history::read(path)?.pop() {
+        Some(record) => Ok(record),
+        None => bail!("{} contains no runs", path.display()),
+    }
+}
+
+/// A metric's value for display, latencies with the decimals `workload::format_micros` gives
+/// them
+pub fn format_value(unit: &str, v: Option<f64>) -> String {
+    match v {
+        None => "-".to_string(),
+        Some(v) if unit == "µs" => workload::format_micros(v),
+        Some(v) => (v.round() as u64).separate_with_underscores(),
+    }
+}
+
+/// Every compared metric of `baseline` and `candidate`
+pub fn compare(baseline: &WorkloadStats, candidate: &WorkloadStats) -> Vec<MetricDelta> {
+    let a = metrics(baseline);
+    let b = metrics(candidate);
+    a.into_iter()
+        .zip(b)
+        .map(
+            |((name, unit, better, baseline), (_, _, _, candidate))| MetricDelta {
+                name,
+                unit,
+                better,
+                baseline,
+                candidate,
+            },
+        )
+        .collect()
+}
+
+/// (name, unit, direction of improvement, value)
+pub type Metric = (&'static str, &'static str, Better, Option<f64>);
+
+/// The metrics `compare` looks at, for a single run
+pub fn metrics(stats: &WorkloadStats) -> Vec<Metric> {
+    let per_sec =
+        |ops: u64, d: Duration| (ops > 0 && !d.is_zero()).then(|| ops as f64 / d.as_secs_f64());
+    let quantile =
+        |h: &Histogram<u64>, q: f64| (!h.is_empty()).then(|| workload::quantile_micros(h, q));
+    let read = &stats.run_read_hist_nano_sec;
+    let write = &stats.run_write_hist_nano_sec;
+    vec![
+        (
+            "load throughput",
+            "ops/s",
+            Better::Higher,
+            per_sec(stats.load_ops, stats.load_time),
+        ),
+        (
+            "run throughput",
+            "ops/s",
+            Better::Higher,
+            per_sec(stats.run_ops(), stats.run_wall_time),
+        ),
+        (
+            "read throughput",
+            "ops/s",
+            Better::Higher,
+            per_sec(stats.run_read_ops, stats.run_wall_time),
+        ),
+        ("read p50", "µs", Better::Lower, quantile(read, 0.50)),
+        ("read p99", "µs", Better::Lower, quantile(read, 0.99)),
+        (
+            "write throughput",
+            "ops/s",
+            Better::Higher,
+            per_sec(stats.run_write_ops, stats.run_wall_time),
+        ),
+        ("write p50", "µs", Better::Lower, quantile(write, 0.50)),
+        ("write p99", "µs", Better::Lower, quantile(write, 0.99)),
+        (
+            "run efficiency",
+            "ops/J",
+            Better::Higher,
+            stats
+                .run_energy_joules
+                .filter(|j| *j > 0.0)
+                .map(|j| stats.run_ops() as f64 / j),
+        ),
+    ]
+}
@@ -0,0 +1,109 @@
+use anyhow::{Context, Result, bail};
+use std::net::{SocketAddr, TcpStream};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+const READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How to run a networked database in a container
+#[derive(Clone, Debug)]
+pub struct ContainerSpec {
+    /// Image (with tag) used when none is given on the command line
+    pub default_image: &'static str,
+    /// Port the server listens on inside the container
+    pub port: u16,
+    pub env: &'static [(&'static str, &'static str)],
+    /// Extra arguments passed to the image's entrypoint
+    pub args: &'static [&'static str],
+}
+
+/// A running docker container, force-removed on drop
+pub struct Container {
+    id: String,
+    addr: SocketAddr,
+}
+
+impl Container {
+    /// Starts `image` (or the spec's default) with the server port published on a random
+    /// localhost port, and waits until that port accepts connections
+    pub fn start(spec: &ContainerSpec, image: Option<&str>) -> Result<Self> {
+        let image = image.unwrap_or(spec.default_image);
+        let mut cmd = Command::new("docker");
+        cmd.args(["run", "--detach", "--rm"])
+            .arg("--publish")
+            .arg(format!("127.0.0.1::{}", spec.port));
+        for (k, v) in spec.env {
+            cmd.arg("--env").arg(format!("{k}={v}"));
+        }
+        cmd.arg(image).args(spec.args);
+        let id = docker(&mut cmd).with_context(|| format!("failed to start container {image}"))?;
+
+        // constructed before waiting so the container is removed if it never gets ready
+        let mut container = Container {
+            id,
+            addr: SocketAddr::from(([127, 0, 0, 1], 0)),
+        };
+        let mapping =
+            docker(Command::new("docker").args(["port", &container.id, &spec.port.to_string()]))?;
+        // e.g. `127.0.0.1:49153`, one line per published address
+        container.addr = mapping
+            .lines()
+            .find_map(|l| l.trim().parse().ok())
+            .with_context(|| format!("unexpected `docker port` output `{mapping}`"))?;
+        container.wait_ready()?;
+        Ok(container)
+    }
+
+    /// Address of the published server port on localhost
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The container id as `docker ps` shows it
+    pub fn id_short(&self) -> &str {
+        &self.id[..self.id.len().min(12)]
+    }
+
+    fn wait_ready(&self) -> Result<()> {
+        let start = Instant::now();
+        while start.elapsed() < READY_TIMEOUT {
+            // docker-proxy accepts connections before the server is up, so require a server
+            // that keeps the connection open instead of resetting it
+            if let Ok(stream) = TcpStream::connect_timeout(&self.addr, Duration::from_secs(1)) {
+                stream.set_read_timeout(Some(Duration::from_millis(200)))?;
+                let mut buf = [0u8; 1];
+                match std::io::Read::read(&mut &stream, &mut buf) {
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(()),
+                    Err(e) if e.kind() == std::io::ErrorKind::TimedOut => return Ok(()),
+                    Ok(n) if n > 0 => return Ok(()),
+                    _ => {}
+                }
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        }
+        bail!(
+            "container {} did not accept connections on {} within {READY_TIMEOUT:?}",
+            self.id_short(),
+            self.addr
+        )
+    }
+}
+
+impl Drop for Container {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["rm", "--force", &self.id])
+            .output();
+    }
+}
+
+/// Runs a docker command, returning its trimmed stdout
+fn docker(cmd: &mut Command) -> Result<String> {
+    let out = cmd
+        .output()
+        .context("failed to run docker, is it installed and on PATH?")?;
+    if !out.status.success() {
+        bail!("{}", String::from_utf8_lossy(&out.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
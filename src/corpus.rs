@@ -0,0 +1,224 @@
+use crate::generator::{self, DeleteCounter, InsertCounter, KVSizeGen, ScanLengths, ValuePool};
+use crate::workload::spec::WorkloadSpec;
+use anyhow::Result;
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+
+/// Every key of the working set, a pool of value bytes and each run thread's exact sequence
+/// of operations, generated before the run phase so the measured loop only indexes into memory
+pub struct Corpus {
+    key_size: usize,
+    keys: Vec<u8>,
+    values: ValuePool,
+    schedules: Vec<Vec<ScheduledOp>>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ScheduledOp {
+    pub kind: OpKind,
+    key: u64,
+    value_offset: u32,
+    /// Length of the value, or the records to read for a scan
+    value_len: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OpKind {
+    Read,
+    /// An overwrite of a loaded record
+    Write,
+    /// A write of a record that was not loaded, under a new key following the loaded ones
+    Insert,
+    /// A read and a write of the same key, see `Database::read_modify_write`
+    ReadModifyWrite,
+    Delete,
+    /// Records in key order from a chosen key on, see `Database::scan`
+    Scan,
+}
+
+impl OpKind {
+    /// Number of kinds, for tallies indexed by kind
+    pub const COUNT: usize = 6;
+
+    /// Whether the operation writes a value
+    pub fn has_value(self) -> bool {
+        matches!(
+            self,
+            OpKind::Write | OpKind::Insert | OpKind::ReadModifyWrite
+        )
+    }
+}
+
+/// Picks the kind of each run phase operation, in proportion to the workload's shares of
+/// each kind relative to their sum
+#[derive(Clone, Copy, Debug)]
+pub struct OpMix {
+    /// Upper bound of each kind's slice of `0..1`, in `OpMix::KINDS` order
+    bounds: [f64; OpKind::COUNT],
+}
+
+impl OpMix {
+    /// Order the kinds take their slices of `0..1` in
+    const KINDS: [OpKind; OpKind::COUNT] = [
+        OpKind::Read,
+        OpKind::Write,
+        OpKind::Insert,
+        OpKind::ReadModifyWrite,
+        OpKind::Delete,
+        OpKind::Scan,
+    ];
+
+    /// `shares` in `OpMix::KINDS` order, not negative and not all 0
+    pub fn new(shares: [f64; OpKind::COUNT]) -> Self {
+        let sum: f64 = shares.iter().sum();
+        let mut bounds = [0.0; OpKind::COUNT];
+        let mut acc = 0.0;
+        for (bound, share) in bounds.iter_mut().zip(shares) {
+            acc += share / sum;
+            *bound = acc;
+        }
+        // rounding must not leave a gap below 1 after the last kind with a share
+        if let Some(last) = shares.iter().rposition(|&s| s > 0.0) {
+            bounds[last..].fill(1.0);
+        }
+        OpMix { bounds }
+    }
+
+    /// The kind of an operation for `x` drawn uniformly from `0..1`
+    #[inline]
+    pub fn choose(&self, x: f64) -> OpKind {
+        let i = self.bounds.iter().position(|&b| x < b);
+        Self::KINDS[i.unwrap_or(OpKind::COUNT - 1)]
+    }
+}
+
+/// One run phase operation with its key and the value it writes, empty for reads, deletes
+/// and scans
+#[derive(Clone, Copy, Debug)]
+pub struct Op<'a> {
+    pub kind: OpKind,
+    pub key: &'a [u8],
+    pub value: &'a [u8],
+    /// Records a scan reads, 0 for the other kinds
+    pub scan_length: usize,
+}
+
+impl Corpus {
+    /// Generates the corpus for `spec`, identical for the same spec and `seed`
+    pub fn generate(spec: &WorkloadSpec, seed: u64) -> Result<Self> {
+        let v_r = spec.value_size_range();
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let values = ValuePool::new(*v_r.end() as usize, &mut rng);
+
+        // inserted records follow the loaded ones, numbered in the order they are generated
+        let inserts = Arc::new(InsertCounter::new(spec.record_count));
+        let oldest = DeleteCounter::default();
+        let chooser = spec.key_chooser(&inserts)?;
+        let scan_lengths =
+            ScanLengths::new(spec.scan_length_range(), spec.scan_length_distribution)?;
+        let format = spec.key_format();
+        let mix = spec.op_mix();
+        let mut schedules = Vec::with_capacity(spec.thread_count as usize);
+        for thread in 0..spec.thread_count as u64 {
+            let mut rng = SmallRng::seed_from_u64(generator::thread_seed(seed, thread));
+            let mut value_size_gen = KVSizeGen::new(v_r.end() - v_r.start() + 1, rng.random())?;
+            let schedule = (0..spec.operation_count)
+                .map(|_| {
+                    let kind = mix.choose(rng.random());
+                    let value_len = match kind {
+                        OpKind::Scan => scan_lengths.next(&mut rng),
+                        _ => value_size_gen.get_size() + v_r.start(),
+                    };
+                    let key = if kind == OpKind::Scan {
+                        format.scan_start(chooser.next(&mut rng), value_len)
+                    } else if kind == OpKind::Insert {
+                        inserts.next()
+                    } else if kind == OpKind::Delete && spec.delete_oldest {
+                        oldest.next()
+                    } else {
+                        chooser.next(&mut rng)
+                    };
+                    ScheduledOp {
+                        kind,
+                        key,
+                        value_offset: values.offset(&mut rng) as u32,
+                        value_len: value_len as u32,
+                    }
+                })
+                .collect();
+            schedules.push(schedule);
+        }
+
+        let key_size = spec.key_size as usize;
+        // deletes of the oldest records may run past the newest insert, their keys miss
+        let records = inserts.count().max(oldest.count());
+        let mut keys = vec![0u8; records as usize * key_size];
+        for (i, key) in keys.chunks_exact_mut(key_size.max(1)).enumerate() {
+            generator::record_key(i as u64, format, key);
+        }
+        Ok(Corpus {
+            key_size,
+            keys,
+            values,
+            schedules,
+        })
+    }
+
+    /// Operations of run thread `thread`
+    pub fn schedule(&self, thread: usize) -> &[ScheduledOp] {
+        &self.schedules[thread]
+    }
+
+    /// Operations of run thread `thread` with their keys and values
+    pub fn ops(&self, thread: usize) -> impl Iterator<Item = Op<'_>> {
+        self.schedule(thread).iter().map(|op| Op {
+            kind: op.kind,
+            key: self.key(op),
+            value: if op.kind.has_value() {
+                self.value(op)
+            } else {
+                &[]
+            },
+            scan_length: match op.kind {
+                OpKind::Scan => op.value_len as usize,
+                _ => 0,
+            },
+        })
+    }
+
+    pub fn key(&self, op: &ScheduledOp) -> &[u8] {
+        let start = op.key as usize * self.key_size;
+        &self.keys[start..start + self.key_size]
+    }
+
+    pub fn value(&self, op: &ScheduledOp) -> &[u8] {
+        self.values
+            .get(op.value_offset as usize, op.value_len as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_deletes_past_the_inserts_have_keys() {
+        let spec = WorkloadSpec {
+            record_count: 10,
+            operation_count: 100,
+            thread_count: 2,
+            read_percent: 0.0,
+            write_percent: 0.0,
+            delete_percent: 1.0,
+            delete_oldest: true,
+            pregenerate: true,
+            key_size: 16,
+            ..Default::default()
+        };
+        let corpus = Corpus::generate(&spec, 1).unwrap();
+        for thread in 0..spec.thread_count as usize {
+            assert_eq!(corpus.ops(thread).count(), 100);
+        }
+    }
+}
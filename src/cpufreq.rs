@@ -0,0 +1,102 @@
+use crate::sampler::IntervalSample;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const CPU_ROOT: &str = "/sys/devices/system/cpu";
+/// An interval below this fraction of the run's fastest interval counts as throttled
+const THROTTLED_FREQUENCY_RATIO: f64 = 0.85;
+
+/// CPU frequency and thermal state at the end of a sampling interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CpuSample {
+    /// Current frequency averaged over all cpus
+    pub avg_freq_mhz: Option<f64>,
+    /// Current frequency averaged over the fastest cpus, one per run thread. Idle cpus clock
+    /// down, so these are the ones the run kept busy.
+    #[serde(default)]
+    pub busy_freq_mhz: Option<f64>,
+    /// Highest frequency the cpus are rated for
+    pub max_freq_mhz: Option<f64>,
+    /// Thermal throttling events reported by the cpus since boot (Intel only)
+    pub throttle_events: Option<u64>,
+}
+
+impl CpuSample {
+    /// Reads cpufreq and thermal_throttle from sysfs, `None` where neither is exposed.
+    /// `busy_cpus` is the number of threads the run keeps busy.
+    pub fn read(busy_cpus: usize) -> Option<Self> {
+        let cpus: Vec<_> = std::fs::read_dir(CPU_ROOT)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let name = e.file_name();
+                let name = name.to_string_lossy();
+                name.strip_prefix("cpu")
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .map(|e| e.path())
+            .collect();
+        let khz = |cpu: &Path, file: &str| read_u64(&cpu.join("cpufreq").join(file));
+        let avg = |file: &str| {
+            let values: Vec<u64> = cpus.iter().filter_map(|c| khz(c, file)).collect();
+            (!values.is_empty())
+                .then(|| values.iter().sum::<u64>() as f64 / values.len() as f64 / 1000.0)
+        };
+        let busy = {
+            let mut values: Vec<u64> = cpus
+                .iter()
+                .filter_map(|c| khz(c, "scaling_cur_freq"))
+                .collect();
+            values.sort_unstable_by(|a, b| b.cmp(a));
+            values.truncate(busy_cpus.max(1));
+            (!values.is_empty())
+                .then(|| values.iter().sum::<u64>() as f64 / values.len() as f64 / 1000.0)
+        };
+        let throttle: Vec<u64> = cpus
+            .iter()
+            .filter_map(|c| read_u64(&c.join("thermal_throttle/core_throttle_count")))
+            .collect();
+        let sample = CpuSample {
+            avg_freq_mhz: avg("scaling_cur_freq"),
+            busy_freq_mhz: busy,
+            max_freq_mhz: avg("cpuinfo_max_freq"),
+            throttle_events: (!throttle.is_empty()).then(|| throttle.iter().sum()),
+        };
+        (sample.avg_freq_mhz.is_some() || sample.throttle_events.is_some()).then_some(sample)
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Describes significant throttling in a run's time series: thermal throttling events, or
+/// an interval whose busy cpus' frequency fell well below the run's fastest interval. The
+/// last sample is taken as the run stops, when the cpus are already clocking down, so its
+/// frequency is left out.
+pub fn throttling_warning(series: &[IntervalSample]) -> Option<String> {
+    let cpu: Vec<&CpuSample> = series.iter().filter_map(|s| s.cpu.as_ref()).collect();
+    let events = |s: &&CpuSample| s.throttle_events;
+    if let (Some(first), Some(last)) = (
+        cpu.iter().find_map(events),
+        cpu.iter().rev().find_map(events),
+    ) && last > first
+    {
+        return Some(format!(
+            "cpu reported {} thermal throttling events during the run, results may not be comparable",
+            last - first
+        ));
+    }
+    let full_intervals = series.split_last().map_or(&[][..], |(_, full)| full);
+    let freqs: Vec<f64> = full_intervals
+        .iter()
+        .filter_map(|s| s.cpu.as_ref()?.busy_freq_mhz)
+        .collect();
+    let peak = freqs.iter().copied().fold(0.0, f64::max);
+    let low = freqs.iter().copied().fold(f64::INFINITY, f64::min);
+    (peak > 0.0 && low < peak * THROTTLED_FREQUENCY_RATIO).then(|| {
+        format!(
+            "cpu frequency dropped to {low:.0} MHz from {peak:.0} MHz during the run, results may not be comparable"
+        )
+    })
+}
@@ -1,24 +1,758 @@
+#[cfg(feature = "aerospike")]
+mod aerospike;
+#[cfg(feature = "canopydb")]
+mod canopydb;
+#[cfg(feature = "couchbase")]
+mod couchbase;
+mod data_dir;
+#[cfg(all(target_os = "linux", feature = "redb"))]
+mod direct_io;
+#[cfg(feature = "dynamodb")]
+mod dynamodb;
+mod endpoint;
+#[cfg(feature = "etcd")]
+mod etcd;
+mod external;
+mod file_per_key;
+#[cfg(feature = "fjall")]
+mod fjall;
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "jammdb")]
+mod jammdb;
+#[cfg(feature = "leveldb")]
+mod leveldb;
+#[cfg(feature = "lmdb")]
+mod lmdb;
+#[cfg(feature = "marble")]
+mod marble;
 mod mem_btree;
+#[cfg(feature = "mem-dashmap")]
+mod mem_dashmap;
+mod mem_hashmap;
+#[cfg(feature = "mem-skipmap")]
+mod mem_skipmap;
+#[cfg(feature = "memcached")]
+mod memcached;
+mod null;
+#[cfg(feature = "object-store")]
+mod object_store;
+#[cfg(feature = "persy")]
+mod persy;
+#[cfg(feature = "plugin")]
+mod plugin;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "redb")]
 mod redb;
+#[cfg(feature = "redis")]
+mod redis;
+#[cfg(feature = "redis")]
+mod redis_cluster;
+mod registry;
+#[cfg(feature = "rocksdb")]
+mod rocksdb;
+#[cfg(feature = "rocksdb")]
+mod rocksdb_txn;
+#[cfg(feature = "sanakirja")]
+mod sanakirja;
+#[cfg(feature = "scylla")]
+mod scylla;
+#[cfg(feature = "sled")]
 mod sled;
+#[cfg(feature = "surrealkv")]
+mod surrealkv;
+#[cfg(feature = "tikv")]
+mod tikv;
 
 use crate::DatabaseType;
+use crate::container::ContainerSpec;
+#[cfg(feature = "aerospike")]
+use crate::database::aerospike::Aerospike;
+#[cfg(feature = "canopydb")]
+use crate::database::canopydb::CanopyDb;
+#[cfg(feature = "couchbase")]
+use crate::database::couchbase::Couchbase;
+#[cfg(feature = "dynamodb")]
+use crate::database::dynamodb::DynamoDb;
+#[cfg(feature = "etcd")]
+use crate::database::etcd::Etcd;
+use crate::database::external::External;
+use crate::database::file_per_key::FilePerKey;
+#[cfg(feature = "fjall")]
+use crate::database::fjall::Fjall;
+#[cfg(feature = "grpc")]
+use crate::database::grpc::Grpc;
+#[cfg(feature = "http")]
+use crate::database::http::Http;
+#[cfg(feature = "jammdb")]
+use crate::database::jammdb::JammDb;
+#[cfg(feature = "leveldb")]
+use crate::database::leveldb::LevelDb;
+#[cfg(feature = "lmdb")]
+use crate::database::lmdb::Lmdb;
+#[cfg(feature = "marble")]
+use crate::database::marble::MarbleDb;
 use crate::database::mem_btree::MemBTree;
+#[cfg(feature = "mem-dashmap")]
+use crate::database::mem_dashmap::MemDashMap;
+use crate::database::mem_hashmap::MemHashMap;
+#[cfg(feature = "mem-skipmap")]
+use crate::database::mem_skipmap::MemSkipMap;
+#[cfg(feature = "memcached")]
+use crate::database::memcached::Memcached;
+use crate::database::null::Null;
+#[cfg(feature = "object-store")]
+use crate::database::object_store::ObjectStoreDb;
+#[cfg(feature = "persy")]
+use crate::database::persy::PersyDb;
+#[cfg(feature = "plugin")]
+use crate::database::plugin::Plugin;
+#[cfg(feature = "postgres")]
+use crate::database::postgres::Postgres;
+#[cfg(feature = "redb")]
 use crate::database::redb::Redb;
+#[cfg(feature = "redis")]
+use crate::database::redis::Redis;
+#[cfg(feature = "redis")]
+use crate::database::redis_cluster::RedisCluster;
+#[cfg(feature = "rocksdb")]
+use crate::database::rocksdb::RocksDb;
+#[cfg(feature = "rocksdb")]
+use crate::database::rocksdb_txn::RocksDbTxn;
+#[cfg(feature = "sanakirja")]
+use crate::database::sanakirja::Sanakirja;
+#[cfg(feature = "scylla")]
+use crate::database::scylla::Scylla;
+#[cfg(feature = "sled")]
 use crate::database::sled::Sled;
-use anyhow::Result;
+#[cfg(feature = "surrealkv")]
+use crate::database::surrealkv::SurrealKv;
+#[cfg(feature = "tikv")]
+use crate::database::tikv::Tikv;
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::path::Path;
 use std::sync::Arc;
 
-pub trait Database: Send + Sync {
+pub use data_dir::{DataDir, DataDirKind, DbOptions};
+pub use endpoint::Endpoint;
+pub use registry::{Backend, BackendParser, register_database, registered_databases};
+
+pub trait Database: Any + Send + Sync {
     fn init(&self) -> Result<()>;
     fn get(&self, key: &[u8]) -> Result<()>;
     fn set(&self, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Directory holding the database's files, if it has any
+    fn data_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Reserves space for about `bytes` of data before loading, so file growth does not show
+    /// up in the measurement
+    fn preallocate(&self, _bytes: u64) -> Result<()> {
+        bail!("this database does not support preallocation")
+    }
+
+    /// Background work (flushes, compactions) as reported by the engine, polled once per
+    /// reporting interval. `None` for engines that report nothing.
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        None
+    }
+
+    /// Reads so far that found no value, counted by backends that may lose data (caches)
+    /// instead of failing the read. `None` for backends that do not count them.
+    fn read_misses(&self) -> Option<u64> {
+        None
+    }
+
+    /// Removes `key`, succeeding whether or not it was there
+    fn delete(&self, _key: &[u8]) -> Result<()> {
+        bail!("this database does not support deletes")
+    }
+
+    /// Reads up to `count` records in key order, starting at `start` or the first key after
+    /// it, and returns how many it read
+    fn scan(&self, _start: &[u8], _count: usize) -> Result<usize> {
+        bail!("this database does not support scans")
+    }
+
+    /// Reads `key` and writes `value` in its place, in one transaction on backends that have
+    /// them so no other write to the key lands in between. The default is a `get` followed
+    /// by a `set`.
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.get(key)?;
+        self.set(key, value)
+    }
+
+    /// Whether `create_tables` gives the tables their own namespaces (tables, trees, column
+    /// families). Other backends keep the keys of every table in their one keyspace.
+    fn supports_tables(&self) -> bool {
+        false
+    }
+
+    /// Creates tables `0..count` for `get_in` and `set_in`, called after `init` when the
+    /// workload spreads its keys over more than one table. Tables a kept data dir already has
+    /// are reopened, not recreated.
+    fn create_tables(&self, _count: u32) -> Result<()> {
+        Ok(())
+    }
+
+    /// `get` from one of the tables made by `create_tables`
+    fn get_in(&self, _table: u32, key: &[u8]) -> Result<()> {
+        self.get(key)
+    }
+
+    /// `set` into one of the tables made by `create_tables`
+    fn set_in(&self, _table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+        self.set(key, value)
+    }
+
+    /// `delete` from one of the tables made by `create_tables`
+    fn delete_in(&self, _table: u32, key: &[u8]) -> Result<()> {
+        self.delete(key)
+    }
+
+    /// `scan` of one of the tables made by `create_tables`
+    fn scan_in(&self, _table: u32, start: &[u8], count: usize) -> Result<usize> {
+        self.scan(start, count)
+    }
+
+    /// `read_modify_write` in one of the tables made by `create_tables`
+    fn read_modify_write_in(&self, _table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+        self.read_modify_write(key, value)
+    }
+
+    /// Executes `ops` as one pipeline. Network backends override this to send every request
+    /// before waiting for the replies, the default runs them one after another.
+    fn pipeline(&self, ops: &[PipelinedOp]) -> Result<()> {
+        for op in ops {
+            match *op {
+                PipelinedOp::Get(key) => self.get(key)?,
+                PipelinedOp::Set(key, value) => self.set(key, value)?,
+                PipelinedOp::Delete(key) => self.delete(key)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One request of a pipeline, see `Database::pipeline`
+#[derive(Clone, Copy, Debug)]
+pub enum PipelinedOp<'a> {
+    Get(&'a [u8]),
+    Set(&'a [u8], &'a [u8]),
+    Delete(&'a [u8]),
+}
+
+/// Background activity an engine reports about itself, `None` where it does not track a value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EngineActivity {
+    pub flushes_running: Option<u64>,
+    pub compactions_running: Option<u64>,
+    /// Bytes compaction still has to rewrite to reach the engine's target shape
+    pub pending_compaction_bytes: Option<u64>,
+    /// Whether the engine is currently slowing down or stopping writes
+    pub write_stalled: Option<bool>,
+    pub size_on_disk_bytes: Option<u64>,
+}
+
+impl EngineActivity {
+    /// Whether any flush or compaction was running, or writes were stalled
+    pub fn is_busy(&self) -> bool {
+        self.flushes_running.unwrap_or(0) > 0
+            || self.compactions_running.unwrap_or(0) > 0
+            || self.write_stalled.unwrap_or(false)
+    }
+}
+
+/// A measured loop, generic over the database so it can be monomorphized per backend
+pub trait Dispatch {
+    type Output;
+
+    fn call<D: Database + ?Sized>(self, db: &D) -> Self::Output;
+}
+
+/// Calls `f` with the concrete type behind `db` for the built-in backends, so their
+/// operations are not virtual calls. Wrapped (traced, probed, delayed) or external databases
+/// go through the trait object.
+pub fn dispatch<F: Dispatch>(db: &Arc<dyn Database>, f: F) -> F::Output {
+    let any: &dyn Any = db.as_ref();
+    if let Some(db) = any.downcast_ref::<MemBTree>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "redb")]
+    if let Some(db) = any.downcast_ref::<Redb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "sled")]
+    if let Some(db) = any.downcast_ref::<Sled>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "lmdb")]
+    if let Some(db) = any.downcast_ref::<Lmdb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "fjall")]
+    if let Some(db) = any.downcast_ref::<Fjall>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "leveldb")]
+    if let Some(db) = any.downcast_ref::<LevelDb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "persy")]
+    if let Some(db) = any.downcast_ref::<PersyDb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "jammdb")]
+    if let Some(db) = any.downcast_ref::<JammDb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "canopydb")]
+    if let Some(db) = any.downcast_ref::<CanopyDb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "sanakirja")]
+    if let Some(db) = any.downcast_ref::<Sanakirja>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "marble")]
+    if let Some(db) = any.downcast_ref::<MarbleDb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "redis")]
+    if let Some(db) = any.downcast_ref::<Redis>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "redis")]
+    if let Some(db) = any.downcast_ref::<RedisCluster>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "memcached")]
+    if let Some(db) = any.downcast_ref::<Memcached>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "tikv")]
+    if let Some(db) = any.downcast_ref::<Tikv>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "postgres")]
+    if let Some(db) = any.downcast_ref::<Postgres>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "scylla")]
+    if let Some(db) = any.downcast_ref::<Scylla>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "aerospike")]
+    if let Some(db) = any.downcast_ref::<Aerospike>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "couchbase")]
+    if let Some(db) = any.downcast_ref::<Couchbase>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "object-store")]
+    if let Some(db) = any.downcast_ref::<ObjectStoreDb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "mem-dashmap")]
+    if let Some(db) = any.downcast_ref::<MemDashMap>() {
+        return f.call(db);
+    }
+    if let Some(db) = any.downcast_ref::<MemHashMap>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "mem-skipmap")]
+    if let Some(db) = any.downcast_ref::<MemSkipMap>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "surrealkv")]
+    if let Some(db) = any.downcast_ref::<SurrealKv>() {
+        return f.call(db);
+    }
+    if let Some(db) = any.downcast_ref::<Null>() {
+        return f.call(db);
+    }
+    if let Some(db) = any.downcast_ref::<FilePerKey>() {
+        return f.call(db);
+    }
+    if let Some(db) = any.downcast_ref::<External>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "plugin")]
+    if let Some(db) = any.downcast_ref::<Plugin>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "grpc")]
+    if let Some(db) = any.downcast_ref::<Grpc>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "http")]
+    if let Some(db) = any.downcast_ref::<Http>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "rocksdb")]
+    if let Some(db) = any.downcast_ref::<RocksDb>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "rocksdb")]
+    if let Some(db) = any.downcast_ref::<RocksDbTxn>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "etcd")]
+    if let Some(db) = any.downcast_ref::<Etcd>() {
+        return f.call(db);
+    }
+    #[cfg(feature = "dynamodb")]
+    if let Some(db) = any.downcast_ref::<DynamoDb>() {
+        return f.call(db);
+    }
+    f.call(db.as_ref())
+}
+
+pub fn get_db(database: DatabaseType, opts: &DbOptions) -> Result<Arc<dyn Database>> {
+    match database {
+        DatabaseType::MemBtree => {
+            opts.check_supported("mem-btree", &[])?;
+            Ok(Arc::new(MemBTree::default()))
+        }
+        #[cfg(feature = "mem-dashmap")]
+        DatabaseType::MemDashmap => {
+            opts.check_supported("mem-dashmap", &[])?;
+            Ok(Arc::new(MemDashMap::default()))
+        }
+        DatabaseType::MemHashmap => {
+            opts.check_supported("mem-hashmap", &[])?;
+            Ok(Arc::new(MemHashMap::default()))
+        }
+        #[cfg(feature = "mem-skipmap")]
+        DatabaseType::MemSkipmap => {
+            opts.check_supported("mem-skipmap", &[])?;
+            Ok(Arc::new(MemSkipMap::default()))
+        }
+        DatabaseType::Null => {
+            opts.check_supported("null", &[])?;
+            Ok(Arc::new(Null))
+        }
+        #[cfg(feature = "redb")]
+        DatabaseType::Redb => Ok(Arc::new(Redb::new(opts)?)),
+        #[cfg(feature = "sled")]
+        DatabaseType::Sled => Ok(Arc::new(Sled::new(opts)?)),
+        #[cfg(feature = "lmdb")]
+        DatabaseType::Lmdb => Ok(Arc::new(Lmdb::new(opts)?)),
+        #[cfg(feature = "fjall")]
+        DatabaseType::Fjall => Ok(Arc::new(Fjall::new(opts)?)),
+        #[cfg(feature = "leveldb")]
+        DatabaseType::LevelDb => Ok(Arc::new(LevelDb::new(opts)?)),
+        #[cfg(feature = "persy")]
+        DatabaseType::Persy => Ok(Arc::new(PersyDb::new(opts)?)),
+        #[cfg(feature = "jammdb")]
+        DatabaseType::JammDb => Ok(Arc::new(JammDb::new(opts)?)),
+        #[cfg(feature = "canopydb")]
+        DatabaseType::CanopyDb => Ok(Arc::new(CanopyDb::new(opts)?)),
+        #[cfg(feature = "sanakirja")]
+        DatabaseType::Sanakirja => Ok(Arc::new(Sanakirja::new(opts)?)),
+        #[cfg(feature = "marble")]
+        DatabaseType::Marble => Ok(Arc::new(MarbleDb::new(opts)?)),
+        #[cfg(feature = "redis")]
+        DatabaseType::Redis => Ok(Arc::new(Redis::new(opts)?)),
+        #[cfg(feature = "redis")]
+        DatabaseType::RedisCluster => Ok(Arc::new(RedisCluster::new(opts)?)),
+        #[cfg(feature = "memcached")]
+        DatabaseType::Memcached => Ok(Arc::new(Memcached::new(opts)?)),
+        #[cfg(feature = "tikv")]
+        DatabaseType::Tikv => Ok(Arc::new(Tikv::new(opts)?)),
+        #[cfg(feature = "postgres")]
+        DatabaseType::Postgres => Ok(Arc::new(Postgres::new(opts)?)),
+        #[cfg(feature = "scylla")]
+        DatabaseType::Scylla => Ok(Arc::new(Scylla::new(opts)?)),
+        #[cfg(feature = "aerospike")]
+        DatabaseType::Aerospike => Ok(Arc::new(Aerospike::new(opts)?)),
+        #[cfg(feature = "couchbase")]
+        DatabaseType::Couchbase => Ok(Arc::new(Couchbase::new(opts)?)),
+        #[cfg(feature = "object-store")]
+        DatabaseType::ObjectStore => Ok(Arc::new(ObjectStoreDb::new(opts)?)),
+        #[cfg(feature = "surrealkv")]
+        DatabaseType::SurrealKv => Ok(Arc::new(SurrealKv::new(opts)?)),
+        DatabaseType::FilePerKey => Ok(Arc::new(FilePerKey::new(opts)?)),
+        DatabaseType::External => Ok(Arc::new(External::new(opts)?)),
+        #[cfg(feature = "plugin")]
+        DatabaseType::Plugin => Ok(Arc::new(Plugin::new(opts)?)),
+        #[cfg(feature = "grpc")]
+        DatabaseType::Grpc => Ok(Arc::new(Grpc::new(opts)?)),
+        #[cfg(feature = "http")]
+        DatabaseType::Http => Ok(Arc::new(Http::new(opts)?)),
+        #[cfg(feature = "rocksdb")]
+        DatabaseType::RocksDb => Ok(Arc::new(RocksDb::new(opts)?)),
+        #[cfg(feature = "rocksdb")]
+        DatabaseType::RocksDbTxn => Ok(Arc::new(RocksDbTxn::new(opts)?)),
+        #[cfg(feature = "etcd")]
+        DatabaseType::Etcd => Ok(Arc::new(Etcd::new(opts)?)),
+        #[cfg(feature = "dynamodb")]
+        DatabaseType::DynamoDb => Ok(Arc::new(DynamoDb::new(opts)?)),
+        #[allow(unreachable_patterns)]
+        _ => {
+            let name = database.to_possible_value().expect("no skipped variants");
+            bail!(
+                "kvbencher was built without {0} support, rebuild with `--features {0}`",
+                name.get_name()
+            )
+        }
+    }
+}
+
+/// Whether support for the database was compiled in
+pub fn is_enabled(database: DatabaseType) -> bool {
+    match database {
+        DatabaseType::MemBtree
+        | DatabaseType::MemHashmap
+        | DatabaseType::Null
+        | DatabaseType::FilePerKey
+        | DatabaseType::External => true,
+        DatabaseType::Redb => cfg!(feature = "redb"),
+        DatabaseType::Sled => cfg!(feature = "sled"),
+        DatabaseType::Lmdb => cfg!(feature = "lmdb"),
+        DatabaseType::Fjall => cfg!(feature = "fjall"),
+        DatabaseType::LevelDb => cfg!(feature = "leveldb"),
+        DatabaseType::Persy => cfg!(feature = "persy"),
+        DatabaseType::JammDb => cfg!(feature = "jammdb"),
+        DatabaseType::CanopyDb => cfg!(feature = "canopydb"),
+        DatabaseType::Sanakirja => cfg!(feature = "sanakirja"),
+        DatabaseType::Marble => cfg!(feature = "marble"),
+        DatabaseType::Redis | DatabaseType::RedisCluster => cfg!(feature = "redis"),
+        DatabaseType::Memcached => cfg!(feature = "memcached"),
+        DatabaseType::Tikv => cfg!(feature = "tikv"),
+        DatabaseType::Postgres => cfg!(feature = "postgres"),
+        DatabaseType::Scylla => cfg!(feature = "scylla"),
+        DatabaseType::Aerospike => cfg!(feature = "aerospike"),
+        DatabaseType::Couchbase => cfg!(feature = "couchbase"),
+        DatabaseType::ObjectStore => cfg!(feature = "object-store"),
+        DatabaseType::MemDashmap => cfg!(feature = "mem-dashmap"),
+        DatabaseType::MemSkipmap => cfg!(feature = "mem-skipmap"),
+        DatabaseType::SurrealKv => cfg!(feature = "surrealkv"),
+        DatabaseType::Plugin => cfg!(feature = "plugin"),
+        DatabaseType::Grpc => cfg!(feature = "grpc"),
+        DatabaseType::Http => cfg!(feature = "http"),
+        DatabaseType::RocksDb | DatabaseType::RocksDbTxn => cfg!(feature = "rocksdb"),
+        DatabaseType::Etcd => cfg!(feature = "etcd"),
+        DatabaseType::DynamoDb => cfg!(feature = "dynamodb"),
+    }
+}
+
+/// How to run the database server in a container, for networked databases
+pub fn container_spec(database: DatabaseType) -> Option<ContainerSpec> {
+    match database {
+        DatabaseType::MemBtree
+        | DatabaseType::Redb
+        | DatabaseType::Sled
+        | DatabaseType::RocksDb
+        | DatabaseType::RocksDbTxn
+        | DatabaseType::Lmdb
+        | DatabaseType::Fjall
+        | DatabaseType::LevelDb
+        | DatabaseType::Persy
+        | DatabaseType::JammDb
+        | DatabaseType::CanopyDb
+        | DatabaseType::Sanakirja
+        | DatabaseType::Marble
+        | DatabaseType::Tikv
+        | DatabaseType::MemDashmap
+        | DatabaseType::MemHashmap
+        | DatabaseType::MemSkipmap
+        | DatabaseType::SurrealKv
+        | DatabaseType::Null
+        | DatabaseType::FilePerKey
+        | DatabaseType::External
+        | DatabaseType::Plugin
+        | DatabaseType::Grpc
+        | DatabaseType::Http
+        | DatabaseType::Couchbase
+        | DatabaseType::RedisCluster
+        | DatabaseType::ObjectStore => None,
+        DatabaseType::Redis => Some(ContainerSpec {
+            default_image: "redis:7",
+            port: 6379,
+            env: &[],
+            args: &[],
+        }),
+        DatabaseType::Memcached => Some(ContainerSpec {
+            default_image: "memcached:1.6",
+            port: 11211,
+            env: &[],
+            args: &[],
+        }),
+        DatabaseType::Postgres => Some(ContainerSpec {
+            default_image: "postgres:17",
+            port: 5432,
+            env: &[("POSTGRES_HOST_AUTH_METHOD", "trust")],
+            args: &[],
+        }),
+        DatabaseType::DynamoDb => Some(ContainerSpec {
+            default_image: "amazon/dynamodb-local:2.5.4",
+            port: 8000,
+            env: &[],
+            args: &[],
+        }),
+        DatabaseType::Scylla => Some(ContainerSpec {
+            default_image: "scylladb/scylla:6.2",
+            port: 9042,
+            env: &[],
+            // one shard and a bounded heap, so it starts on a laptop
+            args: &[
+                "--smp",
+                "1",
+                "--memory",
+                "1G",
+                "--overprovisioned",
+                "1",
+                "--developer-mode",
+                "1",
+            ],
+        }),
+        DatabaseType::Aerospike => Some(ContainerSpec {
+            default_image: "aerospike/aerospike-server:7.2",
+            port: 3000,
+            env: &[],
+            args: &[],
+        }),
+        DatabaseType::Etcd => Some(ContainerSpec {
+            default_image: "quay.io/coreos/etcd:v3.5.17",
+            port: 2379,
+            env: &[],
+            // the image has no entrypoint, and etcd only listens on localhost by default
+            args: &[
+                "etcd",
+                "--listen-client-urls",
+                "http://0.0.0.0:2379",
+                "--advertise-client-urls",
+                "http://0.0.0.0:2379",
+            ],
+        }),
+    }
+}
+
+/// Whether the database is a server reached over the network, rather than running in-process
+pub fn is_networked(database: DatabaseType) -> bool {
+    match database {
+        DatabaseType::Redis
+        | DatabaseType::RedisCluster
+        | DatabaseType::Memcached
+        | DatabaseType::Tikv
+        | DatabaseType::Etcd
+        | DatabaseType::Postgres
+        | DatabaseType::DynamoDb
+        | DatabaseType::Scylla
+        | DatabaseType::Aerospike
+        | DatabaseType::Couchbase
+        | DatabaseType::ObjectStore
+        | DatabaseType::Grpc
+        | DatabaseType::Http => true,
+        DatabaseType::MemBtree
+        | DatabaseType::Redb
+        | DatabaseType::Sled
+        | DatabaseType::RocksDb
+        | DatabaseType::RocksDbTxn
+        | DatabaseType::Lmdb
+        | DatabaseType::Fjall
+        | DatabaseType::LevelDb
+        | DatabaseType::Persy
+        | DatabaseType::JammDb
+        | DatabaseType::CanopyDb
+        | DatabaseType::Sanakirja
+        | DatabaseType::Marble
+        | DatabaseType::MemDashmap
+        | DatabaseType::MemHashmap
+        | DatabaseType::MemSkipmap
+        | DatabaseType::SurrealKv
+        | DatabaseType::Null
+        | DatabaseType::FilePerKey
+        | DatabaseType::External
+        | DatabaseType::Plugin => false,
+    }
 }
 
-pub fn get_db(database: DatabaseType) -> Result<Arc<dyn Database>> {
+/// Whether the database stores its data in the data dir, and so can be reused across runs
+pub fn is_file_backed(database: DatabaseType) -> bool {
     match database {
-        DatabaseType::MemBtree => Ok(Arc::new(MemBTree::default())),
-        DatabaseType::Redb => Ok(Arc::new(Redb::new())),
-        DatabaseType::Sled => Ok(Arc::new(Sled::new())),
+        DatabaseType::MemBtree
+        | DatabaseType::Redis
+        | DatabaseType::RedisCluster
+        | DatabaseType::Memcached
+        | DatabaseType::Tikv
+        | DatabaseType::Etcd
+        | DatabaseType::Postgres
+        | DatabaseType::DynamoDb
+        | DatabaseType::Scylla
+        | DatabaseType::Aerospike
+        | DatabaseType::Couchbase
+        | DatabaseType::ObjectStore
+        | DatabaseType::MemDashmap
+        | DatabaseType::MemHashmap
+        | DatabaseType::MemSkipmap
+        | DatabaseType::Null
+        | DatabaseType::External
+        | DatabaseType::Grpc
+        | DatabaseType::Http => false,
+        DatabaseType::Redb
+        | DatabaseType::Sled
+        | DatabaseType::RocksDb
+        | DatabaseType::RocksDbTxn
+        | DatabaseType::Lmdb
+        | DatabaseType::Fjall
+        | DatabaseType::LevelDb
+        | DatabaseType::Persy
+        | DatabaseType::JammDb
+        | DatabaseType::CanopyDb
+        | DatabaseType::Sanakirja
+        | DatabaseType::Marble
+        | DatabaseType::SurrealKv
+        | DatabaseType::FilePerKey
+        | DatabaseType::Plugin => true,
+    }
+}
+
+pub fn get_db_name(db: DatabaseType) -> String {
+    match db {
+        DatabaseType::MemBtree => "MemBtree".to_string(),
+        DatabaseType::Redb => "Redb".to_string(),
+        DatabaseType::Sled => "Sled".to_string(),
+        DatabaseType::RocksDb => "RocksDb".to_string(),
+        DatabaseType::RocksDbTxn => "RocksDbTxn".to_string(),
+        DatabaseType::Lmdb => "Lmdb".to_string(),
+        DatabaseType::Fjall => "Fjall".to_string(),
+        DatabaseType::LevelDb => "LevelDb".to_string(),
+        DatabaseType::Persy => "Persy".to_string(),
+        DatabaseType::JammDb => "JammDb".to_string(),
+        DatabaseType::CanopyDb => "CanopyDb".to_string(),
+        DatabaseType::Sanakirja => "Sanakirja".to_string(),
+        DatabaseType::Marble => "Marble".to_string(),
+        DatabaseType::Redis => "Redis".to_string(),
+        DatabaseType::RedisCluster => "RedisCluster".to_string(),
+        DatabaseType::Memcached => "Memcached".to_string(),
+        DatabaseType::Tikv => "Tikv".to_string(),
+        DatabaseType::Etcd => "Etcd".to_string(),
+        DatabaseType::DynamoDb => "DynamoDb".to_string(),
+        DatabaseType::Postgres => "Postgres".to_string(),
+        DatabaseType::Scylla => "Scylla".to_string(),
+        DatabaseType::Aerospike => "Aerospike".to_string(),
+        DatabaseType::Couchbase => "Couchbase".to_string(),
+        DatabaseType::ObjectStore => "ObjectStore".to_string(),
+        DatabaseType::MemDashmap => "MemDashmap".to_string(),
+        DatabaseType::MemHashmap => "MemHashmap".to_string(),
+        DatabaseType::MemSkipmap => "MemSkipmap".to_string(),
+        DatabaseType::SurrealKv => "SurrealKv".to_string(),
+        DatabaseType::Null => "Null".to_string(),
+        DatabaseType::FilePerKey => "FilePerKey".to_string(),
+        DatabaseType::External => "External".to_string(),
+        DatabaseType::Plugin => "Plugin".to_string(),
+        DatabaseType::Grpc => "Grpc".to_string(),
+        DatabaseType::Http => "Http".to_string(),
     }
 }
@@ -0,0 +1,172 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use aerospike::{
+    AuthMode, Bin, Bins, Client, ClientPolicy, Host, Key, ReadPolicy, ResultCode, TlsPolicy, Value,
+    WritePolicy,
+};
+use anyhow::Context;
+use tokio::runtime::Runtime;
+
+const DEFAULT_HOSTS: &str = "127.0.0.1:3000";
+const DEFAULT_PORT: u16 = 3000;
+const DEFAULT_NAMESPACE: &str = "test";
+const DEFAULT_SET: &str = "kvbencher";
+const BIN: &str = "v";
+
+pub struct Aerospike {
+    client: Client,
+    namespace: String,
+    set: String,
+    read_policy: ReadPolicy,
+    write_policy: WritePolicy,
+    runtime: Runtime,
+}
+
+impl Aerospike {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("aerospike", &["hosts", "endpoint", "namespace", "set"])?;
+        let mut policy = ClientPolicy::default();
+        let mut namespace = opts.properties.get("namespace").cloned();
+        let hosts = match (opts.properties.get("hosts"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.hosts",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(hosts), None) => parse_hosts(hosts)?,
+            (None, Some(endpoint)) => {
+                if let Some(user) = &endpoint.user {
+                    policy.set_auth_mode(AuthMode::Internal(
+                        user.clone(),
+                        endpoint.password.clone().unwrap_or_default(),
+                    ));
+                }
+                if endpoint.tls {
+                    policy.tls_policy = Some(TlsPolicy::new(endpoint.rustls_config()?));
+                }
+                if let Some(database) = &endpoint.database {
+                    if namespace.is_some() {
+                        return Err(ConfigError::new(
+                            "db.namespace",
+                            "cannot be combined with a namespace in db.endpoint",
+                        )
+                        .into());
+                    }
+                    namespace = Some(database.clone());
+                }
+                // the node certificates are checked against the host names
+                endpoint
+                    .hosts
+                    .iter()
+                    .map(|h| {
+                        let port = h.port.unwrap_or(DEFAULT_PORT);
+                        match endpoint.tls {
+                            true => Host::new_tls(&h.name, &h.name, port),
+                            false => Host::new(&h.name, port),
+                        }
+                    })
+                    .collect()
+            }
+            (None, None) => parse_hosts(DEFAULT_HOSTS)?,
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let client = runtime
+            .block_on(Client::new(&policy, &hosts))
+            .with_context(|| {
+                let addrs: Vec<_> = hosts.iter().map(Host::address).collect();
+                format!("failed to connect to aerospike at {}", addrs.join(","))
+            })?;
+        Ok(Aerospike {
+            client,
+            namespace: namespace.unwrap_or_else(|| DEFAULT_NAMESPACE.to_string()),
+            set: opts
+                .properties
+                .get("set")
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_SET.to_string()),
+            read_policy: ReadPolicy::default(),
+            write_policy: WritePolicy::default(),
+            runtime,
+        })
+    }
+
+    fn key(&self, key: &[u8]) -> anyhow::Result<Key> {
+        Ok(Key::new(
+            self.namespace.as_str(),
+            self.set.as_str(),
+            Value::Blob(key.to_vec()),
+        )?)
+    }
+
+    async fn get_record(&self, key: &[u8]) -> anyhow::Result<()> {
+        let key = self.key(key)?;
+        match self.client.get(&self.read_policy, &key, Bins::All).await {
+            Err(e) if e.server_result_code() != Some(ResultCode::KeyNotFoundError) => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn put_record(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let key = self.key(key)?;
+        let bin = Bin::new(BIN, Value::Blob(value.to_vec()));
+        self.client.put(&self.write_policy, &key, &[bin]).await?;
+        Ok(())
+    }
+
+    async fn delete_record(&self, key: &[u8]) -> anyhow::Result<()> {
+        let key = self.key(key)?;
+        self.client.delete(&self.write_policy, &key).await?;
+        Ok(())
+    }
+}
+
+/// `host:port,...`, the port defaulting to 3000
+fn parse_hosts(hosts: &str) -> Result<Vec<Host>, ConfigError> {
+    hosts
+        .split(',')
+        .map(str::trim)
+        .map(|host| match host.rsplit_once(':') {
+            Some((name, port)) => port
+                .parse()
+                .map(|port| Host::new(name, port))
+                .map_err(|_| ConfigError::new("db.hosts", format!("invalid port in `{host}`"))),
+            None => Ok(Host::new(host, DEFAULT_PORT)),
+        })
+        .collect()
+}
+
+impl Database for Aerospike {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.get_record(key))
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.put_record(key, value))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.delete_record(key))
+    }
+
+    /// All requests in flight at once, each still its own command
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| async move {
+            match *op {
+                PipelinedOp::Get(key) => self.get_record(key).await,
+                PipelinedOp::Set(key, value) => self.put_record(key, value).await,
+                PipelinedOp::Delete(key) => self.delete_record(key).await,
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
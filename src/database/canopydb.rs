@@ -0,0 +1,72 @@
+use crate::database::{DataDir, Database, DbOptions};
+use anyhow::Context;
+use std::path::Path;
+
+const TREE: &[u8] = b"data";
+
+pub struct CanopyDb {
+    db: canopydb::Database,
+    dir: DataDir,
+}
+
+impl CanopyDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("canopydb", &[])?;
+        let dir = DataDir::new(opts, "canopydb")?;
+        let db = canopydb::Database::new(dir.path()).with_context(|| {
+            format!(
+                "failed to open canopydb database at {}",
+                dir.path().display()
+            )
+        })?;
+        Ok(CanopyDb { db, dir })
+    }
+}
+
+impl Database for CanopyDb {
+    fn init(&self) -> anyhow::Result<()> {
+        let tx = self.db.begin_write()?;
+        tx.get_or_create_tree(TREE)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let tx = self.db.begin_read()?;
+        if let Some(tree) = tx.get_tree(TREE)? {
+            tree.get(key)?;
+        }
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let tx = self.db.begin_write()?;
+        tx.get_or_create_tree(TREE)?.insert(key, value)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let tx = self.db.begin_write()?;
+        tx.get_or_create_tree(TREE)?.delete(key)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let tx = self.db.begin_read()?;
+        let Some(tree) = tx.get_tree(TREE)? else {
+            return Ok(0);
+        };
+        let mut records = 0;
+        for entry in tree.range(start..)?.take(count) {
+            entry?;
+            records += 1;
+        }
+        Ok(records)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+}
@@ -0,0 +1,178 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use couchbase::authenticator::PasswordAuthenticator;
+use couchbase::cluster::Cluster;
+use couchbase::collection::Collection;
+use couchbase::error::ErrorKind;
+use couchbase::options::cluster_options::{ClusterOptions, TlsOptions};
+use couchbase::transcoding::raw_binary;
+use std::fmt::Write;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const DEFAULT_URL: &str = "couchbase://127.0.0.1";
+const DEFAULT_PORT: u16 = 11210;
+const DEFAULT_TLS_PORT: u16 = 11207;
+const DEFAULT_BUCKET: &str = "kvbencher";
+// the credentials the Couchbase quick start sets up
+const DEFAULT_USER: &str = "Administrator";
+const DEFAULT_PASSWORD: &str = "password";
+// the SDK retries bootstrapping forever when no node answers
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Documents in the default collection of one bucket, reached through the KV service only.
+/// Document ids are strings, so keys are stored hex encoded.
+pub struct Couchbase {
+    // keeps the connections to the cluster open
+    _cluster: Cluster,
+    collection: Collection,
+    runtime: Runtime,
+}
+
+impl Couchbase {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported(
+            "couchbase",
+            &["url", "endpoint", "user", "password", "bucket"],
+        )?;
+        let mut bucket = opts.properties.get("bucket").cloned();
+        let (url, user, password, tls) = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (None, Some(endpoint)) => {
+                if opts.properties.contains_key("user") || opts.properties.contains_key("password")
+                {
+                    return Err(ConfigError::new(
+                        "db.user",
+                        "cannot be combined with db.endpoint, put the user in the endpoint",
+                    )
+                    .into());
+                }
+                if let Some(database) = &endpoint.database {
+                    if bucket.is_some() {
+                        return Err(ConfigError::new(
+                            "db.bucket",
+                            "cannot be combined with a bucket in db.endpoint",
+                        )
+                        .into());
+                    }
+                    bucket = Some(database.clone());
+                }
+                let (scheme, port, tls) = if endpoint.tls {
+                    let tls = TlsOptions::new().add_ca_certificates(endpoint.root_certificates()?);
+                    ("couchbases", DEFAULT_TLS_PORT, Some(tls))
+                } else {
+                    ("couchbase", DEFAULT_PORT, None)
+                };
+                (
+                    format!("{scheme}://{}", endpoint.addrs(port).join(",")),
+                    endpoint.user.clone(),
+                    endpoint.password.clone(),
+                    tls,
+                )
+            }
+            (url, None) => (
+                url.map_or(DEFAULT_URL, String::as_str).to_string(),
+                opts.properties.get("user").cloned(),
+                opts.properties.get("password").cloned(),
+                None,
+            ),
+        };
+        let authenticator = PasswordAuthenticator::new(
+            user.as_deref().unwrap_or(DEFAULT_USER),
+            password.as_deref().unwrap_or(DEFAULT_PASSWORD),
+        );
+        let mut options = ClusterOptions::new(authenticator.into());
+        if let Some(tls) = tls {
+            options = options.tls_options(tls);
+        }
+        let bucket = bucket.unwrap_or_else(|| DEFAULT_BUCKET.to_string());
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let cluster = runtime
+            .block_on(async {
+                let connect = Cluster::connect(url.as_str(), options);
+                match tokio::time::timeout(CONNECT_TIMEOUT, connect).await {
+                    Ok(cluster) => Ok(cluster?),
+                    Err(_) => anyhow::bail!("no node answered within {CONNECT_TIMEOUT:?}"),
+                }
+            })
+            .with_context(|| format!("failed to connect to couchbase at {url}"))?;
+        let collection = cluster.bucket(bucket).default_collection();
+        Ok(Couchbase {
+            _cluster: cluster,
+            collection,
+            runtime,
+        })
+    }
+
+    async fn get_doc(&self, key: &[u8]) -> anyhow::Result<()> {
+        match self.collection.get(id(key), None).await {
+            Err(e) if !matches!(e.kind(), ErrorKind::DocumentNotFound) => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    async fn upsert_doc(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let (value, flags) = raw_binary::encode(value)?;
+        self.collection
+            .upsert_raw(id(key), value, flags, None)
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_doc(&self, key: &[u8]) -> anyhow::Result<()> {
+        match self.collection.remove(id(key), None).await {
+            Err(e) if !matches!(e.kind(), ErrorKind::DocumentNotFound) => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn id(key: &[u8]) -> String {
+    let mut id = String::with_capacity(key.len() * 2);
+    for b in key {
+        let _ = write!(id, "{b:02x}");
+    }
+    id
+}
+
+impl Database for Couchbase {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.get_doc(key))
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.upsert_doc(key, value))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.remove_doc(key))
+    }
+
+    /// All requests in flight at once, each still its own KV command
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| async move {
+            match *op {
+                PipelinedOp::Get(key) => self.get_doc(key).await,
+                PipelinedOp::Set(key, value) => self.upsert_doc(key, value).await,
+                PipelinedOp::Delete(key) => self.remove_doc(key).await,
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::database::Endpoint;
+use crate::environment;
+use crate::workload::ConfigError;
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use tempfile::TempDir;
+
+/// Memory-backed filesystem used for `DataDirKind::Tmpfs` when no data dir is given
+const TMPFS_ROOT: &str = "/dev/shm";
+
+/// Kind of storage file-backed databases are placed on
+#[derive(Copy, Clone, Debug, Default, PartialEq, ValueEnum)]
+pub enum DataDirKind {
+    /// The data dir, or the system temp dir
+    #[default]
+    Disk,
+    /// A tmpfs mount, taking the storage device out of the measurement
+    Tmpfs,
+}
+
+/// Where file-backed databases store their data, and adapter specific `db.*` properties
+#[derive(Clone, Debug, Default)]
+pub struct DbOptions {
+    /// Parent directory for database files, a temp dir is used when unset
+    pub data_dir: Option<PathBuf>,
+    pub data_dir_kind: DataDirKind,
+    /// Leave the database files in place after the run
+    pub keep_data: bool,
+    /// `db.<name>` properties with the prefix removed
+    pub properties: BTreeMap<String, String>,
+}
+
+impl DbOptions {
+    /// Fails on the first property the adapter `db` does not understand
+    pub fn check_supported(&self, db: &str, supported: &[&str]) -> Result<(), ConfigError> {
+        match self
+            .properties
+            .keys()
+            .find(|k| !supported.contains(&k.as_str()))
+        {
+            Some(k) if supported.is_empty() => Err(ConfigError::new(
+                format!("db.{k}"),
+                format!("{db} does not take any db properties"),
+            )),
+            Some(k) => Err(ConfigError::new(
+                format!("db.{k}"),
+                format!("not supported by {db}, expected one of {supported:?}"),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The `db.endpoint` connection string, if set
+    pub fn endpoint(&self) -> Result<Option<Endpoint>, ConfigError> {
+        self.properties
+            .get("endpoint")
+            .map(|s| Endpoint::parse(s))
+            .transpose()
+    }
+
+    /// Parses the `db.<key>` property, if set
+    pub fn parse<T: FromStr>(&self, key: &str) -> Result<Option<T>, ConfigError> {
+        self.properties
+            .get(key)
+            .map(|v| {
+                v.parse().map_err(|_| {
+                    ConfigError::new(format!("db.{key}"), format!("cannot parse `{v}`"))
+                })
+            })
+            .transpose()
+    }
+}
+
+/// The directory owned by one database instance, removed on drop unless data is kept
+pub struct DataDir {
+    path: PathBuf,
+    remove_on_drop: bool,
+}
+
+impl DataDir {
+    /// Creates (or reuses) the directory `name` below the configured data dir
+    pub fn new(opts: &DbOptions, name: &str) -> Result<Self> {
+        let prefix = format!("kvbencher-{name}-");
+        let path = match (&opts.data_dir, opts.data_dir_kind) {
+            (Some(dir), _) => dir.join(name),
+            (None, DataDirKind::Disk) => TempDir::with_prefix(prefix)
+                .context("failed to create temp dir")?
+                .keep(),
+            (None, DataDirKind::Tmpfs) => TempDir::with_prefix_in(prefix, TMPFS_ROOT)
+                .with_context(|| format!("failed to create temp dir in {TMPFS_ROOT}"))?
+                .keep(),
+        };
+        std::fs::create_dir_all(&path)
+            .with_context(|| format!("failed to create data dir {}", path.display()))?;
+        let dir = DataDir {
+            path,
+            remove_on_drop: !opts.keep_data,
+        };
+        if opts.data_dir_kind == DataDirKind::Tmpfs {
+            let fs = environment::filesystem(&dir.path);
+            if fs.as_deref() != Some("tmpfs") {
+                bail!(
+                    "data dir {} is on {}, not tmpfs",
+                    dir.path.display(),
+                    fs.as_deref().unwrap_or("an unknown filesystem")
+                );
+            }
+        }
+        Ok(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn keep_data(&self) -> bool {
+        !self.remove_on_drop
+    }
+
+    /// Reserves `bytes` of disk blocks for `file` in this directory without changing its
+    /// length, so the engine growing the file later does not have to allocate blocks
+    #[cfg(target_os = "linux")]
+    pub fn preallocate(&self, file: &str, bytes: u64) -> Result<()> {
+        use std::os::fd::AsRawFd;
+
+        let path = self.path.join(file);
+        let f = std::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        // SAFETY: the fd stays open for the duration of the call
+        let ret =
+            unsafe { libc::fallocate(f.as_raw_fd(), libc::FALLOC_FL_KEEP_SIZE, 0, bytes as i64) };
+        if ret != 0 {
+            return Err(std::io::Error::last_os_error())
+                .with_context(|| format!("failed to preallocate {}", path.display()));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn preallocate(&self, _file: &str, _bytes: u64) -> Result<()> {
+        bail!("preallocation is only supported on Linux")
+    }
+}
+
+impl Drop for DataDir {
+    fn drop(&mut self) {
+        if self.remove_on_drop {
+            let _ = std::fs::remove_dir_all(&self.path);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::alloc::{Layout, alloc_zeroed, dealloc};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::os::unix::fs::{FileExt, OpenOptionsExt};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// O_DIRECT needs buffers, offsets and lengths aligned to the device's logical block size
+const ALIGN: u64 = 4096;
+
+/// A file opened with O_DIRECT that accepts unaligned reads and writes by bouncing them
+/// through aligned buffers, bypassing the OS page cache.
+#[derive(Debug)]
+pub struct DirectFile {
+    file: File,
+    // serializes read-modify-write of partially written blocks
+    write_lock: Mutex<()>,
+}
+
+impl DirectFile {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .custom_flags(libc::O_DIRECT)
+            .open(path)?;
+        Ok(DirectFile {
+            file,
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    pub fn len(&self) -> io::Result<u64> {
+        Ok(self.file.metadata()?.len())
+    }
+
+    pub fn set_len(&self, len: u64) -> io::Result<()> {
+        self.file.set_len(len)
+    }
+
+    pub fn sync_data(&self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+
+    pub fn read(&self, offset: u64, out: &mut [u8]) -> io::Result<()> {
+        if out.is_empty() {
+            return Ok(());
+        }
+        let start = align_down(offset);
+        let end = align_up(offset + out.len() as u64);
+        let mut buf = AlignedBuf::new((end - start) as usize);
+        let read = self.read_aligned(start, &mut buf)?;
+        let skip = (offset - start) as usize;
+        if read < skip + out.len() {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        out.copy_from_slice(&buf[skip..skip + out.len()]);
+        Ok(())
+    }
+
+    pub fn write(&self, offset: u64, data: &[u8]) -> io::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let _guard = self.write_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let data_end = offset + data.len() as u64;
+        let start = align_down(offset);
+        let end = align_up(data_end);
+        let mut buf = AlignedBuf::new((end - start) as usize);
+        let len = self.len()?;
+        if start != offset || end != data_end {
+            // keep the bytes of the partially overwritten edge blocks
+            self.read_aligned(start, &mut buf)?;
+        }
+        let skip = (offset - start) as usize;
+        buf[skip..skip + data.len()].copy_from_slice(data);
+        self.file.write_all_at(&buf, start)?;
+        // writing whole blocks may have extended the file past what was asked for
+        let wanted_len = len.max(data_end);
+        if end > wanted_len {
+            self.file.set_len(wanted_len)?;
+        }
+        Ok(())
+    }
+
+    /// Reads into `buf` from the aligned `offset` until full or EOF, returning the bytes read
+    fn read_aligned(&self, offset: u64, buf: &mut AlignedBuf) -> io::Result<usize> {
+        let mut read = 0;
+        while read < buf.len() {
+            match self.file.read_at(&mut buf[read..], offset + read as u64)? {
+                0 => break,
+                n => read += n,
+            }
+            // O_DIRECT reads stop at EOF on a block boundary, anything else is a short tail
+            if read % ALIGN as usize != 0 {
+                break;
+            }
+        }
+        Ok(read)
+    }
+}
+
+fn align_down(n: u64) -> u64 {
+    n & !(ALIGN - 1)
+}
+
+fn align_up(n: u64) -> u64 {
+    align_down(n + ALIGN - 1)
+}
+
+/// Zeroed heap buffer aligned to `ALIGN`
+struct AlignedBuf {
+    ptr: *mut u8,
+    layout: Layout,
+}
+
+impl AlignedBuf {
+    fn new(len: usize) -> Self {
+        let layout = Layout::from_size_align(len.max(ALIGN as usize), ALIGN as usize)
+            .expect("valid aligned layout");
+        // SAFETY: layout has a non-zero size
+        let ptr = unsafe { alloc_zeroed(layout) };
+        if ptr.is_null() {
+            std::alloc::handle_alloc_error(layout);
+        }
+        AlignedBuf { ptr, layout }
+    }
+}
+
+impl std::ops::Deref for AlignedBuf {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: ptr is valid and initialized for layout.size() bytes
+        unsafe { std::slice::from_raw_parts(self.ptr, self.layout.size()) }
+    }
+}
+
+impl std::ops::DerefMut for AlignedBuf {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: ptr is valid and initialized for layout.size() bytes, and uniquely borrowed
+        unsafe { std::slice::from_raw_parts_mut(self.ptr, self.layout.size()) }
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        // SAFETY: allocated in `new` with the same layout
+        unsafe { dealloc(self.ptr, self.layout) }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_dynamodb::Client;
+use aws_sdk_dynamodb::client::Waiters;
+use aws_sdk_dynamodb::config::Credentials;
+use aws_sdk_dynamodb::primitives::Blob;
+use aws_sdk_dynamodb::types::{
+    AttributeDefinition, AttributeValue, BillingMode, KeySchemaElement, KeyType,
+    ScalarAttributeType,
+};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+const DEFAULT_TABLE: &str = "kvbencher";
+/// Region used with a custom endpoint and no region configured, DynamoDB Local ignores it
+const LOCAL_REGION: &str = "us-east-1";
+const TABLE_READY_TIMEOUT: Duration = Duration::from_secs(120);
+const KEY: &str = "k";
+const VALUE: &str = "v";
+
+pub struct DynamoDb {
+    client: Client,
+    table: String,
+    runtime: Runtime,
+}
+
+impl DynamoDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("dynamodb", &["url", "endpoint", "region", "table"])?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let mut config = aws_config::defaults(BehaviorVersion::latest());
+        let url = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(url), None) => Some(url.clone()),
+            (None, Some(endpoint)) => {
+                endpoint.reject_database("dynamodb")?;
+                // the user and password are an access key id and secret, DynamoDB Local
+                // started by --container accepts any
+                let credentials = Credentials::new(
+                    endpoint.user.as_deref().unwrap_or("kvbencher"),
+                    endpoint.password.as_deref().unwrap_or("kvbencher"),
+                    None,
+                    None,
+                    "kvbencher",
+                );
+                config = config.credentials_provider(credentials);
+                let default_port = if endpoint.tls { 443 } else { 80 };
+                Some(endpoint.url("dynamodb", "http", "https", default_port)?)
+            }
+            // the real service, with credentials and region from the environment
+            (None, None) => None,
+        };
+        if let Some(url) = url {
+            config = config.endpoint_url(url).region(Region::new(LOCAL_REGION));
+        }
+        if let Some(region) = opts.properties.get("region") {
+            config = config.region(Region::new(region.clone()));
+        }
+        let config = runtime.block_on(config.load());
+        Ok(DynamoDb {
+            client: Client::new(&config),
+            table: opts
+                .properties
+                .get("table")
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_TABLE.to_string()),
+            runtime,
+        })
+    }
+
+    async fn create_table(&self) -> anyhow::Result<()> {
+        let created = self
+            .client
+            .create_table()
+            .table_name(&self.table)
+            .attribute_definitions(
+                AttributeDefinition::builder()
+                    .attribute_name(KEY)
+                    .attribute_type(ScalarAttributeType::B)
+                    .build()?,
+            )
+            .key_schema(
+                KeySchemaElement::builder()
+                    .attribute_name(KEY)
+                    .key_type(KeyType::Hash)
+                    .build()?,
+            )
+            .billing_mode(BillingMode::PayPerRequest)
+            .send()
+            .await;
+        match created {
+            Ok(_) => {}
+            // kept from an earlier run
+            Err(e)
+                if e.as_service_error()
+                    .is_some_and(|e| e.is_resource_in_use_exception()) => {}
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to create table {}", self.table));
+            }
+        }
+        self.client
+            .wait_until_table_exists()
+            .table_name(&self.table)
+            .wait(TABLE_READY_TIMEOUT)
+            .await
+            .with_context(|| format!("table {} did not become active", self.table))?;
+        Ok(())
+    }
+
+    async fn get_item(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.client
+            .get_item()
+            .table_name(&self.table)
+            .key(KEY, AttributeValue::B(Blob::new(key)))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn put_item(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.client
+            .put_item()
+            .table_name(&self.table)
+            .item(KEY, AttributeValue::B(Blob::new(key)))
+            .item(VALUE, AttributeValue::B(Blob::new(value)))
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_item(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.client
+            .delete_item()
+            .table_name(&self.table)
+            .key(KEY, AttributeValue::B(Blob::new(key)))
+            .send()
+            .await?;
+        Ok(())
+    }
+}
+
+impl Database for DynamoDb {
+    fn init(&self) -> anyhow::Result<()> {
+        self.runtime.block_on(self.create_table())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.get_item(key))
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.put_item(key, value))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.delete_item(key))
+    }
+
+    /// All requests in flight at once, each still its own HTTP request
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| async move {
+            match *op {
+                PipelinedOp::Get(key) => self.get_item(key).await,
+                PipelinedOp::Set(key, value) => self.put_item(key, value).await,
+                PipelinedOp::Delete(key) => self.delete_item(key).await,
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
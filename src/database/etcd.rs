@@ -0,0 +1,104 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use etcd_client::{Certificate, Client, ConnectOptions, GetOptions, KvClient, TlsOptions};
+use tokio::runtime::Runtime;
+
+const DEFAULT_ENDPOINT: &str = "127.0.0.1:2379";
+const DEFAULT_PORT: u16 = 2379;
+
+pub struct Etcd {
+    // cloned per request, clones share the underlying channel
+    kv: KvClient,
+    runtime: Runtime,
+}
+
+impl Etcd {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("etcd", &["endpoints", "endpoint"])?;
+        let mut options = ConnectOptions::new();
+        let endpoints = match (opts.properties.get("endpoints"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.endpoints",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(endpoints), None) => endpoints.split(',').map(|e| e.trim().to_string()).collect(),
+            (None, Some(endpoint)) => {
+                endpoint.reject_database("etcd")?;
+                if let Some(user) = &endpoint.user {
+                    options = options.with_user(user, endpoint.password.as_deref().unwrap_or(""));
+                }
+                if endpoint.tls {
+                    let tls = TlsOptions::new();
+                    options = options.with_tls(match endpoint.tls_ca_pem()? {
+                        Some(pem) => tls.ca_certificate(Certificate::from_pem(pem)),
+                        None => tls.with_native_roots(),
+                    });
+                }
+                endpoint.addrs(DEFAULT_PORT)
+            }
+            (None, None) => vec![DEFAULT_ENDPOINT.to_string()],
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let client = runtime
+            .block_on(Client::connect(&endpoints, Some(options)))
+            .with_context(|| format!("failed to connect to etcd at {endpoints:?}"))?;
+        Ok(Etcd {
+            kv: client.kv_client(),
+            runtime,
+        })
+    }
+}
+
+impl Database for Etcd {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.kv.clone().get(key, None))?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime
+            .block_on(self.kv.clone().put(key, value, None))?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.kv.clone().delete(key, None))?;
+        Ok(())
+    }
+
+    /// One range request from `start` to the end of the keyspace
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let options = GetOptions::new().with_from_key().with_limit(count as i64);
+        let response = self
+            .runtime
+            .block_on(self.kv.clone().get(start, Some(options)))?;
+        Ok(response.kvs().len())
+    }
+
+    /// All requests in flight at once on the shared channel
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| {
+            let mut kv = self.kv.clone();
+            async move {
+                match *op {
+                    PipelinedOp::Get(key) => kv.get(key, None).await.map(drop),
+                    PipelinedOp::Set(key, value) => kv.put(key, value, None).await.map(drop),
+                    PipelinedOp::Delete(key) => kv.delete(key, None).await.map(drop),
+                }
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
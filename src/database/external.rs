@@ -0,0 +1,191 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::{Context, bail};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Layout, integers little-endian:
+//   request:
+//     u8 op (0 get, 1 set, 2 delete)
+//     u32 key length + key bytes
+//     u32 value length + value bytes, sets only
+//   response:
+//     u8 status (0 ok, 1 not found, 2 error), not found only for gets and deletes
+//     u32 value length + value bytes, found gets only
+//     u32 message length + UTF-8 message, errors only
+// A process answers requests in the order they were sent, and exits when stdin is closed.
+
+const OP_GET: u8 = 0;
+const OP_SET: u8 = 1;
+const OP_DELETE: u8 = 2;
+const STATUS_OK: u8 = 0;
+const STATUS_NOT_FOUND: u8 = 1;
+const STATUS_ERROR: u8 = 2;
+
+/// Processes started when none is configured, each one has to reach the same data for more
+const DEFAULT_PROCESSES: usize = 1;
+
+pub struct External {
+    command: String,
+    // requests go to the processes in turn, each answering one request sequence at a time
+    processes: Vec<Mutex<Process>>,
+    next: AtomicUsize,
+}
+
+/// A running instance of the command, with a buffer for the values it returns
+struct Process {
+    child: Child,
+    stdin: BufWriter<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    value: Vec<u8>,
+}
+
+impl External {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("external", &["command", "processes"])?;
+        let command = opts.properties.get("command").cloned().ok_or_else(|| {
+            ConfigError::new(
+                "db.command",
+                "set the command to run, e.g. `-p db.command='python3 server.py'`",
+            )
+        })?;
+        let processes = opts.parse("processes")?.unwrap_or(DEFAULT_PROCESSES);
+        if processes == 0 {
+            return Err(ConfigError::new("db.processes", "must be at least 1").into());
+        }
+        let processes = (0..processes)
+            .map(|_| spawn(&command).map(Mutex::new))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(External {
+            command,
+            processes,
+            next: AtomicUsize::new(0),
+        })
+    }
+}
+
+fn spawn(command: &str) -> anyhow::Result<Process> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run `{command}`"))?;
+    let stdin = child.stdin.take().context("stdin is piped")?;
+    let stdout = child.stdout.take().context("stdout is piped")?;
+    Ok(Process {
+        child,
+        stdin: BufWriter::new(stdin),
+        stdout: BufReader::new(stdout),
+        value: Vec::new(),
+    })
+}
+
+impl Drop for External {
+    fn drop(&mut self) {
+        for process in self.processes.drain(..) {
+            let Process {
+                mut child, stdin, ..
+            } = process.into_inner().unwrap_or_else(|e| e.into_inner());
+            // closing stdin asks the process to exit, it may still be flushing its data
+            drop(stdin);
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Process {
+    /// Sends all of `ops` before reading the first response
+    fn exchange(&mut self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        for &op in ops {
+            self.send(op)?;
+        }
+        self.stdin.flush()?;
+        for &op in ops {
+            self.receive(op)?;
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, op: PipelinedOp) -> anyhow::Result<()> {
+        match op {
+            PipelinedOp::Get(key) => {
+                self.stdin.write_all(&[OP_GET])?;
+                self.write_bytes(key)?;
+            }
+            PipelinedOp::Set(key, value) => {
+                self.stdin.write_all(&[OP_SET])?;
+                self.write_bytes(key)?;
+                self.write_bytes(value)?;
+            }
+            PipelinedOp::Delete(key) => {
+                self.stdin.write_all(&[OP_DELETE])?;
+                self.write_bytes(key)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> anyhow::Result<()> {
+        let len = u32::try_from(bytes.len()).context("keys and values are below 4 GiB")?;
+        self.stdin.write_all(&len.to_le_bytes())?;
+        self.stdin.write_all(bytes)?;
+        Ok(())
+    }
+
+    /// Reads the response to the oldest request not answered yet
+    fn receive(&mut self, op: PipelinedOp) -> anyhow::Result<()> {
+        let mut status = [0; 1];
+        self.stdout
+            .read_exact(&mut status)
+            .context("the process closed stdout")?;
+        match (status[0], op) {
+            (STATUS_OK, PipelinedOp::Get(_)) => self.read_value(),
+            (STATUS_OK, PipelinedOp::Set(..) | PipelinedOp::Delete(_))
+            | (STATUS_NOT_FOUND, PipelinedOp::Get(_) | PipelinedOp::Delete(_)) => Ok(()),
+            (STATUS_ERROR, _) => {
+                self.read_value()?;
+                bail!("{}", String::from_utf8_lossy(&self.value))
+            }
+            (status, _) => bail!("unexpected response status {status}"),
+        }
+    }
+
+    fn read_value(&mut self) -> anyhow::Result<()> {
+        let mut len = [0; 4];
+        self.stdout.read_exact(&mut len)?;
+        self.value.resize(u32::from_le_bytes(len) as usize, 0);
+        self.stdout.read_exact(&mut self.value)?;
+        Ok(())
+    }
+}
+
+impl Database for External {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.pipeline(&[PipelinedOp::Get(key)])
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.pipeline(&[PipelinedOp::Set(key, value)])
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.pipeline(&[PipelinedOp::Delete(key)])
+    }
+
+    /// The whole pipeline goes to one process, written before the first response is read
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let i = self.next.fetch_add(1, Ordering::Relaxed) % self.processes.len();
+        let mut process = self.processes[i].lock().unwrap_or_else(|e| e.into_inner());
+        process
+            .exchange(ops)
+            .with_context(|| format!("`{}` failed", self.command))
+    }
+}
@@ -0,0 +1,80 @@
+use crate::database::{DataDir, Database, DbOptions};
+use std::fmt::Write;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Longest path component used, below the usual 255 byte file name limit
+const MAX_NAME_LEN: usize = 254;
+
+pub struct FilePerKey {
+    dir: DataDir,
+}
+
+impl FilePerKey {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("file-per-key", &[])?;
+        let dir = DataDir::new(opts, "file-per-key")?;
+        Ok(FilePerKey { dir })
+    }
+
+    /// `<first hex byte>/<hex key>`, with the hex key split into directories when it is
+    /// longer than a file name may be
+    fn key_path(&self, key: &[u8]) -> PathBuf {
+        let mut hex = String::with_capacity(key.len() * 2);
+        for b in key {
+            let _ = write!(hex, "{b:02x}");
+        }
+        let mut path = self.dir.path().join(hex.get(..2).unwrap_or_default());
+        // hex is ASCII, so every chunk is valid UTF-8
+        for name in hex.as_bytes().chunks(MAX_NAME_LEN) {
+            path.push(std::str::from_utf8(name).unwrap_or_default());
+        }
+        path
+    }
+}
+
+impl Database for FilePerKey {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        match fs::read(self.key_path(key)) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        // written in place and never synced, like the code this stands in for
+        let path = self.key_path(key);
+        match fs::write(&path, value) {
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                create_parent(&path)?;
+                fs::write(&path, value)?;
+            }
+            result => result?,
+        }
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        match fs::remove_file(self.key_path(key)) {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+}
+
+fn create_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,70 @@
+use crate::database::{DataDir, Database, DbOptions, EngineActivity};
+use anyhow::Context;
+use fjall::{Keyspace, KeyspaceCreateOptions};
+use std::path::Path;
+
+pub struct Fjall {
+    db: fjall::Database,
+    data: Keyspace,
+    dir: DataDir,
+}
+
+impl Fjall {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("fjall", &[])?;
+        let dir = DataDir::new(opts, "fjall")?;
+        let db = fjall::Database::builder(dir.path())
+            .open()
+            .with_context(|| {
+                format!("failed to open fjall database at {}", dir.path().display())
+            })?;
+        let data = db.keyspace("data", KeyspaceCreateOptions::default)?;
+        Ok(Fjall { db, data, dir })
+    }
+}
+
+impl Database for Fjall {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data.get(key)?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        // journaled without an fsync per write, fjall syncs in the background and on drop
+        self.data.insert(key, value)?;
+        Ok(())
+    }
+
+    /// A tombstone, dropped once compaction reaches the last level
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data.remove(key)?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let mut records = 0;
+        for entry in self.data.range(start..).take(count) {
+            entry.into_inner()?;
+            records += 1;
+        }
+        Ok(records)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        Some(EngineActivity {
+            // fjall counts flushes as they are queued, not while they run
+            flushes_running: Some(self.db.outstanding_flushes() as u64),
+            compactions_running: Some(self.db.active_compactions() as u64),
+            size_on_disk_bytes: self.db.disk_space().ok(),
+            ..EngineActivity::default()
+        })
+    }
+}
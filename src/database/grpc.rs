@@ -0,0 +1,224 @@
+use crate::database::{Database, DbOptions, Endpoint, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use tokio::runtime::Runtime;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{Ascii, MetadataValue};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig};
+use tonic_prost::ProstCodec;
+
+const DEFAULT_URL: &str = "http://127.0.0.1:50051";
+const DEFAULT_PORT: u16 = 50051;
+const GET: &str = "/kvbencher.Kv/Get";
+const PUT: &str = "/kvbencher.Kv/Put";
+const DELETE: &str = "/kvbencher.Kv/Delete";
+const SCAN: &str = "/kvbencher.Kv/Scan";
+
+// The messages of proto/kvbencher.proto used here, written out so building needs no protoc
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct GetRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    key: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct GetResponse {
+    #[prost(bool, tag = "1")]
+    found: bool,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct PutRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    key: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct PutResponse {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct DeleteRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    key: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct DeleteResponse {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ScanRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    start: Vec<u8>,
+    #[prost(uint32, tag = "2")]
+    limit: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ScanResponse {
+    #[prost(message, repeated, tag = "1")]
+    entries: Vec<KeyValue>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct KeyValue {
+    #[prost(bytes = "vec", tag = "1")]
+    key: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+pub struct Grpc {
+    // cloned per request, clones share the underlying HTTP/2 connection
+    channel: Channel,
+    /// `Basic` authorization metadata, from the endpoint's user and password
+    authorization: Option<MetadataValue<Ascii>>,
+    runtime: Runtime,
+}
+
+impl Grpc {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("grpc", &["url", "endpoint"])?;
+        let (url, tls, authorization) = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(url), None) => (url.clone(), None, None),
+            (None, Some(endpoint)) => {
+                endpoint.reject_database("grpc")?;
+                let url = endpoint.url("grpc", "http", "https", DEFAULT_PORT)?;
+                let tls = endpoint.tls.then(|| tls_config(&endpoint)).transpose()?;
+                let authorization = endpoint
+                    .user
+                    .as_ref()
+                    .map(|user| {
+                        let credentials =
+                            format!("{user}:{}", endpoint.password.as_deref().unwrap_or(""));
+                        format!("Basic {}", STANDARD.encode(credentials)).parse()
+                    })
+                    .transpose()?;
+                (url, tls, authorization)
+            }
+            (None, None) => (DEFAULT_URL.to_string(), None, None),
+        };
+        let mut channel = Channel::from_shared(url.clone())?;
+        if let Some(tls) = tls {
+            channel = channel.tls_config(tls)?;
+        }
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let channel = runtime
+            .block_on(channel.connect())
+            .with_context(|| format!("failed to connect to gRPC server at {url}"))?;
+        Ok(Grpc {
+            channel,
+            authorization,
+            runtime,
+        })
+    }
+
+    async fn unary<Req, Resp>(&self, path: &'static str, request: Req) -> anyhow::Result<Resp>
+    where
+        Req: prost::Message + 'static,
+        Resp: prost::Message + Default + 'static,
+    {
+        let mut client = tonic::client::Grpc::new(self.channel.clone());
+        client.ready().await?;
+        let mut request = tonic::Request::new(request);
+        if let Some(value) = &self.authorization {
+            request
+                .metadata_mut()
+                .insert("authorization", value.clone());
+        }
+        let response = client
+            .unary(
+                request,
+                PathAndQuery::from_static(path),
+                ProstCodec::default(),
+            )
+            .await?;
+        Ok(response.into_inner())
+    }
+
+    async fn get_value(&self, key: &[u8]) -> anyhow::Result<()> {
+        let request = GetRequest { key: key.to_vec() };
+        let _: GetResponse = self.unary(GET, request).await?;
+        Ok(())
+    }
+
+    async fn put_value(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let request = PutRequest {
+            key: key.to_vec(),
+            value: value.to_vec(),
+        };
+        let _: PutResponse = self.unary(PUT, request).await?;
+        Ok(())
+    }
+
+    async fn delete_value(&self, key: &[u8]) -> anyhow::Result<()> {
+        let request = DeleteRequest { key: key.to_vec() };
+        let _: DeleteResponse = self.unary(DELETE, request).await?;
+        Ok(())
+    }
+}
+
+/// Verifies the server against the endpoint's CA file or the system roots
+fn tls_config(endpoint: &Endpoint) -> anyhow::Result<ClientTlsConfig> {
+    let config = ClientTlsConfig::new();
+    Ok(match endpoint.tls_ca_pem()? {
+        Some(pem) => config.ca_certificate(Certificate::from_pem(pem)),
+        None => config.with_native_roots(),
+    })
+}
+
+impl Database for Grpc {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.get_value(key))
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.put_value(key, value))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.delete_value(key))
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let request = ScanRequest {
+            start: start.to_vec(),
+            limit: count as u32,
+        };
+        let response: ScanResponse = self.runtime.block_on(self.unary(SCAN, request))?;
+        Ok(response.entries.len())
+    }
+
+    /// All requests in flight at once on the shared connection
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| async move {
+            match *op {
+                PipelinedOp::Get(key) => self.get_value(key).await,
+                PipelinedOp::Set(key, value) => self.put_value(key, value).await,
+                PipelinedOp::Delete(key) => self.delete_value(key).await,
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use crate::database::{Database, DbOptions, Endpoint};
+use crate::workload::ConfigError;
+use anyhow::{Context, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::fmt::Write;
+use ureq::http::StatusCode;
+use ureq::http::header::AUTHORIZATION;
+use ureq::tls::{Certificate, RootCerts, TlsConfig};
+use ureq::{Agent, RequestBuilder};
+
+const DEFAULT_URL: &str = "http://127.0.0.1:8080";
+const DEFAULT_PORT: u16 = 80;
+const DEFAULT_TLS_PORT: u16 = 443;
+/// Idle connections kept, one per run thread up to this many
+const DEFAULT_POOL_SIZE: usize = 64;
+
+pub struct Http {
+    agent: Agent,
+    /// Base URL without a trailing slash
+    url: String,
+    /// `Basic` authorization header value, from the endpoint's user and password
+    authorization: Option<String>,
+}
+
+impl Http {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("http", &["url", "endpoint", "pool_size"])?;
+        let pool_size = opts.parse("pool_size")?.unwrap_or(DEFAULT_POOL_SIZE);
+        let config = Agent::config_builder()
+            // a missing key is a 404, not an error
+            .http_status_as_error(false)
+            .max_idle_connections(pool_size)
+            .max_idle_connections_per_host(pool_size);
+        let (url, config, authorization) = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(url), None) => (url.trim_end_matches('/').to_string(), config, None),
+            (None, Some(endpoint)) => {
+                let default_port = if endpoint.tls {
+                    DEFAULT_TLS_PORT
+                } else {
+                    DEFAULT_PORT
+                };
+                let mut url = endpoint.url("http", "http", "https", default_port)?;
+                if let Some(path) = &endpoint.database {
+                    url = format!("{url}/{}", path.trim_end_matches('/'));
+                }
+                let config = config.tls_config(tls_config(&endpoint)?);
+                let authorization = endpoint.user.as_ref().map(|user| {
+                    let credentials =
+                        format!("{user}:{}", endpoint.password.as_deref().unwrap_or(""));
+                    format!("Basic {}", STANDARD.encode(credentials))
+                });
+                (url, config, authorization)
+            }
+            (None, None) => (DEFAULT_URL.to_string(), config, None),
+        };
+        Ok(Http {
+            agent: config.build().into(),
+            url,
+            authorization,
+        })
+    }
+
+    fn authorized<B>(&self, request: RequestBuilder<B>) -> RequestBuilder<B> {
+        match &self.authorization {
+            Some(value) => request.header(AUTHORIZATION, value),
+            None => request,
+        }
+    }
+
+    /// `<url>/kv/<key>`, with the key percent-encoded so the server sees the raw bytes
+    fn key_url(&self, key: &[u8]) -> String {
+        let mut url = String::with_capacity(self.url.len() + 4 + key.len() * 3);
+        url.push_str(&self.url);
+        url.push_str("/kv/");
+        for &b in key {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+                url.push(b as char);
+            } else {
+                let _ = write!(url, "%{b:02X}");
+            }
+        }
+        url
+    }
+}
+
+/// Verifies the server against the endpoint's CA file or the system roots
+fn tls_config(endpoint: &Endpoint) -> anyhow::Result<TlsConfig> {
+    if !endpoint.tls {
+        return Ok(TlsConfig::default());
+    }
+    let certs: Vec<_> = endpoint
+        .root_certificates()?
+        .iter()
+        .map(|der| Certificate::from_der(der).to_owned())
+        .collect();
+    Ok(TlsConfig::builder()
+        .root_certs(RootCerts::new_with_certs(&certs))
+        .build())
+}
+
+impl Database for Http {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let url = self.key_url(key);
+        let mut response = self
+            .authorized(self.agent.get(&url))
+            .call()
+            .with_context(|| format!("GET {url} failed"))?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            bail!("GET {url} returned {status}");
+        }
+        // read to the end so the connection goes back to the pool
+        response.body_mut().read_to_vec()?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let url = self.key_url(key);
+        let mut response = self
+            .authorized(self.agent.put(&url))
+            .send(value)
+            .with_context(|| format!("PUT {url} failed"))?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("PUT {url} returned {status}");
+        }
+        response.body_mut().read_to_vec()?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let url = self.key_url(key);
+        let mut response = self
+            .authorized(self.agent.delete(&url))
+            .call()
+            .with_context(|| format!("DELETE {url} failed"))?;
+        let status = response.status();
+        if !status.is_success() && status != StatusCode::NOT_FOUND {
+            bail!("DELETE {url} returned {status}");
+        }
+        response.body_mut().read_to_vec()?;
+        Ok(())
+    }
+}
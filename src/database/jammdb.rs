@@ -0,0 +1,72 @@
+use crate::database::{DataDir, Database, DbOptions};
+use anyhow::Context;
+use jammdb::DB;
+use std::path::Path;
+
+const FILE_NAME: &str = "data.jammdb";
+const BUCKET: &str = "data";
+
+pub struct JammDb {
+    db: DB,
+    dir: DataDir,
+}
+
+impl JammDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("jammdb", &[])?;
+        let dir = DataDir::new(opts, "jammdb")?;
+        let path = dir.path().join(FILE_NAME);
+        let db = DB::open(&path)
+            .with_context(|| format!("failed to open jammdb database at {}", path.display()))?;
+        Ok(JammDb { db, dir })
+    }
+}
+
+impl Database for JammDb {
+    fn init(&self) -> anyhow::Result<()> {
+        let tx = self.db.tx(true)?;
+        tx.get_or_create_bucket(BUCKET)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let tx = self.db.tx(false)?;
+        tx.get_bucket(BUCKET)?.get(key);
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        // a single writer, each set waits for the previous transaction to commit and sync
+        let tx = self.db.tx(true)?;
+        tx.get_bucket(BUCKET)?.put(key, value)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let tx = self.db.tx(true)?;
+        match tx.get_bucket(BUCKET)?.delete(key) {
+            Ok(_) | Err(jammdb::Error::KeyValueMissing) => {}
+            Err(e) => return Err(e.into()),
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let tx = self.db.tx(false)?;
+        let bucket = tx.get_bucket(BUCKET)?;
+        let mut cursor = bucket.cursor();
+        cursor.seek(start);
+        Ok(cursor.take(count).count())
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn preallocate(&self, bytes: u64) -> anyhow::Result<()> {
+        self.dir.preallocate(FILE_NAME, bytes)
+    }
+}
@@ -0,0 +1,77 @@
+use crate::database::{DataDir, Database, DbOptions};
+use anyhow::Context;
+use rusty_leveldb::{DB, LdbIterator, Options};
+use std::path::Path;
+use std::sync::Mutex;
+
+pub struct LevelDb {
+    // rusty-leveldb is not concurrent, reads need `&mut` too
+    db: Mutex<DB>,
+    dir: DataDir,
+}
+
+impl LevelDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("leveldb", &[])?;
+        let dir = DataDir::new(opts, "leveldb")?;
+        let db = DB::open(dir.path(), Options::default()).with_context(|| {
+            format!(
+                "failed to open leveldb database at {}",
+                dir.path().display()
+            )
+        })?;
+        Ok(LevelDb {
+            db: Mutex::new(db),
+            dir,
+        })
+    }
+
+    fn db(&self) -> std::sync::MutexGuard<'_, DB> {
+        self.db.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for LevelDb {
+    fn drop(&mut self) {
+        if self.dir.keep_data() {
+            let _ = self.db().flush();
+        }
+    }
+}
+
+impl Database for LevelDb {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.db().get(key);
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.db().put(key, value)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.db().delete(key)?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let mut db = self.db();
+        let mut iter = db.new_iter()?;
+        iter.seek(start);
+        let mut records = 0;
+        while records < count && iter.current().is_some() {
+            records += 1;
+            iter.advance();
+        }
+        Ok(records)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+}
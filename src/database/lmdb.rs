@@ -0,0 +1,96 @@
+use crate::database::{DataDir, Database, DbOptions, EngineActivity};
+use anyhow::Context;
+use heed::types::Bytes;
+use heed::{Env, EnvOpenOptions};
+use std::ops::Bound;
+use std::path::Path;
+
+/// LMDB reserves its whole map as address space up front, this only bounds the file size
+const DEFAULT_MAP_SIZE: usize = 1 << 40;
+const FILE_NAME: &str = "data.mdb";
+
+pub struct Lmdb {
+    env: Env,
+    db: heed::Database<Bytes, Bytes>,
+    dir: DataDir,
+}
+
+impl Lmdb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("lmdb", &["map_size"])?;
+        let map_size = opts.parse("map_size")?.unwrap_or(DEFAULT_MAP_SIZE);
+
+        let dir = DataDir::new(opts, "lmdb")?;
+        let mut options = EnvOpenOptions::new();
+        // one reader slot per run thread, the default of 126 is below high thread counts
+        options.map_size(map_size).max_readers(4096);
+        // SAFETY: the data dir is owned by this instance, nothing else maps its files
+        let env = unsafe { options.open(dir.path()) }
+            .with_context(|| format!("failed to open lmdb database at {}", dir.path().display()))?;
+        let mut tx = env.write_txn()?;
+        let db = env.create_database(&mut tx, None)?;
+        tx.commit()?;
+        Ok(Lmdb { env, db, dir })
+    }
+}
+
+impl Database for Lmdb {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let tx = self.env.read_txn()?;
+        self.db.get(&tx, key)?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut tx = self.env.write_txn()?;
+        self.db.put(&mut tx, key, value)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let mut tx = self.env.write_txn()?;
+        self.db.delete(&mut tx, key)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let tx = self.env.read_txn()?;
+        let mut records = 0;
+        let range = (Bound::Included(start), Bound::Unbounded);
+        for entry in self.db.range(&tx, &range)?.take(count) {
+            entry?;
+            records += 1;
+        }
+        Ok(records)
+    }
+
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut tx = self.env.write_txn()?;
+        self.db.get(&tx, key)?;
+        self.db.put(&mut tx, key, value)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        // LMDB has no background work, writers do everything inline
+        Some(EngineActivity {
+            size_on_disk_bytes: self.env.real_disk_size().ok(),
+            ..EngineActivity::default()
+        })
+    }
+
+    fn preallocate(&self, bytes: u64) -> anyhow::Result<()> {
+        self.dir.preallocate(FILE_NAME, bytes)
+    }
+}
@@ -0,0 +1,235 @@
+use crate::database::{DataDir, Database, DbOptions, EngineActivity};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use thread_local::ThreadLocal;
+
+/// marble stores objects under dense `u64` ids, so keys get ids in order of their first set and
+/// are written into each object to rebuild this mapping when a kept data dir is reopened. Sets
+/// are buffered and written as one batch, one heap file, every `batch_size` sets, the way marble
+/// expects a write-ahead log to be flushed into it. Nothing logs the buffer, it is lost on a
+/// crash.
+pub struct MarbleDb {
+    // a marble handle is not Sync, every thread clones its own from `heap`
+    heap: Mutex<marble::Marble>,
+    handles: ThreadLocal<marble::Marble>,
+    index: RwLock<HashMap<Vec<u8>, u64>>,
+    next_id: AtomicU64,
+    // encoded objects not yet written to the heap, `None` for deleted ones
+    pending: Mutex<HashMap<u64, Option<Vec<u8>>>>,
+    batch_size: usize,
+    maintenance: Option<Maintenance>,
+    dir: DataDir,
+}
+
+/// Background thread defragmenting the heap files, which marble never does on its own
+struct Maintenance {
+    running: Arc<AtomicBool>,
+    // dropping it stops the thread
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MarbleDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported(
+            "marble",
+            &[
+                "batch_size",
+                "sync",
+                "target_file_size",
+                "compaction_percent",
+                "maintenance_ms",
+            ],
+        )?;
+        let batch_size = opts.parse("batch_size")?.unwrap_or(256);
+        if batch_size == 0 {
+            return Err(ConfigError::new("db.batch_size", "must be at least 1").into());
+        }
+        let dir = DataDir::new(opts, "marble")?;
+        let mut config = marble::Config {
+            path: dir.path().to_path_buf(),
+            // an fsync per written batch, off by default like in marble
+            fsync_each_batch: opts.parse("sync")?.unwrap_or(false),
+            ..marble::Config::default()
+        };
+        if let Some(bytes) = opts.parse("target_file_size")? {
+            config.target_file_size = bytes;
+        }
+        if let Some(percent) = opts.parse("compaction_percent")? {
+            if percent > 99 {
+                return Err(ConfigError::new("db.compaction_percent", "must be at most 99").into());
+            }
+            config.file_compaction_percent = percent;
+        }
+        let heap = config
+            .open()
+            .with_context(|| format!("failed to open marble heap at {}", dir.path().display()))?;
+
+        let mut index = HashMap::new();
+        let mut next_id = 0;
+        for id in heap.allocated_object_ids() {
+            let object = heap
+                .read(id)?
+                .with_context(|| format!("marble object {id} vanished while reopening"))?;
+            let (key, _) = decode(&object)
+                .with_context(|| format!("marble object {id} was not written by kvbencher"))?;
+            index.insert(key.to_vec(), id);
+            next_id = id + 1;
+        }
+
+        let maintenance = match opts.parse("maintenance_ms")?.unwrap_or(1000) {
+            0 => None,
+            ms => Some(Maintenance::start(heap.clone(), Duration::from_millis(ms))),
+        };
+        Ok(MarbleDb {
+            heap: Mutex::new(heap),
+            handles: ThreadLocal::new(),
+            index: RwLock::new(index),
+            next_id: AtomicU64::new(next_id),
+            pending: Mutex::new(HashMap::new()),
+            batch_size,
+            maintenance,
+            dir,
+        })
+    }
+
+    fn heap(&self) -> &marble::Marble {
+        self.handles.get_or(|| self.heap.lock().unwrap().clone())
+    }
+
+    fn id_of(&self, key: &[u8]) -> u64 {
+        if let Some(&id) = self.index.read().unwrap().get(key) {
+            return id;
+        }
+        *self
+            .index
+            .write()
+            .unwrap()
+            .entry(key.to_vec())
+            .or_insert_with(|| self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl MarbleDb {
+    fn buffer(&self, id: u64, object: Option<Vec<u8>>) -> anyhow::Result<()> {
+        let mut pending = self.pending.lock().unwrap();
+        pending.insert(id, object);
+        if pending.len() >= self.batch_size {
+            // the write filling the batch writes it, holding up other writes until the file is written
+            self.heap().write_batch(pending.drain())?;
+        }
+        Ok(())
+    }
+}
+
+impl Maintenance {
+    fn start(heap: marble::Marble, interval: Duration) -> Self {
+        let running = Arc::new(AtomicBool::new(false));
+        let (stop, stopped) = mpsc::channel::<()>();
+        let thread = {
+            let running = running.clone();
+            std::thread::spawn(move || {
+                while let Err(RecvTimeoutError::Timeout) = stopped.recv_timeout(interval) {
+                    running.store(true, Ordering::Relaxed);
+                    if let Err(e) = heap.maintenance() {
+                        eprintln!("marble maintenance failed: {e}");
+                    }
+                    running.store(false, Ordering::Relaxed);
+                }
+            })
+        };
+        Maintenance {
+            running,
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Maintenance {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for MarbleDb {
+    fn drop(&mut self) {
+        if self.dir.keep_data() {
+            let heap = self.heap.get_mut().unwrap_or_else(|e| e.into_inner());
+            let pending = self.pending.get_mut().unwrap_or_else(|e| e.into_inner());
+            if !pending.is_empty() {
+                let _ = heap.write_batch(pending.drain());
+            }
+            let _ = heap.sync_all();
+        }
+    }
+}
+
+impl Database for MarbleDb {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let Some(id) = self.index.read().unwrap().get(key).copied() else {
+            return Ok(());
+        };
+        if self.pending.lock().unwrap().contains_key(&id) {
+            return Ok(());
+        }
+        self.heap().read(id)?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.buffer(self.id_of(key), Some(encode(key, value)))
+    }
+
+    /// Buffered like a set, the key keeps its id for when it is set again
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let Some(id) = self.index.read().unwrap().get(key).copied() else {
+            return Ok(());
+        };
+        self.buffer(id, None)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        Some(EngineActivity {
+            compactions_running: self
+                .maintenance
+                .as_ref()
+                .map(|m| m.running.load(Ordering::Relaxed) as u64),
+            size_on_disk_bytes: Some(self.heap().stats().total_file_size),
+            ..EngineActivity::default()
+        })
+    }
+}
+
+/// `key length (u32 LE) | key | value`
+fn encode(key: &[u8], value: &[u8]) -> Vec<u8> {
+    let mut object = Vec::with_capacity(4 + key.len() + value.len());
+    object.extend_from_slice(&(key.len() as u32).to_le_bytes());
+    object.extend_from_slice(key);
+    object.extend_from_slice(value);
+    object
+}
+
+fn decode(object: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (len, rest) = object.split_first_chunk::<4>()?;
+    let len = u32::from_le_bytes(*len) as usize;
+    (rest.len() >= len).then(|| rest.split_at(len))
+}
@@ -1,5 +1,6 @@
 use crate::database::Database;
 use std::collections::BTreeMap;
+use std::ops::Bound;
 use std::sync::RwLock;
 
 #[derive(Default)]
@@ -24,4 +25,25 @@ impl Database for MemBTree {
             .insert(Vec::from(key), Vec::from(value));
         Ok(())
     }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let data = self.data.read().unwrap_or_else(|e| e.into_inner());
+        let range = data.range::<[u8], _>((Bound::Included(start), Bound::Unbounded));
+        Ok(range.take(count).count())
+    }
+
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut data = self.data.write().unwrap_or_else(|e| e.into_inner());
+        data.get(key);
+        data.insert(Vec::from(key), Vec::from(value));
+        Ok(())
+    }
 }
@@ -0,0 +1,39 @@
+use crate::database::Database;
+use dashmap::DashMap;
+
+#[derive(Default)]
+pub struct MemDashMap {
+    data: DashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Database for MemDashMap {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data.get(key);
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.data.insert(Vec::from(key), Vec::from(value));
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data.remove(key);
+        Ok(())
+    }
+
+    /// Holds the key's shard locked from the read to the write
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        match self.data.get_mut(key) {
+            Some(mut entry) => *entry = Vec::from(value),
+            None => {
+                self.data.insert(Vec::from(key), Vec::from(value));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use crate::database::Database;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Every operation, reads included, takes the one lock
+#[derive(Default)]
+pub struct MemHashMap {
+    data: Mutex<HashMap<Vec<u8>, Vec<u8>>>,
+}
+
+impl Database for MemHashMap {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner()).get(key);
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.data
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(Vec::from(key), Vec::from(value));
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(key);
+        Ok(())
+    }
+
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut data = self.data.lock().unwrap_or_else(|e| e.into_inner());
+        data.get(key);
+        data.insert(Vec::from(key), Vec::from(value));
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::database::Database;
+use crossbeam_skiplist::SkipMap;
+use std::ops::Bound;
+
+#[derive(Default)]
+pub struct MemSkipMap {
+    data: SkipMap<Vec<u8>, Vec<u8>>,
+}
+
+impl Database for MemSkipMap {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data.get(key);
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.data.insert(Vec::from(key), Vec::from(value));
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.data.remove(key);
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let range = self
+            .data
+            .range::<[u8], _>((Bound::Included(start), Bound::Unbounded));
+        Ok(range.take(count).count())
+    }
+}
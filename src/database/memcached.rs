@@ -0,0 +1,96 @@
+use crate::database::{Database, DbOptions};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use memcache::Client;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+const DEFAULT_URL: &str = "memcache://127.0.0.1:11211";
+const DEFAULT_PORT: u16 = 11211;
+/// Connections opened up front, one per run thread up to this many
+const DEFAULT_POOL_SIZE: u32 = 64;
+/// memcached's key length limit, reached by 187 byte keys once base64-encoded
+const MAX_KEY_LEN: usize = 250;
+
+pub struct Memcached {
+    client: Client,
+    misses: AtomicU64,
+}
+
+impl Memcached {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("memcached", &["url", "endpoint", "pool_size"])?;
+        let urls = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(url), None) => vec![url.clone()],
+            (None, Some(endpoint)) => {
+                // the client only speaks plain text, and memcached authenticates over SASL
+                endpoint.reject_auth("memcached")?;
+                endpoint.reject_tls("memcached")?;
+                endpoint.reject_database("memcached")?;
+                endpoint
+                    .addrs(DEFAULT_PORT)
+                    .into_iter()
+                    .map(|addr| format!("memcache://{addr}"))
+                    .collect()
+            }
+            (None, None) => vec![DEFAULT_URL.to_string()],
+        };
+        let pool_size = opts.parse("pool_size")?.unwrap_or(DEFAULT_POOL_SIZE);
+        let client = Client::builder()
+            .add_server(urls.clone())
+            .and_then(|b| b.with_max_pool_size(pool_size).build())
+            .with_context(|| format!("failed to connect to memcached at {urls:?}"))?;
+        Ok(Memcached {
+            client,
+            misses: AtomicU64::new(0),
+        })
+    }
+}
+
+/// Base64-encodes `key` into `buf`, the text protocol does not allow arbitrary bytes in keys
+fn encode_key<'a>(key: &[u8], buf: &'a mut [u8; MAX_KEY_LEN]) -> anyhow::Result<&'a str> {
+    let len = URL_SAFE_NO_PAD
+        .encode_slice(key, buf)
+        .with_context(|| format!("memcached keys are at most 187 bytes, got {}", key.len()))?;
+    Ok(std::str::from_utf8(&buf[..len])?)
+}
+
+impl Database for Memcached {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let mut buf = [0; MAX_KEY_LEN];
+        let key = encode_key(key, &mut buf)?;
+        // memcached evicts and forgets on restart, a miss is an expected outcome
+        if self.client.get::<Vec<u8>>(key)?.is_none() {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut buf = [0; MAX_KEY_LEN];
+        self.client.set(encode_key(key, &mut buf)?, value, 0)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let mut buf = [0; MAX_KEY_LEN];
+        self.client.delete(encode_key(key, &mut buf)?)?;
+        Ok(())
+    }
+
+    fn read_misses(&self) -> Option<u64> {
+        Some(self.misses.load(Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,31 @@
+use crate::database::Database;
+
+/// Stores nothing, so a run measures key generation, timing and the rest of the harness
+pub struct Null;
+
+impl Database for Null {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        std::hint::black_box(key);
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        std::hint::black_box((key, value));
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        std::hint::black_box(key);
+        Ok(())
+    }
+
+    /// Finds no records
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        std::hint::black_box((start, count));
+        Ok(0)
+    }
+}
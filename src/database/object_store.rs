@@ -0,0 +1,176 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path;
+use object_store::{
+    Certificate, ClientOptions, ObjectStore, ObjectStoreExt, PutPayload, RetryConfig,
+};
+use std::fmt::Write;
+use tokio::runtime::Runtime;
+
+const DEFAULT_BUCKET: &str = "kvbencher";
+/// Region used with a custom endpoint and no region configured, MinIO ignores it
+const LOCAL_REGION: &str = "us-east-1";
+
+/// Objects in an S3 compatible store, one per key, named by the hex encoded key below
+/// `db.prefix`. The bucket has to exist.
+pub struct ObjectStoreDb {
+    store: AmazonS3,
+    prefix: Option<String>,
+    runtime: Runtime,
+}
+
+impl ObjectStoreDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported(
+            "object-store",
+            &["url", "endpoint", "region", "bucket", "prefix"],
+        )?;
+        // credentials and region from the environment, as for the AWS CLI
+        let mut builder = AmazonS3Builder::from_env();
+        let mut bucket = opts.properties.get("bucket").cloned();
+        let url = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(url), None) => Some(url.clone()),
+            (None, Some(endpoint)) => {
+                // the user and password are an access key id and secret
+                if let Some(user) = &endpoint.user {
+                    builder = builder.with_access_key_id(user);
+                }
+                if let Some(password) = &endpoint.password {
+                    builder = builder.with_secret_access_key(password);
+                }
+                if let Some(database) = &endpoint.database {
+                    if bucket.is_some() {
+                        return Err(ConfigError::new(
+                            "db.bucket",
+                            "cannot be combined with a bucket in db.endpoint",
+                        )
+                        .into());
+                    }
+                    bucket = Some(database.clone());
+                }
+                if let Some(pem) = endpoint.tls_ca_pem()? {
+                    let mut client = ClientOptions::new();
+                    for certificate in Certificate::from_pem_bundle(&pem)? {
+                        client = client.with_root_certificate(certificate);
+                    }
+                    builder = builder.with_client_options(client);
+                }
+                let default_port = if endpoint.tls { 443 } else { 80 };
+                Some(endpoint.url("object-store", "http", "https", default_port)?)
+            }
+            // the real service
+            (None, None) => None,
+        };
+        if let Some(url) = url {
+            // MinIO and most other stores take the bucket in the path, not the host name
+            builder = builder
+                .with_endpoint(url)
+                .with_allow_http(true)
+                .with_virtual_hosted_style_request(false)
+                .with_region(LOCAL_REGION);
+        }
+        if let Some(region) = opts.properties.get("region") {
+            builder = builder.with_region(region);
+        }
+        let bucket = bucket.unwrap_or_else(|| DEFAULT_BUCKET.to_string());
+        // a retried request would be measured as one slow operation
+        let store = builder
+            .with_bucket_name(&bucket)
+            .with_retry(RetryConfig {
+                max_retries: 0,
+                ..RetryConfig::default()
+            })
+            .build()
+            .with_context(|| format!("invalid object store settings for bucket {bucket}"))?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        Ok(ObjectStoreDb {
+            store,
+            prefix: opts.properties.get("prefix").cloned(),
+            runtime,
+        })
+    }
+
+    fn path(&self, key: &[u8]) -> Path {
+        let mut name = String::with_capacity(key.len() * 2);
+        for b in key {
+            let _ = write!(name, "{b:02x}");
+        }
+        match &self.prefix {
+            Some(prefix) => Path::from(prefix.as_str()).join(name),
+            None => Path::from(name),
+        }
+    }
+
+    async fn get_object(&self, key: &[u8]) -> anyhow::Result<()> {
+        match self.store.get(&self.path(key)).await {
+            // the body is read too, a get is only done once the value arrived
+            Ok(object) => {
+                object.bytes().await?;
+                Ok(())
+            }
+            Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn put_object(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let payload = PutPayload::from(value.to_vec());
+        self.store.put(&self.path(key), payload).await?;
+        Ok(())
+    }
+
+    async fn delete_object(&self, key: &[u8]) -> anyhow::Result<()> {
+        match self.store.delete(&self.path(key)).await {
+            Err(e) if !matches!(e, object_store::Error::NotFound { .. }) => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Database for ObjectStoreDb {
+    fn init(&self) -> anyhow::Result<()> {
+        // fails early on an unreachable store or a missing bucket
+        let prefix = self.prefix.as_deref().map(Path::from);
+        self.runtime
+            .block_on(self.store.list_with_delimiter(prefix.as_ref()))
+            .with_context(|| format!("failed to list {}", self.store))?;
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.get_object(key))
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.put_object(key, value))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.delete_object(key))
+    }
+
+    /// All requests in flight at once, each still its own HTTP request
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| async move {
+            match *op {
+                PipelinedOp::Get(key) => self.get_object(key).await,
+                PipelinedOp::Set(key, value) => self.put_object(key, value).await,
+                PipelinedOp::Delete(key) => self.delete_object(key).await,
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::database::{DataDir, Database, DbOptions};
+use anyhow::Context;
+use persy::{ByteVec, OpenOptions, Persy, ValueMode};
+use std::path::Path;
+
+const FILE_NAME: &str = "data.persy";
+/// Index mapping keys to values, one value per key
+const INDEX: &str = "data";
+
+pub struct PersyDb {
+    db: Persy,
+    dir: DataDir,
+}
+
+impl PersyDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("persy", &[])?;
+        let dir = DataDir::new(opts, "persy")?;
+        let path = dir.path().join(FILE_NAME);
+        let db = OpenOptions::new()
+            .create(true)
+            .open(&path)
+            .with_context(|| format!("failed to open persy database at {}", path.display()))?;
+        Ok(PersyDb { db, dir })
+    }
+}
+
+impl Database for PersyDb {
+    fn init(&self) -> anyhow::Result<()> {
+        // kept data already has the index
+        if self.db.exists_index(INDEX)? {
+            return Ok(());
+        }
+        let mut tx = self.db.begin()?;
+        tx.create_index::<ByteVec, ByteVec>(INDEX, ValueMode::Replace)?;
+        tx.prepare()?.commit()?;
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.db
+            .one::<ByteVec, ByteVec>(INDEX, &ByteVec::from(key))?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut tx = self.db.begin()?;
+        tx.put::<ByteVec, ByteVec>(INDEX, key.into(), value.into())?;
+        tx.prepare()?.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let mut tx = self.db.begin()?;
+        tx.remove::<ByteVec, ByteVec>(INDEX, key.into(), None)?;
+        tx.prepare()?.commit()?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let range = self
+            .db
+            .range::<ByteVec, ByteVec, _>(INDEX, ByteVec::from(start)..)?;
+        Ok(range.take(count).count())
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn preallocate(&self, bytes: u64) -> anyhow::Result<()> {
+        self.dir.preallocate(FILE_NAME, bytes)
+    }
+}
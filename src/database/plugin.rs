@@ -0,0 +1,153 @@
+use crate::database::{DataDir, Database, DbOptions};
+use crate::workload::ConfigError;
+use anyhow::{Context, bail};
+use libloading::Library;
+use std::ffi::{CString, c_char, c_int, c_void};
+use std::path::Path;
+
+// The shared object exports the vtable through a function, in C:
+//
+//   struct kvbencher_plugin {
+//       uint32_t abi_version; /* 1 */
+//       /* NULL on failure, the data dir is created and removed by kvbencher */
+//       void *(*open)(const char *data_dir, const char *config);
+//       int (*init)(void *db);
+//       int (*get)(void *db, const uint8_t *key, size_t key_len);
+//       int (*set)(void *db, const uint8_t *key, size_t key_len,
+//                  const uint8_t *value, size_t value_len);
+//       void (*close)(void *db);
+//   };
+//   const struct kvbencher_plugin *kvbencher_plugin(void);
+//
+// Functions other than open and close return 0 on success and an error code otherwise.
+// get and set are called from every run thread at once.
+
+const ABI_VERSION: u32 = 1;
+const ENTRY_POINT: &[u8] = b"kvbencher_plugin";
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Vtable {
+    abi_version: u32,
+    open: unsafe extern "C" fn(data_dir: *const c_char, config: *const c_char) -> *mut c_void,
+    init: unsafe extern "C" fn(db: *mut c_void) -> c_int,
+    get: unsafe extern "C" fn(db: *mut c_void, key: *const u8, key_len: usize) -> c_int,
+    set: unsafe extern "C" fn(
+        db: *mut c_void,
+        key: *const u8,
+        key_len: usize,
+        value: *const u8,
+        value_len: usize,
+    ) -> c_int,
+    close: unsafe extern "C" fn(db: *mut c_void),
+}
+
+pub struct Plugin {
+    db: *mut c_void,
+    vtable: Vtable,
+    dir: DataDir,
+    // unloaded last, after `close`
+    _library: Library,
+}
+
+// SAFETY: the plugin interface requires get and set to be callable from any thread at once
+unsafe impl Send for Plugin {}
+unsafe impl Sync for Plugin {}
+
+impl Plugin {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("plugin", &["path", "config"])?;
+        let path = opts.properties.get("path").ok_or_else(|| {
+            ConfigError::new(
+                "db.path",
+                "set the shared object to load, e.g. `-p db.path=./libengine.so`",
+            )
+        })?;
+        let config = CString::new(opts.properties.get("config").map_or("", String::as_str))
+            .map_err(|_| ConfigError::new("db.config", "cannot contain a NUL byte"))?;
+        // SAFETY: loading runs the library's initializers, the user vouches for it by
+        // naming it
+        let library =
+            unsafe { Library::new(path) }.with_context(|| format!("failed to load {path}"))?;
+        // SAFETY: the entry point has the signature the plugin interface defines
+        let vtable = unsafe {
+            let entry = library
+                .get::<unsafe extern "C" fn() -> *const Vtable>(ENTRY_POINT)
+                .with_context(|| format!("{path} does not export kvbencher_plugin"))?;
+            entry().as_ref().copied()
+        }
+        .with_context(|| format!("kvbencher_plugin of {path} returned NULL"))?;
+        if vtable.abi_version != ABI_VERSION {
+            bail!(
+                "{path} implements plugin interface version {}, kvbencher supports {ABI_VERSION}",
+                vtable.abi_version
+            );
+        }
+
+        let dir = DataDir::new(opts, "plugin")?;
+        let data_dir = dir
+            .path()
+            .to_str()
+            .and_then(|p| CString::new(p).ok())
+            .with_context(|| format!("data dir {} is not valid UTF-8", dir.path().display()))?;
+        // SAFETY: both strings outlive the call
+        let db = unsafe { (vtable.open)(data_dir.as_ptr(), config.as_ptr()) };
+        if db.is_null() {
+            bail!(
+                "{path} failed to open a database in {}",
+                dir.path().display()
+            );
+        }
+        Ok(Plugin {
+            db,
+            vtable,
+            dir,
+            _library: library,
+        })
+    }
+}
+
+impl Drop for Plugin {
+    fn drop(&mut self) {
+        // SAFETY: `db` came from `open` and is not used after this
+        unsafe { (self.vtable.close)(self.db) }
+    }
+}
+
+fn check(op: &str, code: c_int) -> anyhow::Result<()> {
+    if code != 0 {
+        bail!("plugin {op} failed with code {code}");
+    }
+    Ok(())
+}
+
+impl Database for Plugin {
+    fn init(&self) -> anyhow::Result<()> {
+        // SAFETY: `db` is open until drop
+        check("init", unsafe { (self.vtable.init)(self.db) })
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        // SAFETY: `db` is open until drop, the key outlives the call
+        check("get", unsafe {
+            (self.vtable.get)(self.db, key.as_ptr(), key.len())
+        })
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        // SAFETY: `db` is open until drop, the key and value outlive the call
+        check("set", unsafe {
+            (self.vtable.set)(
+                self.db,
+                key.as_ptr(),
+                key.len(),
+                value.as_ptr(),
+                value.len(),
+            )
+        })
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+}
@@ -0,0 +1,174 @@
+use crate::database::{Database, DbOptions, Endpoint};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use postgres::config::SslMode;
+use postgres::{Client, Config, NoTls, Statement};
+use std::sync::Mutex;
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+const DEFAULT_URL: &str = "host=127.0.0.1 user=postgres";
+const DEFAULT_PORT: u16 = 5432;
+const DEFAULT_USER: &str = "postgres";
+const CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS kvbencher (key bytea PRIMARY KEY, value bytea NOT NULL)";
+const GET: &str = "SELECT value FROM kvbencher WHERE key = $1";
+const GET_FOR_UPDATE: &str = "SELECT value FROM kvbencher WHERE key = $1 FOR UPDATE";
+const DELETE: &str = "DELETE FROM kvbencher WHERE key = $1";
+const SCAN: &str = "SELECT key, value FROM kvbencher WHERE key >= $1 ORDER BY key LIMIT $2";
+const SET: &str = "INSERT INTO kvbencher (key, value) VALUES ($1, $2) \
+                   ON CONFLICT (key) DO UPDATE SET value = excluded.value";
+
+pub struct Postgres {
+    config: Config,
+    // set when the endpoint asks for TLS
+    tls: Option<MakeRustlsConnect>,
+    // idle connections, a thread takes one per operation and opens another when none is left
+    idle: Mutex<Vec<Connection>>,
+}
+
+/// A connection with the statements prepared once for it
+struct Connection {
+    client: Client,
+    get: Statement,
+    get_for_update: Statement,
+    set: Statement,
+    delete: Statement,
+    scan: Statement,
+}
+
+impl Postgres {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("postgres", &["url", "endpoint"])?;
+        let (config, tls) = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(url), None) => (
+                url.parse()
+                    .with_context(|| format!("invalid postgres url `{url}`"))?,
+                None,
+            ),
+            (None, Some(endpoint)) => config_for(&endpoint)?,
+            (None, None) => (DEFAULT_URL.parse()?, None),
+        };
+        Ok(Postgres {
+            config,
+            tls,
+            idle: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn connect(&self) -> anyhow::Result<Client> {
+        let client = match &self.tls {
+            Some(tls) => self.config.connect(tls.clone()),
+            None => self.config.connect(NoTls),
+        };
+        client.with_context(|| {
+            format!(
+                "failed to connect to postgres at {:?}",
+                self.config.get_hosts()
+            )
+        })
+    }
+
+    /// Runs `f` on an idle connection, returning it to the pool unless `f` failed
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> Result<T, postgres::Error>,
+    ) -> anyhow::Result<T> {
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let mut conn = match idle {
+            Some(conn) => conn,
+            None => {
+                let mut client = self.connect()?;
+                let get = client.prepare(GET)?;
+                let get_for_update = client.prepare(GET_FOR_UPDATE)?;
+                let set = client.prepare(SET)?;
+                let delete = client.prepare(DELETE)?;
+                let scan = client.prepare(SCAN)?;
+                Connection {
+                    client,
+                    get,
+                    get_for_update,
+                    set,
+                    delete,
+                    scan,
+                }
+            }
+        };
+        // a failed connection may be broken, it is dropped rather than reused
+        let out = f(&mut conn)?;
+        self.idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(conn);
+        Ok(out)
+    }
+}
+
+fn config_for(endpoint: &Endpoint) -> anyhow::Result<(Config, Option<MakeRustlsConnect>)> {
+    let mut config = Config::new();
+    // several hosts are tried in order until one accepts the connection
+    for host in &endpoint.hosts {
+        config
+            .host(&host.name)
+            .port(host.port.unwrap_or(DEFAULT_PORT));
+    }
+    config.user(endpoint.user.as_deref().unwrap_or(DEFAULT_USER));
+    if let Some(password) = &endpoint.password {
+        config.password(password);
+    }
+    if let Some(database) = &endpoint.database {
+        config.dbname(database);
+    }
+    if !endpoint.tls {
+        return Ok((config, None));
+    }
+    config.ssl_mode(SslMode::Require);
+    let tls = MakeRustlsConnect::new(endpoint.rustls_config()?);
+    Ok((config, Some(tls)))
+}
+
+impl Database for Postgres {
+    fn init(&self) -> anyhow::Result<()> {
+        // statements are prepared against the table, so it has to exist first
+        self.connect()?.batch_execute(CREATE_TABLE)?;
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| conn.client.query_opt(&conn.get, &[&key]))?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| conn.client.execute(&conn.set, &[&key, &value]))?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| conn.client.execute(&conn.delete, &[&key]))?;
+        Ok(())
+    }
+
+    /// One query walking the primary key index
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let rows =
+            self.with_conn(|conn| conn.client.query(&conn.scan, &[&start, &(count as i64)]))?;
+        Ok(rows.len())
+    }
+
+    /// One transaction, the row locked by the read until the write commits
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| {
+            let mut tx = conn.client.transaction()?;
+            tx.query_opt(&conn.get_for_update, &[&key])?;
+            tx.execute(&conn.set, &[&key, &value])?;
+            tx.commit()
+        })
+    }
+}
@@ -1,44 +1,275 @@
-use redb::{Database, ReadableDatabase, TableDefinition};
-use tempfile::NamedTempFile;
+#[cfg(target_os = "linux")]
+use crate::database::direct_io::DirectFile;
+use crate::database::{DataDir, DbOptions};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use redb::{Builder, Database, Durability, ReadableDatabase, ReadableTable, TableDefinition};
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
 
 static TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("data");
+const FILE_NAME: &str = "data.redb";
+
 pub struct Redb {
-    _f: NamedTempFile,
     db: Database,
+    durability: Durability,
+    // names of the tables made by `create_tables`
+    tables: OnceLock<Vec<String>>,
+    dir: DataDir,
 }
 
 impl Redb {
-    pub fn new() -> Self {
-        let f = NamedTempFile::new().unwrap();
-        let db = Database::create(f.path()).unwrap();
-        Redb { _f: f, db }
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("redb", &["direct_io", "durability", "cache_bytes"])?;
+        let direct_io = opts.parse("direct_io")?.unwrap_or(false);
+        let durability = parse_durability(opts)?;
+        let mut builder = Builder::new();
+        if let Some(bytes) = opts.parse("cache_bytes")? {
+            builder.set_cache_size(bytes);
+        }
+
+        let dir = DataDir::new(opts, "redb")?;
+        let path = dir.path().join(FILE_NAME);
+        let db = if direct_io {
+            create_direct(&builder, &path)?
+        } else {
+            builder
+                .create(&path)
+                .with_context(|| format!("failed to create redb database at {}", path.display()))?
+        };
+        Ok(Redb {
+            db,
+            durability,
+            tables: OnceLock::new(),
+            dir,
+        })
     }
 }
 
-impl crate::database::Database for Redb {
-    fn init(&self) -> anyhow::Result<()> {
+/// `db.durability`, `immediate` (redb's default, an fsync per commit) or `none`
+fn parse_durability(opts: &DbOptions) -> Result<Durability, ConfigError> {
+    match opts.properties.get("durability").map(String::as_str) {
+        None | Some("immediate") => Ok(Durability::Immediate),
+        Some("none") => Ok(Durability::None),
+        Some("eventual") => Err(ConfigError::new(
+            "db.durability",
+            "redb 3 has no eventual durability, use `none` to commit without an fsync",
+        )),
+        Some(other) => Err(ConfigError::new(
+            "db.durability",
+            format!("`{other}` is not supported, expected `immediate` or `none`"),
+        )),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn create_direct(builder: &Builder, path: &Path) -> anyhow::Result<Database> {
+    let file = DirectFile::open(path)
+        .with_context(|| format!("failed to open {} with O_DIRECT", path.display()))?;
+    builder
+        .create_with_backend(DirectBackend(file))
+        .with_context(|| format!("failed to create redb database at {}", path.display()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn create_direct(_builder: &Builder, _path: &Path) -> anyhow::Result<Database> {
+    anyhow::bail!("db.direct_io is only supported on Linux")
+}
+
+impl Drop for Redb {
+    fn drop(&mut self) {
+        // commits without durability are only persisted by a later durable one
+        if self.dir.keep_data()
+            && matches!(self.durability, Durability::None)
+            && let Ok(tx) = self.db.begin_write()
+        {
+            let _ = tx.commit();
+        }
+    }
+}
+
+/// redb storage on a file opened with O_DIRECT
+#[cfg(target_os = "linux")]
+#[derive(Debug)]
+struct DirectBackend(DirectFile);
+
+#[cfg(target_os = "linux")]
+impl redb::StorageBackend for DirectBackend {
+    fn len(&self) -> Result<u64, io::Error> {
+        self.0.len()
+    }
+
+    fn read(&self, offset: u64, out: &mut [u8]) -> Result<(), io::Error> {
+        self.0.read(offset, out)
+    }
+
+    fn set_len(&self, len: u64) -> Result<(), io::Error> {
+        self.0.set_len(len)
+    }
+
+    fn sync_data(&self) -> Result<(), io::Error> {
+        self.0.sync_data()
+    }
+
+    fn write(&self, offset: u64, data: &[u8]) -> Result<(), io::Error> {
+        self.0.write(offset, data)
+    }
+}
+
+impl Redb {
+    fn create_table(&self, table: TableDefinition<&[u8], &[u8]>) -> anyhow::Result<()> {
         let tx = self.db.begin_write()?;
         {
-            let _ = tx.open_table(TABLE)?;
+            let _ = tx.open_table(table)?;
         }
         tx.commit()?;
         Ok(())
     }
 
-    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+    fn get_from(&self, table: TableDefinition<&[u8], &[u8]>, key: &[u8]) -> anyhow::Result<()> {
         let tx = self.db.begin_read()?;
-        let t = tx.open_table(TABLE)?;
+        let t = tx.open_table(table)?;
         t.get(key)?;
         Ok(())
     }
 
-    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
-        let tx = self.db.begin_write()?;
+    fn set_into(
+        &self,
+        table: TableDefinition<&[u8], &[u8]>,
+        key: &[u8],
+        value: &[u8],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.db.begin_write()?;
+        tx.set_durability(self.durability)?;
         {
-            let mut t = tx.open_table(TABLE)?;
+            let mut t = tx.open_table(table)?;
             t.insert(key, value)?;
         }
         tx.commit()?;
         Ok(())
     }
+
+    fn remove_from(&self, table: TableDefinition<&[u8], &[u8]>, key: &[u8]) -> anyhow::Result<()> {
+        let mut tx = self.db.begin_write()?;
+        tx.set_durability(self.durability)?;
+        {
+            let mut t = tx.open_table(table)?;
+            t.remove(key)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn scan_from(
+        &self,
+        table: TableDefinition<&[u8], &[u8]>,
+        start: &[u8],
+        count: usize,
+    ) -> anyhow::Result<usize> {
+        let tx = self.db.begin_read()?;
+        let t = tx.open_table(table)?;
+        let mut records = 0;
+        for entry in t.range(start..)?.take(count) {
+            entry?;
+            records += 1;
+        }
+        Ok(records)
+    }
+
+    fn read_modify_write_in_table(
+        &self,
+        table: TableDefinition<&[u8], &[u8]>,
+        key: &[u8],
+        value: &[u8],
+    ) -> anyhow::Result<()> {
+        let mut tx = self.db.begin_write()?;
+        tx.set_durability(self.durability)?;
+        {
+            let mut t = tx.open_table(table)?;
+            t.get(key)?;
+            t.insert(key, value)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn table(
+        &self,
+        table: u32,
+    ) -> anyhow::Result<TableDefinition<'_, &'static [u8], &'static [u8]>> {
+        let name = self
+            .tables
+            .get()
+            .and_then(|names| names.get(table as usize))
+            .with_context(|| format!("redb table {table} was not created"))?;
+        Ok(TableDefinition::new(name))
+    }
+}
+
+impl crate::database::Database for Redb {
+    fn init(&self) -> anyhow::Result<()> {
+        self.create_table(TABLE)
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.get_from(TABLE, key)
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.set_into(TABLE, key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.remove_from(TABLE, key)
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        self.scan_from(TABLE, start, count)
+    }
+
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.read_modify_write_in_table(TABLE, key, value)
+    }
+
+    fn supports_tables(&self) -> bool {
+        true
+    }
+
+    fn create_tables(&self, count: u32) -> anyhow::Result<()> {
+        let names = (0..count).map(|i| format!("data-{i}")).collect::<Vec<_>>();
+        for name in &names {
+            self.create_table(TableDefinition::new(name))?;
+        }
+        let _ = self.tables.set(names);
+        Ok(())
+    }
+
+    fn get_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        self.get_from(self.table(table)?, key)
+    }
+
+    fn set_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.set_into(self.table(table)?, key, value)
+    }
+
+    fn delete_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        self.remove_from(self.table(table)?, key)
+    }
+
+    fn scan_in(&self, table: u32, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        self.scan_from(self.table(table)?, start, count)
+    }
+
+    fn read_modify_write_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.read_modify_write_in_table(self.table(table)?, key, value)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn preallocate(&self, bytes: u64) -> anyhow::Result<()> {
+        self.dir.preallocate(FILE_NAME, bytes)
+    }
 }
@@ -0,0 +1,140 @@
+use crate::database::endpoint::install_crypto_provider;
+use crate::database::{Database, DbOptions, Endpoint, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use redis::{
+    Client, Connection, ConnectionAddr, IntoConnectionInfo, RedisConnectionInfo, TlsCertificates,
+};
+use std::sync::Mutex;
+
+const DEFAULT_URL: &str = "redis://127.0.0.1:6379/";
+const DEFAULT_PORT: u16 = 6379;
+
+pub struct Redis {
+    client: Client,
+    // idle connections, a thread takes one per operation and opens another when none is left
+    idle: Mutex<Vec<Connection>>,
+}
+
+impl Redis {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("redis", &["url", "endpoint"])?;
+        let client = match (opts.properties.get("url"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.url",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(url), None) => {
+                Client::open(url.as_str()).with_context(|| format!("invalid redis url `{url}`"))?
+            }
+            (None, Some(endpoint)) => client_for(&endpoint)?,
+            (None, None) => Client::open(DEFAULT_URL)?,
+        };
+        Ok(Redis {
+            client,
+            idle: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Runs `f` on an idle connection, returning it to the pool unless `f` failed
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&mut Connection) -> redis::RedisResult<T>,
+    ) -> anyhow::Result<T> {
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let mut conn = match idle {
+            Some(conn) => conn,
+            None => self
+                .client
+                .get_connection()
+                .with_context(|| format!("failed to connect to redis at {}", self.addr()))?,
+        };
+        // a failed connection may be broken, it is dropped rather than reused
+        let out = f(&mut conn)?;
+        self.idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(conn);
+        Ok(out)
+    }
+
+    fn addr(&self) -> String {
+        self.client.get_connection_info().addr().to_string()
+    }
+}
+
+fn client_for(endpoint: &Endpoint) -> anyhow::Result<Client> {
+    let host = endpoint.host("redis")?;
+    let (host, port) = (host.name.clone(), host.port.unwrap_or(DEFAULT_PORT));
+    let addr = if endpoint.tls {
+        ConnectionAddr::TcpTls {
+            host,
+            port,
+            insecure: false,
+            tls_params: None,
+        }
+    } else {
+        ConnectionAddr::Tcp(host, port)
+    };
+    let mut settings = RedisConnectionInfo::default();
+    if let Some(user) = &endpoint.user {
+        settings = settings.set_username(user);
+    }
+    if let Some(password) = &endpoint.password {
+        settings = settings.set_password(password);
+    }
+    if let Some(db) = &endpoint.database {
+        let db = db.parse().map_err(|_| {
+            ConfigError::new(
+                "db.endpoint",
+                format!("redis databases are numbers, not `{db}`"),
+            )
+        })?;
+        settings = settings.set_db(db);
+    }
+    let info = addr.into_connection_info()?.set_redis_settings(settings);
+    if !endpoint.tls {
+        return Ok(Client::open(info)?);
+    }
+    install_crypto_provider();
+    let certificates = TlsCertificates {
+        client_tls: None,
+        root_cert: endpoint.tls_ca_pem()?,
+    };
+    Ok(Client::build_with_tls(info, certificates)?)
+}
+
+impl Database for Redis {
+    fn init(&self) -> anyhow::Result<()> {
+        // fails early on an unreachable server rather than in the first loaded key
+        self.with_conn(|conn| redis::cmd("PING").query::<()>(conn))
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| redis::cmd("GET").arg(key).query::<Option<Vec<u8>>>(conn))?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| redis::cmd("SET").arg(key).arg(value).query::<()>(conn))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| redis::cmd("DEL").arg(key).query::<()>(conn))
+    }
+
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let mut pipe = redis::pipe();
+        for op in ops {
+            match *op {
+                PipelinedOp::Get(key) => pipe.cmd("GET").arg(key).ignore(),
+                PipelinedOp::Set(key, value) => pipe.cmd("SET").arg(key).arg(value).ignore(),
+                PipelinedOp::Delete(key) => pipe.cmd("DEL").arg(key).ignore(),
+            };
+        }
+        self.with_conn(|conn| pipe.query::<()>(conn))
+    }
+}
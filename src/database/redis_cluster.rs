@@ -0,0 +1,139 @@
+use crate::database::endpoint::install_crypto_provider;
+use crate::database::{Database, DbOptions, Endpoint, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use redis::cluster::{ClusterClient, ClusterClientBuilder, ClusterConnection};
+use redis::cluster_read_routing::RandomReplicaStrategy;
+use redis::{TlsCertificates, TlsMode};
+use std::sync::Mutex;
+
+const DEFAULT_NODES: &str = "redis://127.0.0.1:6379/";
+const DEFAULT_PORT: u16 = 6379;
+
+/// A Redis Cluster (or Garnet in cluster mode), every key sent to the node owning its slot.
+/// The nodes given are only seeds, the rest of the topology is read from `CLUSTER SLOTS`.
+pub struct RedisCluster {
+    client: ClusterClient,
+    // idle connections, a thread takes one per operation and opens another when none is left
+    idle: Mutex<Vec<ClusterConnection>>,
+    seeds: String,
+}
+
+impl RedisCluster {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported(
+            "redis-cluster",
+            &["nodes", "endpoint", "read_from_replicas"],
+        )?;
+        let (builder, seeds) = match (opts.properties.get("nodes"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.nodes",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(nodes), None) => builder_for_nodes(nodes)?,
+            (None, Some(endpoint)) => builder_for(&endpoint)?,
+            (None, None) => builder_for_nodes(DEFAULT_NODES)?,
+        };
+        let builder = match opts.parse("read_from_replicas")?.unwrap_or(false) {
+            true => builder.read_routing_strategy(RandomReplicaStrategy),
+            false => builder,
+        };
+        let client = builder
+            .build()
+            .with_context(|| format!("invalid redis cluster nodes `{seeds}`"))?;
+        Ok(RedisCluster {
+            client,
+            idle: Mutex::new(Vec::new()),
+            seeds,
+        })
+    }
+
+    /// Runs `f` on an idle connection, returning it to the pool unless `f` failed
+    fn with_conn<T>(
+        &self,
+        f: impl FnOnce(&mut ClusterConnection) -> redis::RedisResult<T>,
+    ) -> anyhow::Result<T> {
+        let idle = self.idle.lock().unwrap_or_else(|e| e.into_inner()).pop();
+        let mut conn = match idle {
+            Some(conn) => conn,
+            None => self.client.get_connection().with_context(|| {
+                format!("failed to connect to the redis cluster at {}", self.seeds)
+            })?,
+        };
+        // a failed connection may be broken, it is dropped rather than reused
+        let out = f(&mut conn)?;
+        self.idle
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(conn);
+        Ok(out)
+    }
+}
+
+/// `url,...`, each a `redis://` or `rediss://` URL of one seed node
+fn builder_for_nodes(nodes: &str) -> Result<(ClusterClientBuilder, String), ConfigError> {
+    let nodes: Vec<_> = nodes.split(',').map(str::trim).collect();
+    if nodes.iter().any(|node| node.starts_with("rediss://")) {
+        install_crypto_provider();
+    }
+    Ok((ClusterClientBuilder::new(nodes.clone()), nodes.join(",")))
+}
+
+fn builder_for(endpoint: &Endpoint) -> anyhow::Result<(ClusterClientBuilder, String)> {
+    // a cluster only has database 0
+    endpoint.reject_database("redis-cluster")?;
+    let addrs = endpoint.addrs(DEFAULT_PORT);
+    let scheme = if endpoint.tls { "rediss" } else { "redis" };
+    let mut builder =
+        ClusterClientBuilder::new(addrs.iter().map(|addr| format!("{scheme}://{addr}/")));
+    if let Some(user) = &endpoint.user {
+        builder = builder.username(user);
+    }
+    if let Some(password) = &endpoint.password {
+        builder = builder.password(password);
+    }
+    if endpoint.tls {
+        install_crypto_provider();
+        builder = builder.tls(TlsMode::Secure).certs(TlsCertificates {
+            client_tls: None,
+            root_cert: endpoint.tls_ca_pem()?,
+        });
+    }
+    Ok((builder, addrs.join(",")))
+}
+
+impl Database for RedisCluster {
+    fn init(&self) -> anyhow::Result<()> {
+        // fails early on an unreachable cluster rather than in the first loaded key
+        self.with_conn(|conn| redis::cmd("PING").query::<()>(conn))
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| redis::cmd("GET").arg(key).query::<Option<Vec<u8>>>(conn))?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| redis::cmd("SET").arg(key).arg(value).query::<()>(conn))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.with_conn(|conn| redis::cmd("DEL").arg(key).query::<()>(conn))
+    }
+
+    /// Split by node, each node's share sent as one batch
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let mut pipe = redis::cluster::cluster_pipe();
+        for op in ops {
+            match *op {
+                PipelinedOp::Get(key) => pipe.cmd("GET").arg(key).ignore(),
+                PipelinedOp::Set(key, value) => pipe.cmd("SET").arg(key).arg(value).ignore(),
+                PipelinedOp::Delete(key) => pipe.cmd("DEL").arg(key).ignore(),
+            };
+        }
+        self.with_conn(|conn| pipe.query::<()>(conn))
+    }
+}
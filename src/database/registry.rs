@@ -0,0 +1,125 @@
+use crate::DatabaseType;
+use crate::container::ContainerSpec;
+use crate::database::{
+    Database, DbOptions, container_spec, get_db, get_db_name, is_file_backed, is_networked,
+};
+use anyhow::{Result, bail};
+use clap::builder::{PossibleValue, PossibleValuesParser, TypedValueParser};
+use clap::{Arg, Command, ValueEnum};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::sync::{Arc, RwLock};
+
+type Factory = Arc<dyn Fn(&DbOptions) -> Result<Arc<dyn Database>> + Send + Sync>;
+
+/// Backends added by crates embedding kvbencher, by name
+static REGISTRY: RwLock<BTreeMap<&'static str, Factory>> = RwLock::new(BTreeMap::new());
+
+/// Makes `name` selectable wherever the CLI takes a database, opening it with `factory` and the
+/// `db.*` properties. Call it before `cli::main`. Registering a name again replaces its factory,
+/// the names of built-in backends cannot be taken.
+pub fn register_database<F>(name: &'static str, factory: F) -> Result<()>
+where
+    F: Fn(&DbOptions) -> Result<Arc<dyn Database>> + Send + Sync + 'static,
+{
+    if DatabaseType::from_str(name, true).is_ok() {
+        bail!("`{name}` is a built-in database");
+    }
+    REGISTRY
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name, Arc::new(factory));
+    Ok(())
+}
+
+/// Names passed to `register_database`, sorted
+pub fn registered_databases() -> Vec<&'static str> {
+    REGISTRY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .keys()
+        .copied()
+        .collect()
+}
+
+/// A database as named on the command line, built in or registered
+#[derive(Clone, Copy)]
+pub enum Backend {
+    BuiltIn(DatabaseType),
+    Registered(&'static str),
+}
+
+impl Backend {
+    pub fn open(&self, opts: &DbOptions) -> Result<Arc<dyn Database>> {
+        match self {
+            Backend::BuiltIn(db) => get_db(*db, opts),
+            Backend::Registered(name) => {
+                let factory = REGISTRY
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .get(name)
+                    .cloned();
+                match factory {
+                    Some(factory) => factory(opts),
+                    None => bail!("no database registered as `{name}`"),
+                }
+            }
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Backend::BuiltIn(db) => get_db_name(*db),
+            Backend::Registered(name) => name.to_string(),
+        }
+    }
+
+    /// Registered backends count as in-process without a data dir, as far as the CLI can tell
+    pub fn is_file_backed(&self) -> bool {
+        matches!(self, Backend::BuiltIn(db) if is_file_backed(*db))
+    }
+
+    pub fn is_networked(&self) -> bool {
+        matches!(self, Backend::BuiltIn(db) if is_networked(*db))
+    }
+
+    pub fn container_spec(&self) -> Option<ContainerSpec> {
+        match self {
+            Backend::BuiltIn(db) => container_spec(*db),
+            Backend::Registered(_) => None,
+        }
+    }
+}
+
+/// Parses a `Backend`, offering the built-in and the registered names as possible values
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BackendParser;
+
+impl TypedValueParser for BackendParser {
+    type Value = Backend;
+
+    fn parse_ref(
+        &self,
+        cmd: &Command,
+        arg: Option<&Arg>,
+        value: &OsStr,
+    ) -> clap::error::Result<Backend> {
+        let values = self.possible_values().into_iter().flatten();
+        let name = PossibleValuesParser::new(values).parse_ref(cmd, arg, value)?;
+        if let Ok(db) = DatabaseType::from_str(&name, false) {
+            return Ok(Backend::BuiltIn(db));
+        }
+        let registered = registered_databases().into_iter().find(|&r| r == name);
+        Ok(Backend::Registered(
+            registered.expect("accepted as a possible value"),
+        ))
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let built_in = DatabaseType::value_variants()
+            .iter()
+            .filter_map(ValueEnum::to_possible_value);
+        let registered = registered_databases().into_iter().map(PossibleValue::new);
+        Some(Box::new(built_in.chain(registered)))
+    }
+}
@@ -0,0 +1,235 @@
+use crate::database::{DataDir, Database, DbOptions, EngineActivity};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use rocksdb::{
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBAccess, DBCompressionType,
+    DBRawIteratorWithThreadMode, DBWithThreadMode, MultiThreaded, Options, WriteOptions,
+    properties,
+};
+use std::path::Path;
+use std::sync::OnceLock;
+
+// multi-threaded mode so column families can be created through a shared reference
+type DB = DBWithThreadMode<MultiThreaded>;
+
+pub struct RocksDb {
+    db: DB,
+    options: Options,
+    write_options: WriteOptions,
+    // column families made by `create_tables`
+    tables: OnceLock<Vec<String>>,
+    dir: DataDir,
+}
+
+impl RocksDb {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("rocksdb", PROPERTIES)?;
+        let (options, write_options) = parse_options(opts)?;
+
+        let dir = DataDir::new(opts, "rocksdb")?;
+        // a kept data dir may hold column families from an earlier load, which must all be opened
+        let families = DB::list_cf(&options, dir.path()).unwrap_or_default();
+        let descriptors = families
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name, options.clone()));
+        let db = DB::open_cf_descriptors(&options, dir.path(), descriptors).with_context(|| {
+            format!(
+                "failed to open rocksdb database at {}",
+                dir.path().display()
+            )
+        })?;
+        Ok(RocksDb {
+            db,
+            options,
+            write_options,
+            tables: OnceLock::new(),
+            dir,
+        })
+    }
+
+    fn table(&self, table: u32) -> anyhow::Result<std::sync::Arc<rocksdb::BoundColumnFamily<'_>>> {
+        self.tables
+            .get()
+            .and_then(|names| names.get(table as usize))
+            .and_then(|name| self.db.cf_handle(name))
+            .with_context(|| format!("rocksdb column family {table} was not created"))
+    }
+
+    fn int_property(&self, name: &properties::PropName) -> Option<u64> {
+        self.db.property_int_value(name).ok().flatten()
+    }
+}
+
+/// Properties understood by both RocksDB variants
+pub(super) const PROPERTIES: &[&str] = &[
+    "block_cache_bytes",
+    "write_buffer_bytes",
+    "compression",
+    "sync",
+    "direct_io",
+];
+
+/// Database and write options from the `db.*` properties in `PROPERTIES`
+pub(super) fn parse_options(opts: &DbOptions) -> Result<(Options, WriteOptions), ConfigError> {
+    let mut options = Options::default();
+    options.create_if_missing(true);
+    // background flushes and compactions on every core, as in most production setups
+    options
+        .increase_parallelism(std::thread::available_parallelism().map_or(1, |n| n.get()) as i32);
+    if let Some(bytes) = opts.parse("block_cache_bytes")? {
+        let mut table = BlockBasedOptions::default();
+        table.set_block_cache(&Cache::new_lru_cache(bytes));
+        options.set_block_based_table_factory(&table);
+    }
+    if let Some(bytes) = opts.parse("write_buffer_bytes")? {
+        options.set_write_buffer_size(bytes);
+    }
+    if let Some(compression) = opts.properties.get("compression") {
+        options.set_compression_type(parse_compression(compression)?);
+    }
+    // O_DIRECT for reads and for flush and compaction writes, bypassing the page cache
+    if opts.parse("direct_io")?.unwrap_or(false) {
+        options.set_use_direct_reads(true);
+        options.set_use_direct_io_for_flush_and_compaction(true);
+    }
+    let mut write_options = WriteOptions::default();
+    // an fsync of the WAL per write, off by default like in RocksDB
+    write_options.set_sync(opts.parse("sync")?.unwrap_or(false));
+    Ok((options, write_options))
+}
+
+/// Background activity from the integer properties of either variant
+pub(super) fn engine_activity(
+    int_property: impl Fn(&properties::PropName) -> Option<u64>,
+) -> EngineActivity {
+    EngineActivity {
+        flushes_running: int_property(properties::NUM_RUNNING_FLUSHES),
+        compactions_running: int_property(properties::NUM_RUNNING_COMPACTIONS),
+        pending_compaction_bytes: int_property(properties::ESTIMATE_PENDING_COMPACTION_BYTES),
+        write_stalled: int_property(properties::ACTUAL_DELAYED_WRITE_RATE)
+            .zip(int_property(properties::IS_WRITE_STOPPED))
+            .map(|(delayed_rate, stopped)| delayed_rate > 0 || stopped > 0),
+        size_on_disk_bytes: int_property(properties::TOTAL_SST_FILES_SIZE),
+    }
+}
+
+/// Reads up to `count` entries from `start` on through a raw iterator of either variant
+pub(super) fn scan_from<D: DBAccess>(
+    mut iter: DBRawIteratorWithThreadMode<'_, D>,
+    start: &[u8],
+    count: usize,
+) -> anyhow::Result<usize> {
+    iter.seek(start);
+    let mut records = 0;
+    while records < count && iter.valid() {
+        iter.value();
+        records += 1;
+        iter.next();
+    }
+    iter.status()?;
+    Ok(records)
+}
+
+fn parse_compression(name: &str) -> Result<DBCompressionType, ConfigError> {
+    Ok(match name {
+        "none" => DBCompressionType::None,
+        "snappy" => DBCompressionType::Snappy,
+        "zlib" => DBCompressionType::Zlib,
+        "bz2" => DBCompressionType::Bz2,
+        "lz4" => DBCompressionType::Lz4,
+        "lz4hc" => DBCompressionType::Lz4hc,
+        "zstd" => DBCompressionType::Zstd,
+        other => {
+            return Err(ConfigError::new(
+                "db.compression",
+                format!(
+                    "`{other}` is not supported, expected one of none, snappy, zlib, bz2, lz4, \
+                     lz4hc or zstd"
+                ),
+            ));
+        }
+    })
+}
+
+impl Drop for RocksDb {
+    fn drop(&mut self) {
+        if self.dir.keep_data() {
+            let _ = self.db.flush();
+        }
+    }
+}
+
+impl Database for RocksDb {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.db.get_pinned(key)?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.db.put_opt(key, value, &self.write_options)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.db.delete_opt(key, &self.write_options)?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        scan_from(self.db.raw_iterator(), start, count)
+    }
+
+    fn supports_tables(&self) -> bool {
+        true
+    }
+
+    fn create_tables(&self, count: u32) -> anyhow::Result<()> {
+        let names = (0..count).map(|i| format!("data-{i}")).collect::<Vec<_>>();
+        for name in &names {
+            if self.db.cf_handle(name).is_none() {
+                self.db.create_cf(name, &self.options)?;
+            }
+        }
+        let _ = self.tables.set(names);
+        Ok(())
+    }
+
+    fn get_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        self.db.get_pinned_cf(&self.table(table)?, key)?;
+        Ok(())
+    }
+
+    fn set_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.db
+            .put_cf_opt(&self.table(table)?, key, value, &self.write_options)?;
+        Ok(())
+    }
+
+    fn delete_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        self.db
+            .delete_cf_opt(&self.table(table)?, key, &self.write_options)?;
+        Ok(())
+    }
+
+    fn scan_in(&self, table: u32, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        scan_from(self.db.raw_iterator_cf(&self.table(table)?), start, count)
+    }
+
+    /// Without transactions, a read and then a write of the key's column family
+    fn read_modify_write_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.get_in(table, key)?;
+        self.set_in(table, key, value)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        Some(engine_activity(|name| self.int_property(name)))
+    }
+}
@@ -0,0 +1,203 @@
+use crate::database::rocksdb::{PROPERTIES, engine_activity, parse_options, scan_from};
+use crate::database::{DataDir, Database, DbOptions, EngineActivity};
+use anyhow::Context;
+use rocksdb::{
+    BoundColumnFamily, ColumnFamilyDescriptor, MultiThreaded, Options, TransactionDB,
+    TransactionDBOptions, TransactionOptions, WriteOptions,
+};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+// multi-threaded mode so column families can be created through a shared reference
+type DB = TransactionDB<MultiThreaded>;
+
+/// RocksDB opened as a pessimistic `TransactionDB`, every set its own transaction locking the
+/// key until it commits. Gets read outside transactions unless `db.lock_reads` is set.
+pub struct RocksDbTxn {
+    db: DB,
+    options: Options,
+    write_options: WriteOptions,
+    txn_options: TransactionOptions,
+    lock_reads: bool,
+    // column families made by `create_tables`
+    tables: OnceLock<Vec<String>>,
+    dir: DataDir,
+}
+
+impl RocksDbTxn {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        let supported: Vec<_> = PROPERTIES
+            .iter()
+            .copied()
+            .chain(["lock_reads", "lock_timeout_ms"])
+            .collect();
+        opts.check_supported("rocksdb-txn", &supported)?;
+        let (options, write_options) = parse_options(opts)?;
+        let mut txn_options = TransactionOptions::default();
+        if let Some(ms) = opts.parse("lock_timeout_ms")? {
+            txn_options.set_lock_timeout(ms);
+        }
+
+        let dir = DataDir::new(opts, "rocksdb-txn")?;
+        let families = DB::list_cf(&options, dir.path()).unwrap_or_default();
+        let descriptors = families
+            .into_iter()
+            .map(|name| ColumnFamilyDescriptor::new(name, options.clone()));
+        let db = DB::open_cf_descriptors(
+            &options,
+            &TransactionDBOptions::default(),
+            dir.path(),
+            descriptors,
+        )
+        .with_context(|| {
+            format!(
+                "failed to open rocksdb transaction database at {}",
+                dir.path().display()
+            )
+        })?;
+        Ok(RocksDbTxn {
+            db,
+            options,
+            write_options,
+            txn_options,
+            lock_reads: opts.parse("lock_reads")?.unwrap_or(false),
+            tables: OnceLock::new(),
+            dir,
+        })
+    }
+
+    fn table(&self, table: u32) -> anyhow::Result<Arc<BoundColumnFamily<'_>>> {
+        self.tables
+            .get()
+            .and_then(|names| names.get(table as usize))
+            .and_then(|name| self.db.cf_handle(name))
+            .with_context(|| format!("rocksdb column family {table} was not created"))
+    }
+}
+
+impl Database for RocksDbTxn {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        if self.lock_reads {
+            let txn = self
+                .db
+                .transaction_opt(&self.write_options, &self.txn_options);
+            txn.get_pinned_for_update(key, true)?;
+            txn.commit()?;
+        } else {
+            self.db.get_pinned(key)?;
+        }
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let txn = self
+            .db
+            .transaction_opt(&self.write_options, &self.txn_options);
+        txn.put(key, value)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let txn = self
+            .db
+            .transaction_opt(&self.write_options, &self.txn_options);
+        txn.delete(key)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Outside a transaction, reading the latest committed records without locking them
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        scan_from(self.db.raw_iterator(), start, count)
+    }
+
+    /// The read locks the key, so the write commits on the value it read
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let txn = self
+            .db
+            .transaction_opt(&self.write_options, &self.txn_options);
+        txn.get_pinned_for_update(key, true)?;
+        txn.put(key, value)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn supports_tables(&self) -> bool {
+        true
+    }
+
+    fn create_tables(&self, count: u32) -> anyhow::Result<()> {
+        let names = (0..count).map(|i| format!("data-{i}")).collect::<Vec<_>>();
+        for name in &names {
+            if self.db.cf_handle(name).is_none() {
+                self.db.create_cf(name, &self.options)?;
+            }
+        }
+        let _ = self.tables.set(names);
+        Ok(())
+    }
+
+    fn get_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        let cf = self.table(table)?;
+        if self.lock_reads {
+            let txn = self
+                .db
+                .transaction_opt(&self.write_options, &self.txn_options);
+            txn.get_pinned_for_update_cf(&cf, key, true)?;
+            txn.commit()?;
+        } else {
+            self.db.get_pinned_cf(&cf, key)?;
+        }
+        Ok(())
+    }
+
+    fn set_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let cf = self.table(table)?;
+        let txn = self
+            .db
+            .transaction_opt(&self.write_options, &self.txn_options);
+        txn.put_cf(&cf, key, value)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn delete_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        let cf = self.table(table)?;
+        let txn = self
+            .db
+            .transaction_opt(&self.write_options, &self.txn_options);
+        txn.delete_cf(&cf, key)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn scan_in(&self, table: u32, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        scan_from(self.db.raw_iterator_cf(&self.table(table)?), start, count)
+    }
+
+    fn read_modify_write_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let cf = self.table(table)?;
+        let txn = self
+            .db
+            .transaction_opt(&self.write_options, &self.txn_options);
+        txn.get_pinned_for_update_cf(&cf, key, true)?;
+        txn.put_cf(&cf, key, value)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        Some(engine_activity(|name| {
+            self.db.property_int_value(name).ok().flatten()
+        }))
+    }
+}
@@ -0,0 +1,169 @@
+use crate::database::{DataDir, Database, DbOptions};
+use anyhow::{Context, bail};
+use sanakirja::btree::{self, UDb};
+use sanakirja::{Commit, Env, MutTxn, RootDb};
+use std::path::Path;
+use std::sync::RwLock;
+
+const FILE_NAME: &str = "data.sanakirja";
+/// Initial file size, sanakirja grows the file as needed
+const INITIAL_BYTES: u64 = 1 << 20;
+/// Versions readers can hold on to before a writer waits for them
+const VERSIONS: usize = 2;
+/// Root page slot holding the key-value B-tree
+const ROOT: usize = 0;
+/// Largest key or value sanakirja stores inline as a `[u8]`, it has no overflow pages
+const MAX_LEN: usize = 510;
+/// Values are split into chunks of this size, stored under the key followed by the chunk's
+/// index. Deleting entries much larger than this trips an assertion in sanakirja's rebalancing.
+const CHUNK_LEN: usize = 256;
+const MAX_CHUNKS: usize = u8::MAX as usize + 1;
+
+pub struct Sanakirja {
+    env: Env,
+    // readers overlapping a commit can land on recycled pages, so a set excludes them
+    lock: RwLock<()>,
+    dir: DataDir,
+}
+
+impl Sanakirja {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("sanakirja", &[])?;
+        let dir = DataDir::new(opts, "sanakirja")?;
+        let path = dir.path().join(FILE_NAME);
+        let env = Env::new(&path, INITIAL_BYTES, VERSIONS)
+            .with_context(|| format!("failed to open sanakirja database at {}", path.display()))?;
+        Ok(Sanakirja {
+            env,
+            lock: RwLock::new(()),
+            dir,
+        })
+    }
+}
+
+/// The key-value B-tree created by `init`
+fn tree(tx: &impl RootDb) -> anyhow::Result<UDb<[u8], [u8]>> {
+    match tx.root_db(ROOT) {
+        Some(db) => Ok(db),
+        None => bail!("sanakirja database is not initialized"),
+    }
+}
+
+/// `key` followed by a chunk index byte, checked here as sanakirja asserts on lengths
+fn chunk_key(key: &[u8]) -> anyhow::Result<Vec<u8>> {
+    if key.is_empty() || key.len() >= MAX_LEN {
+        bail!(
+            "sanakirja stores keys of 1 to {} bytes, got {}",
+            MAX_LEN - 1,
+            key.len()
+        );
+    }
+    let mut chunk_key = Vec::with_capacity(key.len() + 1);
+    chunk_key.extend_from_slice(key);
+    chunk_key.push(0);
+    Ok(chunk_key)
+}
+
+/// Deletes every chunk of the value stored under `chunk_key`, whatever its index byte
+fn delete_chunks(
+    tx: &mut MutTxn<&Env, ()>,
+    db: &mut UDb<[u8], [u8]>,
+    chunk_key: &mut [u8],
+) -> anyhow::Result<()> {
+    for i in 0..=u8::MAX {
+        *chunk_key.last_mut().expect("index byte") = i;
+        if !btree::del(tx, db, &chunk_key[..], None)? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+impl Database for Sanakirja {
+    fn init(&self) -> anyhow::Result<()> {
+        let mut tx = Env::mut_txn_begin(&self.env)?;
+        if tx.root(ROOT).is_none() {
+            // SAFETY: the tree is only ever loaded as `UDb<[u8], [u8]>`
+            let db: UDb<[u8], [u8]> = unsafe { btree::create_db_(&mut tx)? };
+            tx.set_root(ROOT, db.db.get());
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let _guard = self.lock.read().unwrap_or_else(|e| e.into_inner());
+        let tx = Env::txn_begin(&self.env)?;
+        let db = tree(&tx)?;
+        let mut chunk_key = chunk_key(key)?;
+        for i in 0..=u8::MAX {
+            *chunk_key.last_mut().expect("index byte") = i;
+            match btree::get(&tx, &db, &chunk_key[..], None)? {
+                Some((k, _)) if k == &chunk_key[..] => {}
+                _ => break,
+            }
+        }
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let mut chunk_key = chunk_key(key)?;
+        if value.is_empty() || value.len() > MAX_CHUNKS * CHUNK_LEN {
+            bail!(
+                "sanakirja stores values of 1 to {} bytes, got {}",
+                MAX_CHUNKS * CHUNK_LEN,
+                value.len()
+            );
+        }
+        let _guard = self.lock.write().unwrap_or_else(|e| e.into_inner());
+        let mut tx = Env::mut_txn_begin(&self.env)?;
+        let mut db = tree(&tx)?;
+        // the old value may have had more chunks than the new one
+        delete_chunks(&mut tx, &mut db, &mut chunk_key)?;
+        for (i, chunk) in value.chunks(CHUNK_LEN).enumerate() {
+            *chunk_key.last_mut().expect("index byte") = i as u8;
+            btree::put(&mut tx, &mut db, &chunk_key[..], chunk)?;
+        }
+        tx.set_root(ROOT, db.db.get());
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        let mut chunk_key = chunk_key(key)?;
+        let _guard = self.lock.write().unwrap_or_else(|e| e.into_inner());
+        let mut tx = Env::mut_txn_begin(&self.env)?;
+        let mut db = tree(&tx)?;
+        delete_chunks(&mut tx, &mut db, &mut chunk_key)?;
+        tx.set_root(ROOT, db.db.get());
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let _guard = self.lock.read().unwrap_or_else(|e| e.into_inner());
+        let tx = Env::txn_begin(&self.env)?;
+        let db = tree(&tx)?;
+        let chunk_key = chunk_key(start)?;
+        let mut records = 0;
+        // a record's chunks follow each other in index order, it starts at chunk 0
+        for entry in btree::iter(&tx, &db, Some((&chunk_key[..], None)))? {
+            let (k, _) = entry?;
+            if k.last() == Some(&0) {
+                if records == count {
+                    break;
+                }
+                records += 1;
+            }
+        }
+        Ok(records)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn preallocate(&self, bytes: u64) -> anyhow::Result<()> {
+        self.dir.preallocate(FILE_NAME, bytes)
+    }
+}
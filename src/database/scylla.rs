@@ -0,0 +1,147 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use scylla::client::session::Session;
+use scylla::client::session_builder::SessionBuilder;
+use scylla::statement::prepared::PreparedStatement;
+use std::sync::{Arc, OnceLock};
+use tokio::runtime::Runtime;
+
+const DEFAULT_NODE: &str = "127.0.0.1:9042";
+const DEFAULT_PORT: u16 = 9042;
+const DEFAULT_REPLICATION_FACTOR: u32 = 1;
+const CREATE_TABLE: &str =
+    "CREATE TABLE IF NOT EXISTS kvbencher.kv (key blob PRIMARY KEY, value blob)";
+const GET: &str = "SELECT value FROM kvbencher.kv WHERE key = ?";
+const SET: &str = "INSERT INTO kvbencher.kv (key, value) VALUES (?, ?)";
+const DELETE: &str = "DELETE FROM kvbencher.kv WHERE key = ?";
+
+pub struct Scylla {
+    session: Session,
+    replication_factor: u32,
+    // prepared by `init`, once the table exists
+    statements: OnceLock<Statements>,
+    runtime: Runtime,
+}
+
+struct Statements {
+    get: PreparedStatement,
+    set: PreparedStatement,
+    delete: PreparedStatement,
+}
+
+impl Scylla {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("scylla", &["nodes", "endpoint", "replication_factor"])?;
+        let mut builder = SessionBuilder::new();
+        let nodes = match (opts.properties.get("nodes"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.nodes",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(nodes), None) => nodes.split(',').map(|n| n.trim().to_string()).collect(),
+            (None, Some(endpoint)) => {
+                endpoint.reject_database("scylla")?;
+                if let Some(user) = &endpoint.user {
+                    builder = builder.user(user, endpoint.password.as_deref().unwrap_or(""));
+                }
+                if endpoint.tls {
+                    builder = builder.tls_context(Some(Arc::new(endpoint.rustls_config()?)));
+                }
+                endpoint.addrs(DEFAULT_PORT)
+            }
+            (None, None) => vec![DEFAULT_NODE.to_string()],
+        };
+        let replication_factor = opts
+            .parse("replication_factor")?
+            .unwrap_or(DEFAULT_REPLICATION_FACTOR);
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let session = runtime
+            .block_on(builder.known_nodes(&nodes).build())
+            .with_context(|| format!("failed to connect to scylla at {nodes:?}"))?;
+        Ok(Scylla {
+            session,
+            replication_factor,
+            statements: OnceLock::new(),
+            runtime,
+        })
+    }
+
+    fn statements(&self) -> anyhow::Result<&Statements> {
+        self.statements
+            .get()
+            .context("scylla statements are prepared by init")
+    }
+
+    async fn create_table(&self) -> anyhow::Result<Statements> {
+        let keyspace = format!(
+            "CREATE KEYSPACE IF NOT EXISTS kvbencher WITH replication = \
+             {{'class': 'SimpleStrategy', 'replication_factor': {}}}",
+            self.replication_factor
+        );
+        self.session.query_unpaged(keyspace, &[]).await?;
+        self.session.query_unpaged(CREATE_TABLE, &[]).await?;
+        Ok(Statements {
+            get: self.session.prepare(GET).await?,
+            set: self.session.prepare(SET).await?,
+            delete: self.session.prepare(DELETE).await?,
+        })
+    }
+
+    async fn get_row(&self, key: &[u8]) -> anyhow::Result<()> {
+        let get = &self.statements()?.get;
+        self.session.execute_unpaged(get, (key,)).await?;
+        Ok(())
+    }
+
+    async fn set_row(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        let set = &self.statements()?.set;
+        self.session.execute_unpaged(set, (key, value)).await?;
+        Ok(())
+    }
+
+    async fn delete_row(&self, key: &[u8]) -> anyhow::Result<()> {
+        let delete = &self.statements()?.delete;
+        self.session.execute_unpaged(delete, (key,)).await?;
+        Ok(())
+    }
+}
+
+impl Database for Scylla {
+    fn init(&self) -> anyhow::Result<()> {
+        let statements = self.runtime.block_on(self.create_table())?;
+        let _ = self.statements.set(statements);
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.get_row(key))
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.set_row(key, value))
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.delete_row(key))
+    }
+
+    /// All requests in flight at once, each still its own CQL request
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| async move {
+            match *op {
+                PipelinedOp::Get(key) => self.get_row(key).await,
+                PipelinedOp::Set(key, value) => self.set_row(key, value).await,
+                PipelinedOp::Delete(key) => self.delete_row(key).await,
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
@@ -1,18 +1,73 @@
-use crate::database::Database;
-use tempfile::TempDir;
+use crate::database::{DataDir, Database, DbOptions, EngineActivity};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use std::path::Path;
+use std::sync::OnceLock;
 
 pub struct Sled {
     db: sled::Db,
-    _f: TempDir,
+    // trees opened by `create_tables`
+    trees: OnceLock<Vec<sled::Tree>>,
+    dir: DataDir,
 }
 
 impl Sled {
-    pub fn new() -> Self {
-        let f = TempDir::new().unwrap();
-        let db = sled::open(f.path()).unwrap();
-        Sled { db, _f: f }
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("sled", &["compression", "cache_capacity", "flush_every_ms"])?;
+        if opts.parse("compression")? == Some(true) {
+            // sled's zstd links a different native zstd than the one RocksDB builds
+            return Err(ConfigError::new(
+                "db.compression",
+                "kvbencher builds sled without compression, its zstd conflicts with RocksDB's",
+            )
+            .into());
+        }
+        let dir = DataDir::new(opts, "sled")?;
+        let mut config = sled::Config::new().path(dir.path());
+        if let Some(bytes) = opts.parse("cache_capacity")? {
+            config = config.cache_capacity(bytes);
+        }
+        if let Some(ms) = opts.parse("flush_every_ms")? {
+            // 0 turns the background flush off, writes then only reach disk on explicit flushes
+            config = config.flush_every_ms((ms > 0).then_some(ms));
+        }
+        let db = config
+            .open()
+            .with_context(|| format!("failed to open sled database at {}", dir.path().display()))?;
+        Ok(Sled {
+            db,
+            trees: OnceLock::new(),
+            dir,
+        })
     }
 }
+
+impl Sled {
+    fn tree(&self, table: u32) -> anyhow::Result<&sled::Tree> {
+        self.trees
+            .get()
+            .and_then(|trees| trees.get(table as usize))
+            .with_context(|| format!("sled tree {table} was not created"))
+    }
+}
+
+fn scan_tree(tree: &sled::Tree, start: &[u8], count: usize) -> anyhow::Result<usize> {
+    let mut records = 0;
+    for entry in tree.range(start..).take(count) {
+        entry?;
+        records += 1;
+    }
+    Ok(records)
+}
+
+impl Drop for Sled {
+    fn drop(&mut self) {
+        if self.dir.keep_data() {
+            let _ = self.db.flush();
+        }
+    }
+}
+
 impl Database for Sled {
     fn init(&self) -> anyhow::Result<()> {
         Ok(())
@@ -27,4 +82,73 @@ impl Database for Sled {
         self.db.insert(key, value)?;
         Ok(())
     }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.db.remove(key)?;
+        Ok(())
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        scan_tree(&self.db, start, count)
+    }
+
+    /// sled has no locks, the update retries its compare-and-swap until no other write
+    /// landed in between
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.db.update_and_fetch(key, |_| Some(value))?;
+        Ok(())
+    }
+
+    fn supports_tables(&self) -> bool {
+        true
+    }
+
+    fn create_tables(&self, count: u32) -> anyhow::Result<()> {
+        let trees = (0..count)
+            .map(|i| self.db.open_tree(format!("data-{i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let _ = self.trees.set(trees);
+        Ok(())
+    }
+
+    fn get_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        self.tree(table)?.get(key)?;
+        Ok(())
+    }
+
+    fn set_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.tree(table)?.insert(key, value)?;
+        Ok(())
+    }
+
+    fn delete_in(&self, table: u32, key: &[u8]) -> anyhow::Result<()> {
+        self.tree(table)?.remove(key)?;
+        Ok(())
+    }
+
+    fn scan_in(&self, table: u32, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        scan_tree(self.tree(table)?, start, count)
+    }
+
+    fn read_modify_write_in(&self, table: u32, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.tree(table)?.update_and_fetch(key, |_| Some(value))?;
+        Ok(())
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        // sled does not expose its flusher or segment cleaner, only the space they leave behind
+        Some(EngineActivity {
+            size_on_disk_bytes: self.db.size_on_disk().ok(),
+            ..EngineActivity::default()
+        })
+    }
+
+    fn preallocate(&self, bytes: u64) -> anyhow::Result<()> {
+        // sled keeps all pages in the single file `db`
+        self.dir.preallocate("db", bytes)
+    }
 }
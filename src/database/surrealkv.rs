@@ -0,0 +1,100 @@
+use crate::database::{DataDir, Database, DbOptions};
+use anyhow::Context;
+use std::path::Path;
+use surrealkv::{LSMIterator, Mode, Tree, TreeBuilder};
+use tokio::runtime::Runtime;
+
+pub struct SurrealKv {
+    tree: Tree,
+    dir: DataDir,
+    // commits and the background flushes and compactions run on tokio
+    runtime: Runtime,
+}
+
+impl SurrealKv {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("surrealkv", &[])?;
+        let dir = DataDir::new(opts, "surrealkv")?;
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let tree = {
+            // the tree spawns its background tasks while it is built
+            let _guard = runtime.enter();
+            TreeBuilder::new()
+                .with_path(dir.path().to_path_buf())
+                .build()
+                .with_context(|| {
+                    format!(
+                        "failed to open surrealkv database at {}",
+                        dir.path().display()
+                    )
+                })?
+        };
+        Ok(SurrealKv { tree, dir, runtime })
+    }
+}
+
+impl Drop for SurrealKv {
+    fn drop(&mut self) {
+        // dropping the tree only schedules the close, which would be lost with the runtime
+        let _ = self.runtime.block_on(self.tree.close());
+    }
+}
+
+impl Database for SurrealKv {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        let tx = self.tree.begin_with_mode(Mode::ReadOnly)?;
+        tx.get(key)?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        // with the default eventual durability the commit reaches the WAL but is not synced
+        loop {
+            let mut tx = self.tree.begin_with_mode(Mode::WriteOnly)?;
+            tx.set(key, value)?;
+            match self.runtime.block_on(tx.commit()) {
+                // another thread committed the same key since this transaction began, the
+                // blind write is simply retried like a last-writer-wins store would apply it
+                Err(surrealkv::Error::TransactionWriteConflict) => continue,
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        loop {
+            let mut tx = self.tree.begin_with_mode(Mode::WriteOnly)?;
+            tx.delete(key)?;
+            match self.runtime.block_on(tx.commit()) {
+                // retried like a conflicting set
+                Err(surrealkv::Error::TransactionWriteConflict) => continue,
+                result => return Ok(result?),
+            }
+        }
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let tx = self.tree.begin_with_mode(Mode::ReadOnly)?;
+        // ranges need an end, the workload's keys all have the start key's length
+        let end = vec![u8::MAX; start.len() + 1];
+        let mut iter = tx.range(start.to_vec(), end)?;
+        let mut records = 0;
+        let mut valid = iter.seek_first()?;
+        while valid && records < count {
+            iter.value()?;
+            records += 1;
+            valid = iter.next()?;
+        }
+        Ok(records)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(self.dir.path())
+    }
+}
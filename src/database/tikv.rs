@@ -0,0 +1,89 @@
+use crate::database::{Database, DbOptions, PipelinedOp};
+use crate::workload::ConfigError;
+use anyhow::Context;
+use tikv_client::RawClient;
+use tokio::runtime::Runtime;
+
+const DEFAULT_PD_ENDPOINT: &str = "127.0.0.1:2379";
+const DEFAULT_PORT: u16 = 2379;
+
+pub struct Tikv {
+    client: RawClient,
+    // the client is async, every run thread blocks on this runtime for its requests
+    runtime: Runtime,
+}
+
+impl Tikv {
+    pub fn new(opts: &DbOptions) -> anyhow::Result<Self> {
+        opts.check_supported("tikv", &["pd_endpoints", "endpoint"])?;
+        let endpoints = match (opts.properties.get("pd_endpoints"), opts.endpoint()?) {
+            (Some(_), Some(_)) => {
+                return Err(ConfigError::new(
+                    "db.pd_endpoints",
+                    "cannot be combined with db.endpoint, which --endpoint and --container set",
+                )
+                .into());
+            }
+            (Some(endpoints), None) => endpoints.split(',').map(|e| e.trim().to_string()).collect(),
+            (None, Some(endpoint)) => {
+                // TiKV has no passwords, and its TLS needs client certificates
+                endpoint.reject_auth("tikv")?;
+                endpoint.reject_tls("tikv")?;
+                endpoint.reject_database("tikv")?;
+                endpoint.addrs(DEFAULT_PORT)
+            }
+            (None, None) => vec![DEFAULT_PD_ENDPOINT.to_string()],
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let client = runtime
+            .block_on(RawClient::new(endpoints.clone()))
+            .with_context(|| format!("failed to connect to tikv through pd at {endpoints:?}"))?;
+        Ok(Tikv { client, runtime })
+    }
+}
+
+impl Database for Tikv {
+    fn init(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.client.get(key.to_vec()))?;
+        Ok(())
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> anyhow::Result<()> {
+        self.runtime
+            .block_on(self.client.put(key.to_vec(), value.to_vec()))?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &[u8]) -> anyhow::Result<()> {
+        self.runtime.block_on(self.client.delete(key.to_vec()))?;
+        Ok(())
+    }
+
+    /// One raw scan request, TiKV caps its limit at 10240 records
+    fn scan(&self, start: &[u8], count: usize) -> anyhow::Result<usize> {
+        let records = self
+            .runtime
+            .block_on(self.client.scan(start.to_vec().., count as u32))?;
+        Ok(records.len())
+    }
+
+    /// All requests in flight at once, each still its own raw request
+    fn pipeline(&self, ops: &[PipelinedOp]) -> anyhow::Result<()> {
+        let requests = ops.iter().map(|op| async move {
+            match *op {
+                PipelinedOp::Get(key) => self.client.get(key.to_vec()).await.map(drop),
+                PipelinedOp::Set(key, value) => self.client.put(key.to_vec(), value.to_vec()).await,
+                PipelinedOp::Delete(key) => self.client.delete(key.to_vec()).await,
+            }
+        });
+        self.runtime
+            .block_on(futures::future::try_join_all(requests))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const SECTOR_BYTES: f64 = 512.0;
+
+/// Activity of one block device over a sampling interval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskSample {
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub read_mb_per_sec: f64,
+    pub write_mb_per_sec: f64,
+    /// Average number of requests in flight
+    pub queue_depth: f64,
+}
+
+/// Cumulative counters of a device from `/proc/diskstats`
+#[derive(Debug, Clone, Copy)]
+pub struct DiskCounters {
+    reads: u64,
+    sectors_read: u64,
+    writes: u64,
+    sectors_written: u64,
+    /// Milliseconds spent with requests in flight, weighted by their number
+    weighted_io_ms: u64,
+}
+
+impl DiskCounters {
+    /// Current counters of `device`, a name such as `nvme0n1p2` or `/dev/nvme0n1p2`
+    pub fn read(device: &str) -> Result<Self> {
+        let name = device.strip_prefix("/dev/").unwrap_or(device);
+        let content =
+            std::fs::read_to_string("/proc/diskstats").context("failed to read /proc/diskstats")?;
+        content
+            .lines()
+            .find_map(|l| {
+                // `major minor name reads merged sectors ms writes merged sectors ms in-flight
+                // io-ms weighted-io-ms ...`
+                let fields: Vec<&str> = l.split_whitespace().collect();
+                if fields.get(2) != Some(&name) {
+                    return None;
+                }
+                let n = |i: usize| fields.get(i).and_then(|v| v.parse().ok());
+                Some(DiskCounters {
+                    reads: n(3)?,
+                    sectors_read: n(5)?,
+                    writes: n(7)?,
+                    sectors_written: n(9)?,
+                    weighted_io_ms: n(13)?,
+                })
+            })
+            .with_context(|| format!("device {name} not found in /proc/diskstats"))
+    }
+
+    /// Rates between `earlier` and these counters, taken `elapsed` apart
+    pub fn since(&self, earlier: &DiskCounters, elapsed: Duration) -> DiskSample {
+        let secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let rate = |now: u64, then: u64| now.saturating_sub(then) as f64 / secs;
+        let mb = |sectors: f64| sectors * SECTOR_BYTES / 1_000_000.0;
+        DiskSample {
+            read_iops: rate(self.reads, earlier.reads),
+            write_iops: rate(self.writes, earlier.writes),
+            read_mb_per_sec: mb(rate(self.sectors_read, earlier.sectors_read)),
+            write_mb_per_sec: mb(rate(self.sectors_written, earlier.sectors_written)),
+            queue_depth: rate(self.weighted_io_ms, earlier.weighted_io_ms) / 1000.0,
+        }
+    }
+}
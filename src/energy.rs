@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+/// Energy counters of the cpu packages (RAPL), read at the start of a phase
+pub struct EnergyMeter {
+    /// (zone dir, counter value at start, counter range before it wraps)
+    zones: Vec<(PathBuf, u64, u64)>,
+}
+
+impl EnergyMeter {
+    /// Starts measuring, `None` without RAPL support or permission to read its counters
+    /// (`energy_uj` is root-only on most kernels)
+    pub fn start() -> Option<Self> {
+        let mut zones = vec![];
+        for entry in std::fs::read_dir(POWERCAP_ROOT).ok()?.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            // package zones are `intel-rapl:<n>`, their subzones (core, dram) `intel-rapl:<n>:<m>`
+            let is_package = name
+                .strip_prefix("intel-rapl:")
+                .is_some_and(|n| !n.contains(':'));
+            if !is_package {
+                continue;
+            }
+            let dir = entry.path();
+            let energy = read_u64(&dir.join("energy_uj"))?;
+            let range = read_u64(&dir.join("max_energy_range_uj")).unwrap_or(u64::MAX);
+            zones.push((dir, energy, range));
+        }
+        (!zones.is_empty()).then_some(EnergyMeter { zones })
+    }
+
+    /// Joules used by all packages since `start`
+    pub fn joules(&self) -> Option<f64> {
+        let mut micro_joules = 0;
+        for (dir, start, range) in &self.zones {
+            let now = read_u64(&dir.join("energy_uj"))?;
+            micro_joules += if now >= *start {
+                now - start
+            } else {
+                range - start + now
+            };
+        }
+        Some(micro_joules as f64 / 1_000_000.0)
+    }
+}
+
+fn read_u64(path: &std::path::Path) -> Option<u64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The machine a run executed on, stored with every machine-readable result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Environment {
+    pub kvbencher_version: String,
+    pub os: String,
+    pub arch: String,
+    /// Logical cpus available to the process
+    pub cpus: usize,
+    #[serde(default)]
+    pub os_release: Option<String>,
+    #[serde(default)]
+    pub kernel: Option<String>,
+    #[serde(default)]
+    pub cpu_model: Option<String>,
+    #[serde(default)]
+    pub memory_bytes: Option<u64>,
+    #[serde(default)]
+    pub rustc_version: Option<String>,
+    /// Storage holding the database files, absent for in-memory databases
+    #[serde(default)]
+    pub storage: Option<Storage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Storage {
+    pub filesystem: Option<String>,
+    /// Block device the filesystem is mounted from, e.g. `/dev/nvme0n1p2`
+    pub device: Option<String>,
+    pub device_model: Option<String>,
+}
+
+impl Environment {
+    /// Collects information about this machine and the storage behind `data_dir`
+    pub fn collect(data_dir: Option<&Path>) -> Self {
+        Environment {
+            kvbencher_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            cpus: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            os_release: os_release(),
+            kernel: kernel(),
+            cpu_model: cpu_model(),
+            memory_bytes: memory_bytes(),
+            rustc_version: option_env!("KVBENCHER_RUSTC_VERSION").map(str::to_string),
+            storage: data_dir.map(storage),
+        }
+    }
+}
+
+fn read(path: &str) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+fn os_release() -> Option<String> {
+    read("/etc/os-release")?
+        .lines()
+        .find_map(|l| l.strip_prefix("PRETTY_NAME="))
+        .map(|v| v.trim_matches('"').to_string())
+}
+
+fn kernel() -> Option<String> {
+    read("/proc/sys/kernel/osrelease").map(|v| v.trim().to_string())
+}
+
+fn cpu_model() -> Option<String> {
+    read("/proc/cpuinfo")?
+        .lines()
+        .find(|l| l.starts_with("model name"))
+        .and_then(|l| l.split_once(':'))
+        .map(|(_, v)| v.trim().to_string())
+}
+
+fn memory_bytes() -> Option<u64> {
+    let kb = read("/proc/meminfo")?
+        .lines()
+        .find_map(|l| l.strip_prefix("MemTotal:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+fn storage(data_dir: &Path) -> Storage {
+    let mount = data_dir.canonicalize().ok().and_then(|dir| mount_of(&dir));
+    let (filesystem, device) = match mount {
+        Some((fs, dev)) => (Some(fs), Some(dev)),
+        None => (None, None),
+    };
+    let device_model = device.as_deref().and_then(device_model);
+    Storage {
+        filesystem,
+        device,
+        device_model,
+    }
+}
+
+/// Type of the filesystem (e.g. `ext4`) holding `path`
+pub fn filesystem(path: &Path) -> Option<String> {
+    mount_of(&path.canonicalize().ok()?).map(|(fs, _)| fs)
+}
+
+/// Block device (e.g. `/dev/vda1`) holding `path`, if it is on one
+pub fn block_device(path: &Path) -> Option<String> {
+    let (_, source) = mount_of(&path.canonicalize().ok()?)?;
+    source.starts_with("/dev/").then_some(source)
+}
+
+/// (filesystem type, mount source) of the mount containing `path`, from /proc/self/mountinfo
+fn mount_of(path: &Path) -> Option<(String, String)> {
+    let mountinfo = read("/proc/self/mountinfo")?;
+    mountinfo
+        .lines()
+        .filter_map(|l| {
+            // `id parent major:minor root mount-point options... - fstype source super-options`
+            let (left, right) = l.split_once(" - ")?;
+            let mount_point = left.split(' ').nth(4)?;
+            let mut right = right.split(' ');
+            let fs = right.next()?;
+            let source = right.next()?;
+            path.starts_with(mount_point)
+                .then(|| (mount_point.len(), fs.to_string(), source.to_string()))
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .map(|(_, fs, source)| (fs, source))
+}
+
+/// Model (or vendor) of the disk behind a block device such as `/dev/vda1`
+fn device_model(device: &str) -> Option<String> {
+    let name = device.strip_prefix("/dev/")?;
+    let mut block = Path::new("/sys/class/block")
+        .join(name)
+        .canonicalize()
+        .ok()?;
+    // partitions live below their disk in sysfs
+    if block.join("partition").exists() {
+        block.pop();
+    }
+    ["model", "vendor"].iter().find_map(|f| {
+        std::fs::read_to_string(block.join("device").join(f))
+            .ok()
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    })
+}
@@ -0,0 +1,173 @@
+use crate::database::Database;
+use crate::generator::RecordGen;
+use crate::workload;
+use crate::workload::spec::WorkloadSpec;
+use anyhow::{Result, bail};
+use rand::random;
+use serde::Serialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// When `fill` stops inserting, whichever limit is reached first
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FillLimits {
+    /// Size of the database's data dir on disk
+    pub data_dir_bytes: Option<u64>,
+    /// Percentage of the data dir's filesystem in use
+    pub disk_used_percent: Option<f64>,
+    /// Bytes of keys and values inserted between two measurements
+    pub step_bytes: u64,
+}
+
+/// Insert performance over one step of a fill, and the size reached at its end
+#[derive(Debug, Serialize)]
+pub struct FillStep {
+    pub records: u64,
+    pub data_dir_bytes: u64,
+    pub disk_used_percent: f64,
+    pub ops_per_sec: f64,
+    pub p50_micro_sec: f64,
+    pub p99_micro_sec: f64,
+}
+
+/// Keeps inserting new records into `db` until a limit is reached or the disk is full,
+/// measuring every step so the degradation of inserts as the database grows becomes visible.
+/// Key and value sizes are taken from `spec`, `on_step` is called as each step completes.
+pub fn fill(
+    db: &dyn Database,
+    spec: &WorkloadSpec,
+    limits: &FillLimits,
+    mut on_step: impl FnMut(&FillStep),
+) -> Result<Vec<FillStep>> {
+    if limits.data_dir_bytes.is_none() && limits.disk_used_percent.is_none() {
+        bail!("fill needs a data dir size or a disk usage limit");
+    }
+    let Some(dir) = db.data_dir() else {
+        bail!("fill needs a file-backed database");
+    };
+    spec.validate()?;
+    db.init()?;
+
+    let v_r = spec.value_size_range();
+    let record_bytes = spec.key_size + (v_r.start() + v_r.end()) / 2;
+    let step_records = (limits.step_bytes / record_bytes).max(1);
+    let mut record_gen = RecordGen::new(
+        spec.key_size as usize,
+        spec.key_format(),
+        spec.value_size_range(),
+        spec.seed.unwrap_or_else(random),
+    )?;
+
+    let mut steps = vec![];
+    let mut records = 0;
+    loop {
+        let mut hist = workload::latency_histogram()?;
+        let mut busy = Duration::ZERO;
+        let mut inserted = 0;
+        let mut disk_full = false;
+        while inserted < step_records {
+            let (key, value) = record_gen.record(records);
+            let start = Instant::now();
+            match db.set(key, value) {
+                Ok(()) => {}
+                // running out of space is where a fill ends, the partial step is its last
+                Err(e) if is_disk_full(&e) => {
+                    disk_full = true;
+                    break;
+                }
+                Err(e) => return Err(e),
+            }
+            let elapsed = start.elapsed();
+            busy += elapsed;
+            hist.record(elapsed.as_nanos() as u64)?;
+            records += 1;
+            inserted += 1;
+        }
+
+        let step = FillStep {
+            records,
+            data_dir_bytes: dir_size(dir)?,
+            disk_used_percent: disk_used_percent(dir)?,
+            ops_per_sec: inserted as f64 / busy.as_secs_f64().max(f64::EPSILON),
+            p50_micro_sec: workload::quantile_micros(&hist, 0.50),
+            p99_micro_sec: workload::quantile_micros(&hist, 0.99),
+        };
+        on_step(&step);
+        let done = disk_full
+            || limits
+                .data_dir_bytes
+                .is_some_and(|b| step.data_dir_bytes >= b)
+            || limits
+                .disk_used_percent
+                .is_some_and(|p| step.disk_used_percent >= p);
+        steps.push(step);
+        if done {
+            return Ok(steps);
+        }
+    }
+}
+
+/// Whether an insert failed for lack of disk space. Engines wrap the io error in their own
+/// types, not all of which expose it as a source, so the OS message is matched as well.
+fn is_disk_full(e: &anyhow::Error) -> bool {
+    e.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|io| io.kind() == std::io::ErrorKind::StorageFull)
+            || cause.to_string().contains("No space left on device")
+    })
+}
+
+/// Disk space allocated to the files below `path`
+fn dir_size(path: &Path) -> Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        size += if meta.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            allocated(&meta)
+        };
+    }
+    Ok(size)
+}
+
+#[cfg(unix)]
+fn allocated(meta: &std::fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    meta.blocks() * 512
+}
+
+#[cfg(not(unix))]
+fn allocated(meta: &std::fs::Metadata) -> u64 {
+    meta.len()
+}
+
+#[cfg(unix)]
+fn disk_used_percent(path: &Path) -> Result<f64> {
+    use anyhow::Context;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: statvfs only writes to the zeroed struct passed to it
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("failed to stat filesystem of {}", path.display()));
+    }
+    // blocks reserved for root are neither used nor available to the benchmark, so like df
+    // the percentage is of the used and available blocks only
+    let used = (stat.f_blocks - stat.f_bfree) as f64;
+    let usable = used + stat.f_bavail as f64;
+    Ok(if usable == 0.0 {
+        0.0
+    } else {
+        used / usable * 100.0
+    })
+}
+
+#[cfg(not(unix))]
+fn disk_used_percent(_path: &Path) -> Result<f64> {
+    bail!("disk usage is only available on unix")
+}
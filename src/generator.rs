@@ -1,17 +1,277 @@
+use crate::hugepage::ValueBuffer;
 use anyhow::Result;
 use rand::distr::Distribution;
 use rand::rngs::SmallRng;
-use rand::{RngCore, SeedableRng};
+use rand::{Rng, RngCore, SeedableRng};
 use rand_distr::Zipf;
+use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// How keys are chosen from the loaded keyspace during the run phase
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyDistribution {
+    #[default]
+    Zipfian,
+    Uniform,
+    /// A fixed share of the operations on a small hot set of records, the rest spread
+    /// evenly over the others, see `KeyChooser::hotspot`
+    Hotspot,
+    /// Zipfian over how recently records were inserted, the newest the most frequent, see
+    /// `KeyChooser::latest`
+    Latest,
+}
+
+/// How a record's index becomes its key's bytes
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyOrder {
+    /// Scattered over the keyspace, consecutive records landing far apart
+    #[default]
+    Hashed,
+    /// The index big-endian at the end of a zero key, so each record's key sorts after the
+    /// ones before it like a timestamp or counter. Keys under 8 bytes keep the low bytes and
+    /// wrap around.
+    Ordered,
+    /// Record `i` is point `i / series_count` of series `i % series_count`, the series id
+    /// big-endian in the first 4 bytes and the point big-endian in the last 8, so each
+    /// series' points sort together in time order and inserts append to every series
+    TimeSeries,
+}
+
+/// Length of the tenant prefix of keys with several tenants
+pub const TENANT_PREFIX_LEN: usize = 4;
+
+/// A `KeyOrder` with what it needs to turn record indexes into keys
+#[derive(Copy, Clone, Debug)]
+pub struct KeyFormat {
+    pub order: KeyOrder,
+    /// Series of `KeyOrder::TimeSeries`, at least 1
+    pub series_count: u32,
+    /// Above 1, record `i` belongs to tenant `i % tenant_count`, whose id big-endian
+    /// replaces the first `TENANT_PREFIX_LEN` bytes of the key
+    pub tenant_count: u32,
+}
+
+impl KeyFormat {
+    /// The tenant a key of this format belongs to, `None` with a single tenant
+    #[inline]
+    pub fn tenant(&self, key: &[u8]) -> Option<usize> {
+        let prefix = key.get(..TENANT_PREFIX_LEN)?;
+        (self.tenant_count > 1)
+            .then(|| u32::from_be_bytes(prefix.try_into().expect("prefix length")) as usize)
+    }
+
+    /// The record a scan of `len` records starts at to end at record `idx`. A time series
+    /// scan reads the window of `idx`'s series up to its point, starting no earlier than the
+    /// series' first point; with the other orders it starts at `idx`.
+    pub fn scan_start(&self, idx: u64, len: u64) -> u64 {
+        if self.order != KeyOrder::TimeSeries {
+            return idx;
+        }
+        let series = u64::from(self.series_count);
+        let back = (idx / series).min(len.saturating_sub(1));
+        idx - back * series
+    }
+}
 
 pub struct KVSizeGen {
     zipf: Zipf<f64>,
     rng: SmallRng,
 }
 
+/// Keys of the chosen records for one run thread
 pub struct ByteGen {
-    zipf: Zipf<f64>,
+    key_chooser: KeyChooser,
+    hot_keys: Arc<HotKeys>,
     rng: SmallRng,
+    key: Vec<u8>,
+}
+
+/// Largest keyspace sampled through an alias table, 8 bytes per key
+const ALIAS_TABLE_MAX_KEYS: u64 = 1 << 22;
+
+/// Picks indexes of loaded records, `0..record_count`, following a `KeyDistribution`.
+/// Cheap to clone, run threads share one alias table.
+#[derive(Clone)]
+pub enum KeyChooser {
+    /// Keyspaces up to `ALIAS_TABLE_MAX_KEYS`, a constant two draws per key
+    ZipfianAlias(Arc<AliasTable>),
+    /// Larger keyspaces, by rejection-inversion
+    Zipfian(Zipf<f64>),
+    Uniform(u64),
+    /// Records `0..hot` take `hot_ops` of the choices, uniformly within either set
+    Hotspot {
+        hot: u64,
+        range: u64,
+        hot_ops: f64,
+    },
+    /// Zipfian ranks counted back from the newest record the insert counter handed out
+    Latest {
+        ranks: Box<KeyChooser>,
+        inserts: Arc<InsertCounter>,
+    },
+    /// `tenants` picks one of `count` tenants and `records` one of its records, tenant `t`
+    /// owning records `t`, `t + count`, `t + 2 * count` and so on
+    Tenants {
+        tenants: Box<KeyChooser>,
+        records: Box<KeyChooser>,
+        count: u64,
+    },
+}
+
+impl KeyChooser {
+    pub fn new(range: u64, distribution: KeyDistribution) -> Result<Self> {
+        Ok(match distribution {
+            KeyDistribution::Zipfian if range <= ALIAS_TABLE_MAX_KEYS => {
+                KeyChooser::ZipfianAlias(Arc::new(AliasTable::zipf(range)?))
+            }
+            KeyDistribution::Zipfian => KeyChooser::Zipfian(Zipf::new(range as f64, 1.0)?),
+            KeyDistribution::Uniform => KeyChooser::Uniform(range),
+            KeyDistribution::Hotspot => {
+                anyhow::bail!("hotspot keys need their fractions, see `KeyChooser::hotspot`")
+            }
+            KeyDistribution::Latest => {
+                anyhow::bail!("latest keys need the insert counter, see `KeyChooser::latest`")
+            }
+        })
+    }
+
+    /// `hot_ops` of the choices among the first `hot_data` of `0..range`, at least one record
+    pub fn hotspot(range: u64, hot_data: f64, hot_ops: f64) -> Self {
+        let hot = ((range as f64 * hot_data).round() as u64).clamp(1, range);
+        KeyChooser::Hotspot {
+            hot,
+            range,
+            hot_ops,
+        }
+    }
+
+    /// Zipfian over the `range` newest records, following the run phase's inserts as they
+    /// take new indexes. Older records are never chosen. As in YCSB an insert still in
+    /// flight may be chosen and its read miss.
+    pub fn latest(range: u64, inserts: Arc<InsertCounter>) -> Result<Self> {
+        Ok(KeyChooser::Latest {
+            ranks: Box::new(KeyChooser::new(range, KeyDistribution::Zipfian)?),
+            inserts,
+        })
+    }
+
+    /// Records of `count` tenants, `records` choosing among the tenant's own records
+    pub fn tenants(tenants: KeyChooser, records: KeyChooser, count: u64) -> Self {
+        KeyChooser::Tenants {
+            tenants: Box::new(tenants),
+            records: Box::new(records),
+            count,
+        }
+    }
+
+    #[inline]
+    pub fn next(&self, rng: &mut impl Rng) -> u64 {
+        match self {
+            KeyChooser::ZipfianAlias(table) => table.sample(rng),
+            // zipf samples are 1-based
+            KeyChooser::Zipfian(zipf) => zipf.sample(rng) as u64 - 1,
+            KeyChooser::Uniform(range) => rng.random_range(0..*range),
+            &KeyChooser::Hotspot {
+                hot,
+                range,
+                hot_ops,
+            } => {
+                if hot == range || rng.random::<f64>() < hot_ops {
+                    rng.random_range(0..hot)
+                } else {
+                    rng.random_range(hot..range)
+                }
+            }
+            KeyChooser::Latest { ranks, inserts } => {
+                let newest = inserts.count() - 1;
+                newest.saturating_sub(ranks.next(rng))
+            }
+            KeyChooser::Tenants {
+                tenants,
+                records,
+                count,
+            } => records.next(rng) * count + tenants.next(rng),
+        }
+    }
+}
+
+/// Lengths of run phase scans, `min..=max` records following a `KeyDistribution`, the
+/// shortest most frequent under zipfian. Cheap to clone like `KeyChooser`.
+#[derive(Clone)]
+pub struct ScanLengths {
+    lengths: KeyChooser,
+    min: u64,
+}
+
+impl ScanLengths {
+    pub fn new(range: RangeInclusive<u64>, distribution: KeyDistribution) -> Result<Self> {
+        let (min, max) = range.into_inner();
+        Ok(ScanLengths {
+            lengths: KeyChooser::new(max - min + 1, distribution)?,
+            min,
+        })
+    }
+
+    #[inline]
+    pub fn next(&self, rng: &mut impl Rng) -> u64 {
+        self.lengths.next(rng) + self.min
+    }
+}
+
+/// Walker's alias method: any discrete distribution over `0..n` sampled in constant time by
+/// picking a slot uniformly and keeping it or taking its alias
+pub struct AliasTable {
+    /// Chance of keeping each slot rather than its alias
+    keep: Vec<f32>,
+    alias: Vec<u32>,
+}
+
+impl AliasTable {
+    /// Zipf with exponent 1 over `0..n`, index `i` weighted `1 / (i + 1)`
+    pub fn zipf(n: u64) -> Result<Self> {
+        if n == 0 || n > u32::MAX as u64 {
+            anyhow::bail!("alias table needs between 1 and {} entries", u32::MAX);
+        }
+        let n = n as usize;
+        let harmonic: f64 = (1..=n).map(|k| 1.0 / k as f64).sum();
+        // each slot's weight scaled so the average is 1
+        let mut scaled: Vec<f64> = (1..=n).map(|k| n as f64 / (k as f64 * harmonic)).collect();
+        let mut keep = vec![1.0f32; n];
+        let mut alias: Vec<u32> = (0..n as u32).collect();
+        let (mut small, mut large) = (Vec::with_capacity(n), Vec::with_capacity(n));
+        for i in 0..n as u32 {
+            if scaled[i as usize] < 1.0 {
+                small.push(i);
+            } else {
+                large.push(i);
+            }
+        }
+        while let (Some(s), Some(&l)) = (small.pop(), large.last()) {
+            keep[s as usize] = scaled[s as usize] as f32;
+            alias[s as usize] = l;
+            scaled[l as usize] -= 1.0 - scaled[s as usize];
+            if scaled[l as usize] < 1.0 {
+                large.pop();
+                small.push(l);
+            }
+        }
+        // whatever is left is 1 up to rounding, `keep` is already 1 for those
+        Ok(AliasTable { keep, alias })
+    }
+
+    #[inline]
+    pub fn sample(&self, rng: &mut impl Rng) -> u64 {
+        let i = rng.random_range(0..self.keep.len());
+        if rng.random::<f32>() < self.keep[i] {
+            i as u64
+        } else {
+            self.alias[i] as u64
+        }
+    }
 }
 
 impl KVSizeGen {
@@ -23,31 +283,356 @@ impl KVSizeGen {
         })
     }
 
+    /// Returns a size in `0..range`
     pub fn get_size(&mut self) -> u64 {
-        self.zipf.sample(&mut self.rng) as u64
+        self.zipf.sample(&mut self.rng) as u64 - 1
     }
 }
 
 impl ByteGen {
-    pub fn new(range: u64, seed: u64) -> Result<Self> {
-        let g = Zipf::new(range as f64, 1.0)?;
-        Ok(ByteGen {
-            zipf: g,
+    pub fn new(key_chooser: KeyChooser, hot_keys: Arc<HotKeys>, seed: u64) -> Self {
+        let key = vec![0u8; hot_keys.key_size];
+        ByteGen {
+            key_chooser,
+            hot_keys,
             rng: SmallRng::seed_from_u64(seed),
+            key,
+        }
+    }
+
+    /// The bytes of the next chosen key, valid until the next call
+    #[inline]
+    pub fn next_key(&mut self) -> &[u8] {
+        let idx = self.key_chooser.next(&mut self.rng);
+        if let Some(key) = self.hot_keys.get(idx) {
+            return key;
+        }
+        record_key(idx, self.hot_keys.format, &mut self.key);
+        &self.key
+    }
+
+    /// The bytes of the key a scan of `len` records starts at, see `KeyFormat::scan_start`,
+    /// valid until the next call
+    #[inline]
+    pub fn next_scan_key(&mut self, len: u64) -> &[u8] {
+        let idx = self.key_chooser.next(&mut self.rng);
+        let start = self.hot_keys.format.scan_start(idx, len);
+        if let Some(key) = self.hot_keys.get(start) {
+            return key;
+        }
+        record_key(start, self.hot_keys.format, &mut self.key);
+        &self.key
+    }
+
+    /// The bytes of record `idx`'s key, valid until the next call
+    #[inline]
+    pub fn key_of(&mut self, idx: u64) -> &[u8] {
+        record_key(idx, self.hot_keys.format, &mut self.key);
+        &self.key
+    }
+}
+
+/// Indexes of the records inserted during the run phase, following the loaded ones. Shared
+/// by the run threads so every insert takes a key no other insert took.
+pub struct InsertCounter(AtomicU64);
+
+impl InsertCounter {
+    pub fn new(record_count: u64) -> Self {
+        InsertCounter(AtomicU64::new(record_count))
+    }
+
+    #[inline]
+    pub fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Records loaded or handed out to inserts so far
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Indexes of the records deleted oldest first, in the order they were loaded and inserted.
+/// Shared by the run threads so every delete takes a record no other delete took.
+#[derive(Default)]
+pub struct DeleteCounter(AtomicU64);
+
+impl DeleteCounter {
+    #[inline]
+    pub fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Records handed out to deletes so far
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Most bytes spent on encoded hot keys
+const HOT_KEYS_MAX_BYTES: usize = 1 << 20;
+
+/// The keys of the most frequently chosen records, encoded once and shared by the run
+/// threads. Zipfian ranks records by index and a hotspot's hot set comes first, so these are
+/// the first ones; with a uniform choice no key is hotter than another and nothing is cached.
+pub struct HotKeys {
+    key_size: usize,
+    format: KeyFormat,
+    keys: Vec<u8>,
+}
+
+impl HotKeys {
+    pub fn new(
+        chooser: &KeyChooser,
+        record_count: u64,
+        key_size: usize,
+        format: KeyFormat,
+    ) -> Self {
+        let count = match chooser {
+            // a tenant's most chosen records have the lowest indexes like the others
+            KeyChooser::ZipfianAlias(_) | KeyChooser::Zipfian(_) | KeyChooser::Tenants { .. } => {
+                (HOT_KEYS_MAX_BYTES / key_size.max(1)).min(record_count as usize)
+            }
+            KeyChooser::Hotspot { hot, .. } => {
+                (HOT_KEYS_MAX_BYTES / key_size.max(1)).min(*hot as usize)
+            }
+            // the newest records move as inserts arrive
+            KeyChooser::Uniform(_) | KeyChooser::Latest { .. } => 0,
+        };
+        let mut keys = vec![0u8; count * key_size];
+        for (i, key) in keys.chunks_exact_mut(key_size.max(1)).enumerate() {
+            record_key(i as u64, format, key);
+        }
+        HotKeys {
+            key_size,
+            format,
+            keys,
+        }
+    }
+
+    #[inline]
+    fn get(&self, idx: u64) -> Option<&[u8]> {
+        let start = (idx as usize).checked_mul(self.key_size)?;
+        self.keys.get(start..start + self.key_size)
+    }
+}
+
+/// Random bytes values are sliced from, beyond the largest value
+const VALUE_POOL_BYTES: usize = 1 << 20;
+
+/// Random bytes generated once that values are sliced from at random offsets, so producing
+/// a value costs an offset instead of filling every byte
+pub struct ValuePool {
+    bytes: ValueBuffer,
+}
+
+impl ValuePool {
+    pub fn new(max_value_len: usize, rng: &mut impl RngCore) -> Self {
+        let mut bytes = ValueBuffer::new(max_value_len + VALUE_POOL_BYTES, max_value_len);
+        rng.fill_bytes(&mut bytes);
+        ValuePool { bytes }
+    }
+
+    /// Start of a value, any value up to `max_value_len` fits behind it
+    pub fn offset(&self, rng: &mut impl Rng) -> usize {
+        rng.random_range(0..VALUE_POOL_BYTES)
+    }
+
+    pub fn get(&self, offset: usize, len: usize) -> &[u8] {
+        &self.bytes[offset..offset + len]
+    }
+
+    /// A value of `len` bytes at a random offset
+    pub fn next(&self, len: usize, rng: &mut impl Rng) -> &[u8] {
+        self.get(self.offset(rng), len)
+    }
+
+    /// Start of the value of record `idx`, the same for every pool generated from one seed
+    fn record_offset(&self, idx: u64) -> usize {
+        (mix(idx) % VALUE_POOL_BYTES as u64) as usize
+    }
+}
+
+/// Keys and values of the records the load phase inserts. Record `i`'s value is sliced from
+/// an offset derived from `i`, so with the same seed any record's value can be regenerated
+/// to validate what the database returns.
+pub struct RecordGen {
+    values: ValuePool,
+    sizes: KVSizeGen,
+    min_value_len: u64,
+    key: Vec<u8>,
+    format: KeyFormat,
+}
+
+impl RecordGen {
+    pub fn new(
+        key_size: usize,
+        format: KeyFormat,
+        value_size: RangeInclusive<u64>,
+        seed: u64,
+    ) -> Result<Self> {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let (min, max) = value_size.into_inner();
+        Ok(RecordGen {
+            values: ValuePool::new(max as usize, &mut rng),
+            sizes: KVSizeGen::new(max - min + 1, rng.random())?,
+            min_value_len: min,
+            key: vec![0u8; key_size],
+            format,
         })
     }
 
-    pub fn get_key_bytes(&mut self, size: u64) -> Vec<u8> {
-        let idx = self.zipf.sample(&mut self.rng) as u64;
-        let mut bytes = vec![0u8; size as usize];
+    /// Key and value of record `idx`, valid until the next call
+    pub fn record(&mut self, idx: u64) -> (&[u8], &[u8]) {
+        record_key(idx, self.format, &mut self.key);
+        let len = (self.sizes.get_size() + self.min_value_len) as usize;
+        (&self.key, self.value(idx, len))
+    }
+
+    /// The first `len` bytes of record `idx`'s value
+    pub fn value(&self, idx: u64, len: usize) -> &[u8] {
+        self.values.get(self.values.record_offset(idx), len)
+    }
+}
+
+/// Fills `key` with the bytes of record `idx`, as loaded by the load phase
+pub fn record_key(idx: u64, format: KeyFormat, key: &mut [u8]) {
+    match format.order {
+        KeyOrder::Hashed => hashed_key(idx, key),
+        KeyOrder::Ordered => ordered_key(idx, key),
+        KeyOrder::TimeSeries => {
+            let series = u64::from(format.series_count);
+            ordered_key(idx / series, key);
+            prefix_key(idx % series, key);
+        }
+    }
+    if format.tenant_count > 1 {
+        prefix_key(idx % u64::from(format.tenant_count), key);
+    }
+}
+
+/// Bytes mixed from `idx`, scattering consecutive records
+fn hashed_key(idx: u64, key: &mut [u8]) {
+    let mut state = mix(idx);
+    for chunk in key.chunks_mut(8) {
+        state = state.wrapping_add(GOLDEN_GAMMA);
+        chunk.copy_from_slice(&mix(state).to_le_bytes()[..chunk.len()]);
+    }
+}
+
+/// `id` as 4 bytes big-endian at the start of `key`
+fn prefix_key(id: u64, key: &mut [u8]) {
+    let id = (id as u32).to_be_bytes();
+    let len = key.len().min(id.len());
+    key[..len].copy_from_slice(&id[..len]);
+}
+
+/// `idx` big-endian at the end of `key`, zeros before it
+fn ordered_key(idx: u64, key: &mut [u8]) {
+    let bytes = idx.to_be_bytes();
+    let len = key.len().min(bytes.len());
+    let (zeros, tail) = key.split_at_mut(key.len() - len);
+    zeros.fill(0);
+    tail.copy_from_slice(&bytes[bytes.len() - len..]);
+}
+
+/// Seed of run thread `thread`, so every thread draws its own stream that is still
+/// reproducible from the workload's seed
+pub fn thread_seed(seed: u64, thread: u64) -> u64 {
+    mix(seed ^ mix(thread.wrapping_add(1)))
+}
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// splitmix64 finalizer, a bijection scattering consecutive inputs
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: usize = 1_000_000;
+
+    /// Share of `SAMPLES` draws of `chooser` landing on each of `0..n`, panicking on any
+    /// index at or past `n`
+    fn frequencies(chooser: &KeyChooser, n: u64) -> Vec<f64> {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut counts = vec![0u64; n as usize];
+        for _ in 0..SAMPLES {
+            let i = chooser.next(&mut rng);
+            assert!(i < n, "chose {i} out of 0..{n}");
+            counts[i as usize] += 1;
+        }
+        counts
+            .into_iter()
+            .map(|c| c as f64 / SAMPLES as f64)
+            .collect()
+    }
+
+    fn zipf_weights(n: u64) -> Vec<f64> {
+        let harmonic: f64 = (1..=n).map(|k| 1.0 / k as f64).sum();
+        (1..=n).map(|k| 1.0 / (k as f64 * harmonic)).collect()
+    }
+
+    #[test]
+    fn zipfian_choosers_reach_every_index_and_no_further() {
+        let n = 50;
+        for chooser in [
+            KeyChooser::ZipfianAlias(Arc::new(AliasTable::zipf(n).unwrap())),
+            KeyChooser::Zipfian(Zipf::new(n as f64, 1.0).unwrap()),
+        ] {
+            assert!(frequencies(&chooser, n).iter().all(|&f| f > 0.0));
+        }
+        let one = KeyChooser::new(1, KeyDistribution::Zipfian).unwrap();
+        assert_eq!(frequencies(&one, 1), [1.0]);
+    }
 
-        SmallRng::seed_from_u64(idx).fill_bytes(&mut bytes[..]);
-        bytes
+    #[test]
+    fn alias_table_follows_zipf_weights() {
+        let n = 10;
+        let chooser = KeyChooser::ZipfianAlias(Arc::new(AliasTable::zipf(n).unwrap()));
+        for (i, (seen, expected)) in frequencies(&chooser, n)
+            .into_iter()
+            .zip(zipf_weights(n))
+            .enumerate()
+        {
+            assert!(
+                (seen - expected).abs() < 0.003,
+                "index {i}: frequency {seen}, weight {expected}"
+            );
+        }
     }
 
-    pub fn get_value_bytes(&mut self, size: u64) -> Vec<u8> {
-        let mut bytes = vec![0u8; size as usize];
-        self.rng.fill_bytes(&mut bytes[..]);
-        bytes
+    #[test]
+    fn alias_table_and_fallback_agree() {
+        let n = 1_000;
+        let alias = frequencies(&KeyChooser::new(n, KeyDistribution::Zipfian).unwrap(), n);
+        let fallback = frequencies(&KeyChooser::Zipfian(Zipf::new(n as f64, 1.0).unwrap()), n);
+        // the head index by index, then the tail in buckets whose shares are large enough
+        // to compare
+        for i in 0..10 {
+            assert!(
+                (alias[i] - fallback[i]).abs() < 0.003,
+                "index {i}: alias {}, fallback {}",
+                alias[i],
+                fallback[i]
+            );
+        }
+        for bucket in [10..100, 100..500, 500..1_000] {
+            let (a, f): (f64, f64) = (
+                alias[bucket.clone()].iter().sum(),
+                fallback[bucket.clone()].iter().sum(),
+            );
+            assert!(
+                (a - f).abs() < 0.005,
+                "indexes {bucket:?}: alias {a}, fallback {f}"
+            );
+        }
     }
 }
@@ -0,0 +1,125 @@
+use crate::bench::Headline;
+use crate::environment::Environment;
+use crate::workload::WorkloadStats;
+use crate::workload::spec::WorkloadSpec;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bump when a field of `RunRecord` changes meaning or is removed
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// One benchmark run, as stored in result and history files
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub schema_version: u32,
+    /// Seconds since the unix epoch when the run finished
+    pub timestamp: u64,
+    pub database: String,
+    pub workload: WorkloadSpec,
+    pub environment: Environment,
+    /// Revision of the engine under test, as given on the command line
+    pub engine_revision: Option<String>,
+    /// Tags given with `--label name=value`, e.g. `cache=512MB`, to group runs by later
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    pub headline: Headline,
+    pub stats: WorkloadStats,
+}
+
+impl RunRecord {
+    pub fn new(
+        database: String,
+        workload: WorkloadSpec,
+        engine_revision: Option<String>,
+        labels: BTreeMap<String, String>,
+        environment: Environment,
+        stats: WorkloadStats,
+    ) -> Self {
+        RunRecord {
+            schema_version: SCHEMA_VERSION,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            database,
+            workload,
+            environment,
+            engine_revision,
+            labels,
+            headline: Headline::from(&stats),
+            stats,
+        }
+    }
+}
+
+impl RunRecord {
+    /// Brings a record of an older schema version up to the current one: version 1 kept
+    /// latencies in microseconds rather than nanoseconds
+    pub fn upgrade(mut self) -> Result<Self> {
+        if self.schema_version < 2 {
+            self.stats.micros_to_nanos()?;
+            self.schema_version = 2;
+        }
+        Ok(self)
+    }
+}
+
+/// Appends `record` as one json line to the history file at `path`
+pub fn append(path: &Path, record: &RunRecord) -> Result<()> {
+    let mut f = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open history file {}", path.display()))?;
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    f.write_all(line.as_bytes())
+        .with_context(|| format!("failed to append to history file {}", path.display()))
+}
+
+/// `labels` as `name=value` separated by commas, empty without labels
+pub fn format_labels(labels: &BTreeMap<String, String>) -> String {
+    labels
+        .iter()
+        .map(|(k, v)| format!("{k}={v}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parses a `--label` argument
+pub fn parse_label(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((k, v)) if !k.trim().is_empty() => Ok((k.trim().to_string(), v.trim().to_string())),
+        _ => Err(format!("expected a label as `name=value`, got `{s}`")),
+    }
+}
+
+/// Reads every record of the history file at `path`
+pub fn read(path: &Path) -> Result<Vec<RunRecord>> {
+    let f = std::fs::File::open(path)
+        .with_context(|| format!("failed to open history file {}", path.display()))?;
+    let mut records = vec![];
+    for (i, line) in BufReader::new(f).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: RunRecord = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: invalid run record", path.display(), i + 1))?;
+        if record.schema_version > SCHEMA_VERSION {
+            bail!(
+                "{}:{}: schema version {} is newer than supported version {}",
+                path.display(),
+                i + 1,
+                record.schema_version,
+                SCHEMA_VERSION
+            );
+        }
+        records.push(record.upgrade()?);
+    }
+    Ok(records)
+}
@@ -0,0 +1,118 @@
+use std::ops::{Deref, DerefMut};
+
+/// Values at least this large are generated into hugepage-backed buffers
+pub const THRESHOLD_BYTES: usize = 1 << 20;
+
+/// A zeroed byte buffer for values. From `THRESHOLD_BYTES` on it is mapped with explicit
+/// hugepages where the kernel has some reserved, or advised for transparent hugepages
+/// otherwise, so slicing multi-MB values out of it does not thrash the client's TLB.
+pub struct ValueBuffer {
+    inner: Inner,
+}
+
+enum Inner {
+    Heap(Vec<u8>),
+    #[cfg(target_os = "linux")]
+    Mapped {
+        ptr: *mut u8,
+        len: usize,
+        /// `len` rounded up to the page size the mapping was made with
+        mapped_len: usize,
+    },
+}
+
+// SAFETY: the mapping is owned exclusively by the buffer, like a Vec's allocation
+unsafe impl Send for ValueBuffer {}
+unsafe impl Sync for ValueBuffer {}
+
+impl ValueBuffer {
+    /// A buffer of `len` bytes, on hugepages when it holds values from `max_value_len` on
+    pub fn new(len: usize, max_value_len: usize) -> Self {
+        #[cfg(target_os = "linux")]
+        if max_value_len >= THRESHOLD_BYTES
+            && let Some((ptr, mapped_len)) = map(len)
+        {
+            return ValueBuffer {
+                inner: Inner::Mapped {
+                    ptr,
+                    len,
+                    mapped_len,
+                },
+            };
+        }
+        let _ = max_value_len;
+        ValueBuffer {
+            inner: Inner::Heap(vec![0u8; len]),
+        }
+    }
+}
+
+/// Maps `len` anonymous bytes, trying explicit hugepages first and falling back to a regular
+/// mapping advised for transparent hugepages
+#[cfg(target_os = "linux")]
+fn map(len: usize) -> Option<(*mut u8, usize)> {
+    const HUGEPAGE: usize = 2 << 20;
+    let prot = libc::PROT_READ | libc::PROT_WRITE;
+    let flags = libc::MAP_PRIVATE | libc::MAP_ANONYMOUS;
+    let huge_len = len.next_multiple_of(HUGEPAGE);
+    // SAFETY: anonymous mappings with no address hint, checked for MAP_FAILED
+    unsafe {
+        let ptr = libc::mmap(
+            std::ptr::null_mut(),
+            huge_len,
+            prot,
+            flags | libc::MAP_HUGETLB,
+            -1,
+            0,
+        );
+        if ptr != libc::MAP_FAILED {
+            return Some((ptr.cast(), huge_len));
+        }
+        let ptr = libc::mmap(std::ptr::null_mut(), len, prot, flags, -1, 0);
+        if ptr == libc::MAP_FAILED {
+            return None;
+        }
+        // best effort, THP may be disabled
+        libc::madvise(ptr, len, libc::MADV_HUGEPAGE);
+        Some((ptr.cast(), len))
+    }
+}
+
+impl Drop for ValueBuffer {
+    fn drop(&mut self) {
+        #[cfg(target_os = "linux")]
+        if let Inner::Mapped {
+            ptr, mapped_len, ..
+        } = self.inner
+        {
+            // SAFETY: mapped by `map` and not referenced past the buffer's lifetime
+            unsafe {
+                libc::munmap(ptr.cast(), mapped_len);
+            }
+        }
+    }
+}
+
+impl Deref for ValueBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match &self.inner {
+            Inner::Heap(v) => v,
+            #[cfg(target_os = "linux")]
+            // SAFETY: `len` bytes mapped readable and writable, zeroed by the kernel
+            Inner::Mapped { ptr, len, .. } => unsafe { std::slice::from_raw_parts(*ptr, *len) },
+        }
+    }
+}
+
+impl DerefMut for ValueBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match &mut self.inner {
+            Inner::Heap(v) => v,
+            #[cfg(target_os = "linux")]
+            // SAFETY: as in `deref`, `&mut self` makes the access exclusive
+            Inner::Mapped { ptr, len, .. } => unsafe { std::slice::from_raw_parts_mut(*ptr, *len) },
+        }
+    }
+}
@@ -0,0 +1,164 @@
+pub mod bench;
+pub mod cache;
+pub mod cgroup;
+pub mod cli;
+pub mod compare;
+pub mod container;
+pub mod corpus;
+pub mod cpufreq;
+pub mod database;
+pub mod diskstats;
+pub mod energy;
+pub mod environment;
+pub mod fill;
+pub mod generator;
+pub mod history;
+pub mod hugepage;
+pub mod netem;
+pub mod oplog;
+pub mod profile;
+pub mod properties;
+pub mod sampler;
+pub mod serialize;
+pub mod store;
+pub mod summary;
+pub mod trace;
+pub mod upload;
+pub mod workload;
+pub mod ycsb;
+
+use clap::ValueEnum;
+
+#[derive(Copy, Clone, ValueEnum)]
+pub enum WorkloadType {
+    /// Even mix of point reads and updates
+    ReadWrite,
+    /// Mostly point reads with occasional updates
+    ReadHeavy,
+    /// Point reads only
+    ReadOnly,
+    /// Inserts of new keys only, bulk ingest into the loaded records
+    InsertOnly,
+    /// Inserts of new keys only, each key sorting after the ones before it like a timestamp or counter, into records loaded in key order
+    SequentialInsert,
+    /// Even mix of point reads and read-modify-writes, each reading a record and writing it back
+    ReadModifyWrite,
+    /// Reads alongside inserts of new keys and deletes of loaded ones in equal parts, keeping the keyspace about the same size while tombstones pile up
+    Churn,
+    /// Inserts of new keys matched by deletes of the oldest records, keeping the live records at the loaded count over a long run while space waits to be reclaimed, with reads of recent records
+    FifoChurn,
+    /// Even mix of point reads and updates, 90% of them on a hot 10% of the keys
+    Hotspot,
+    /// Mostly point reads of 16 byte keys and 32 to 128 byte values at high operation counts, like a session or metadata cache where the per-operation overhead dominates
+    TinyCache,
+    /// Even mix of point reads and updates by every thread on the same 4 keys, for writer contention and reads waiting on writes
+    Contention,
+    /// Mostly point reads with some updates over 8 tenants, each key prefixed by its tenant, the busiest tenants taking most operations, with each tenant's throughput
+    MultiTenant,
+    /// Even mix of point reads and updates in 50 ms bursts at full rate, each followed by 200 ms idle
+    Bursty,
+    /// Mostly point reads skewed to the newest records while inserts keep adding new ones
+    ReadLatest,
+    /// Mostly range scans of 1 to 100 records in key order, with occasional point reads
+    RangeScan,
+    /// Time series ingestion: appends of new points to 100 series, with scans of the latest 10 to 100 points of recently written series
+    TimeSeries,
+    /// Operations recorded with `--record-ops`, replayed from `-p replay_ops=<file>` as fast as the database allows, ignoring the recorded timing
+    Replay,
+    /// Any mix of the workload fields, read from a TOML file set with `-p file=<file>`, the defaults for fields it leaves out
+    Custom,
+    /// YCSB workload A, update heavy: 50% reads and 50% updates
+    YcsbA,
+    /// YCSB workload B, read mostly: 95% reads and 5% updates
+    YcsbB,
+    /// YCSB workload C, read only
+    YcsbC,
+    /// YCSB workload D, read latest: 95% reads skewed to the newest records and 5% inserts
+    YcsbD,
+    /// YCSB workload E, short ranges: 95% scans of up to 100 records and 5% inserts
+    YcsbE,
+    /// YCSB workload F, read-modify-write: 50% reads and 50% read-modify-writes
+    YcsbF,
+}
+
+#[derive(Copy, Clone, ValueEnum)]
+// Update database::get_db when adding new variation
+pub enum DatabaseType {
+    /// In-memory BTreeMap behind a RwLock, a baseline without persistence
+    MemBtree,
+    /// In-memory DashMap, a sharded hash map with a lock per shard, without persistence
+    MemDashmap,
+    /// In-memory HashMap behind a single Mutex, a naive baseline without persistence
+    MemHashmap,
+    /// In-memory crossbeam SkipMap, an ordered lock-free skip list, without persistence
+    MemSkipmap,
+    /// Discards every operation, to measure the benchmark's own overhead
+    Null,
+    /// redb, an embedded copy-on-write B-tree, one write transaction per set
+    Redb,
+    /// sled, an embedded lock-free Bw-tree-like store
+    Sled,
+    /// RocksDB, an embedded LSM tree (needs `--features rocksdb`)
+    #[value(name = "rocksdb")]
+    RocksDb,
+    /// RocksDB as a pessimistic TransactionDB, one locking transaction per set (needs `--features rocksdb`)
+    #[value(name = "rocksdb-txn")]
+    RocksDbTxn,
+    /// LMDB through heed, an embedded mmap-based B+tree, one write transaction per set (needs `--features lmdb`)
+    Lmdb,
+    /// fjall, a pure-Rust embedded LSM tree, writes journaled without an fsync each (needs `--features fjall`)
+    Fjall,
+    /// LevelDB as ported by rusty-leveldb, not concurrent so every operation takes one lock (needs `--features leveldb`)
+    #[value(name = "leveldb")]
+    LevelDb,
+    /// persy, an embedded store of segments and indexes, one transaction per set (needs `--features persy`)
+    Persy,
+    /// jammdb, an embedded single-writer mmap B+tree ported from BoltDB, one write transaction per set (needs `--features jammdb`)
+    #[value(name = "jammdb")]
+    JammDb,
+    /// canopydb, an embedded MVCC B+tree with a write-ahead log, one write transaction per set (needs `--features canopydb`)
+    #[value(name = "canopydb")]
+    CanopyDb,
+    /// sanakirja, an embedded copy-on-write B-tree, one write transaction per set, keys up to 509 bytes and values split into 256 byte entries (needs `--features sanakirja`)
+    Sanakirja,
+    /// marble, sled's garbage-collecting object heap, sets buffered and written `db.batch_size` (default 256) at a time as one heap file (needs `--features marble`)
+    Marble,
+    /// Redis over the network, `-p db.url=redis://host:port/` (default localhost), pipelines sent as one batch (needs `--features redis`)
+    Redis,
+    /// A Redis Cluster, each key routed to the node owning its slot, `-p db.nodes=redis://host:port/,...` seed nodes (default localhost), pipelines split by node (needs `--features redis`)
+    #[value(name = "redis-cluster")]
+    RedisCluster,
+    /// memcached over the network, `-p db.url=memcache://host:port` (default localhost), read misses counted rather than failed (needs `--features memcached`)
+    Memcached,
+    /// TiKV through its raw API, `-p db.pd_endpoints=host:port,...` (default localhost) (needs `--features tikv`)
+    Tikv,
+    /// etcd v3 over gRPC, `-p db.endpoints=host:port,...` (default localhost) (needs `--features etcd`)
+    Etcd,
+    /// PostgreSQL as a key-value table, sets as `INSERT ... ON CONFLICT DO UPDATE`, `-p db.url=<connection string>` (default localhost) (needs `--features postgres`)
+    Postgres,
+    /// Amazon DynamoDB, or DynamoDB Local with `-p db.url=http://host:port` (needs `--features dynamodb`)
+    #[value(name = "dynamodb")]
+    DynamoDb,
+    /// ScyllaDB or Cassandra over CQL, one blob-keyed table, `-p db.nodes=host:port,...` (default localhost) (needs `--features scylla`)
+    Scylla,
+    /// Aerospike, one record per key in `-p db.namespace=...` (default test) and `-p db.set=...`, `-p db.hosts=host:port,...` (default localhost) (needs `--features aerospike`)
+    Aerospike,
+    /// Couchbase through its KV service, raw binary documents in the default collection of `-p db.bucket=...` (default kvbencher), `-p db.url=couchbase://host,...` (default localhost) (needs `--features couchbase`)
+    Couchbase,
+    /// An S3 compatible object store, one object per key in `-p db.bucket=...` (default kvbencher), `-p db.url=http://host:port` for MinIO and others (default AWS) (needs `--features object-store`)
+    #[value(name = "object-store")]
+    ObjectStore,
+    /// SurrealKV, an embedded MVCC LSM tree, one transaction per set committed without an fsync (needs `--features surrealkv`)
+    #[value(name = "surrealkv")]
+    SurrealKv,
+    /// One file per key below the data dir, named by the hex encoded key, a filesystem reference
+    FilePerKey,
+    /// An external command speaking a length-prefixed protocol on stdin/stdout, set with `-p db.command=...`
+    External,
+    /// A backend loaded from a shared object implementing the C plugin interface, set with `-p db.path=...` (needs `--features plugin`)
+    Plugin,
+    /// Any server implementing the Kv service of proto/kvbencher.proto, set with `-p db.url=...` (needs `--features grpc`)
+    Grpc,
+    /// Any server taking `GET` and `PUT /kv/{key}` below a base URL, set with `-p db.url=...` (needs `--features http`)
+    Http,
+}
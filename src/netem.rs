@@ -0,0 +1,177 @@
+use crate::database::{Database, EngineActivity, PipelinedOp};
+use anyhow::Result;
+use rand::Rng;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Retransmission delay added to an operation whose packets are "lost", Linux's minimum
+/// TCP retransmission timeout
+const LOSS_PENALTY: Duration = Duration::from_millis(200);
+
+/// Simulated network between the benchmark and the database, applied per operation
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NetworkConditions {
+    /// Round trip time added to every operation
+    pub latency: Duration,
+    /// Maximum random deviation from `latency`, in either direction
+    pub jitter: Duration,
+    /// Fraction of operations that suffer a retransmission
+    pub loss: f64,
+}
+
+impl NetworkConditions {
+    pub fn is_empty(&self) -> bool {
+        self.latency.is_zero() && self.jitter.is_zero() && self.loss == 0.0
+    }
+
+    /// `db` with every operation delayed as if it crossed this network
+    pub fn wrap(self, db: Arc<dyn Database>) -> Arc<dyn Database> {
+        Arc::new(DelayedDatabase {
+            inner: db,
+            conditions: self,
+        })
+    }
+
+    fn delay(&self) -> Duration {
+        let mut rng = rand::rng();
+        let mut delay = self.latency;
+        if !self.jitter.is_zero() {
+            let jitter = rng.random_range(-1.0..=1.0) * self.jitter.as_secs_f64();
+            delay = Duration::from_secs_f64((delay.as_secs_f64() + jitter).max(0.0));
+        }
+        if self.loss > 0.0 && rng.random_bool(self.loss) {
+            delay += LOSS_PENALTY;
+        }
+        delay
+    }
+}
+
+/// In-process stand-in for `tc netem`, delaying each call before it reaches the database
+struct DelayedDatabase {
+    inner: Arc<dyn Database>,
+    conditions: NetworkConditions,
+}
+
+impl DelayedDatabase {
+    fn wait(&self) {
+        let delay = self.conditions.delay();
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
+        }
+    }
+}
+
+impl Database for DelayedDatabase {
+    fn init(&self) -> Result<()> {
+        self.inner.init()
+    }
+
+    fn get(&self, key: &[u8]) -> Result<()> {
+        self.wait();
+        self.inner.get(key)
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.wait();
+        self.inner.set(key, value)
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.wait();
+        self.inner.delete(key)
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> Result<usize> {
+        self.wait();
+        self.inner.scan(start, count)
+    }
+
+    /// A read and then a write, two round trips
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.wait();
+        self.wait();
+        self.inner.read_modify_write(key, value)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        self.inner.data_dir()
+    }
+
+    fn preallocate(&self, bytes: u64) -> Result<()> {
+        self.inner.preallocate(bytes)
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        self.inner.engine_activity()
+    }
+
+    fn read_misses(&self) -> Option<u64> {
+        self.inner.read_misses()
+    }
+
+    fn supports_tables(&self) -> bool {
+        self.inner.supports_tables()
+    }
+
+    fn create_tables(&self, count: u32) -> Result<()> {
+        self.inner.create_tables(count)
+    }
+
+    fn get_in(&self, table: u32, key: &[u8]) -> Result<()> {
+        self.wait();
+        self.inner.get_in(table, key)
+    }
+
+    fn set_in(&self, table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+        self.wait();
+        self.inner.set_in(table, key, value)
+    }
+
+    fn delete_in(&self, table: u32, key: &[u8]) -> Result<()> {
+        self.wait();
+        self.inner.delete_in(table, key)
+    }
+
+    fn scan_in(&self, table: u32, start: &[u8], count: usize) -> Result<usize> {
+        self.wait();
+        self.inner.scan_in(table, start, count)
+    }
+
+    fn read_modify_write_in(&self, table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+        self.wait();
+        self.wait();
+        self.inner.read_modify_write_in(table, key, value)
+    }
+
+    /// One round trip for the whole pipeline
+    fn pipeline(&self, ops: &[PipelinedOp]) -> Result<()> {
+        self.wait();
+        self.inner.pipeline(ops)
+    }
+}
+
+/// Parses durations like `20ms`, `1.5s` or `250us`
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (value, unit) = s.split_at(split);
+    let scale = match unit.trim() {
+        "ns" => 1e-9,
+        "us" | "µs" => 1e-6,
+        "ms" => 1e-3,
+        "s" => 1.0,
+        _ => {
+            return Err(format!(
+                "expected a duration like `20ms` or `1.5s`, got `{s}`"
+            ));
+        }
+    };
+    value
+        .parse::<f64>()
+        .ok()
+        .and_then(|v| Duration::try_from_secs_f64(v * scale).ok())
+        .ok_or_else(|| format!("invalid duration `{s}`"))
+}
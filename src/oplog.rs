@@ -0,0 +1,316 @@
+use crate::corpus::OpKind;
+use crate::workload::spec::WorkloadSpec;
+use anyhow::{Context, Result, bail};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::Instant;
+
+/// Start of every operation log, the trailing digits are the format version
+const MAGIC: &[u8; 8] = b"KVBOPS01";
+
+// Layout, integers little-endian unless noted:
+//   MAGIC
+//   u32 length + json of the recorded `WorkloadSpec`
+//   u32 thread count, then per thread:
+//     u64 operation count, then per operation:
+//       u8 kind (0 read, 1 write, 2 read-modify-write, 3 delete, 4 scan, 5 insert)
+//       varint nanoseconds since the previous operation of the thread (the run start for the first)
+//       varint key length + key bytes
+//       varint value length, writes and read-modify-writes only
+//       varint records to read, scans only
+
+/// The operations one run thread executed, appended to as they are issued
+pub struct ThreadLog {
+    epoch: Instant,
+    last_ns: u64,
+    ops: u64,
+    bytes: Vec<u8>,
+}
+
+impl ThreadLog {
+    /// Timestamps are taken relative to `epoch`, the start of the run phase
+    pub fn new(epoch: Instant) -> Self {
+        ThreadLog {
+            epoch,
+            last_ns: 0,
+            ops: 0,
+            bytes: vec![],
+        }
+    }
+
+    /// `len` is the length of the value written, or the records a scan reads
+    #[inline]
+    pub fn push(&mut self, kind: OpKind, key: &[u8], len: usize) {
+        let now = self.epoch.elapsed().as_nanos() as u64;
+        self.bytes.push(match kind {
+            OpKind::Read => 0,
+            OpKind::Write => 1,
+            OpKind::ReadModifyWrite => 2,
+            OpKind::Delete => 3,
+            OpKind::Scan => 4,
+            OpKind::Insert => 5,
+        });
+        put_varint(&mut self.bytes, now.saturating_sub(self.last_ns));
+        put_varint(&mut self.bytes, key.len() as u64);
+        self.bytes.extend_from_slice(key);
+        if kind.has_value() || kind == OpKind::Scan {
+            put_varint(&mut self.bytes, len as u64);
+        }
+        self.last_ns = now;
+        self.ops += 1;
+    }
+}
+
+/// Writes the operations of every run thread of a run of `spec` to `path`
+pub fn write(path: &Path, spec: &WorkloadSpec, threads: &[ThreadLog]) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut w = BufWriter::new(file);
+    w.write_all(MAGIC)?;
+    let spec = serde_json::to_vec(spec)?;
+    w.write_all(&(spec.len() as u32).to_le_bytes())?;
+    w.write_all(&spec)?;
+    w.write_all(&(threads.len() as u32).to_le_bytes())?;
+    for t in threads {
+        w.write_all(&t.ops.to_le_bytes())?;
+        w.write_all(&t.bytes)?;
+    }
+    w.flush()
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// An operation log read back for replay
+pub struct OpLog {
+    /// The workload the operations were recorded from, its records are what the log's keys
+    /// refer to
+    pub spec: WorkloadSpec,
+    pub threads: Vec<ReplayThread>,
+}
+
+/// The operations of one recorded run thread, replayed by one thread in order
+pub struct ReplayThread {
+    keys: Vec<u8>,
+    ops: Vec<ReplayOp>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReplayOp {
+    pub kind: OpKind,
+    /// When the operation was issued, relative to the start of the recorded run phase. Replay
+    /// does not pace to it, it runs the operations back to back.
+    pub at_nanos: u64,
+    key_offset: usize,
+    key_len: u32,
+    pub value_len: u32,
+    pub scan_length: u32,
+}
+
+impl ReplayThread {
+    pub fn ops(&self) -> &[ReplayOp] {
+        &self.ops
+    }
+
+    pub fn key(&self, op: &ReplayOp) -> &[u8] {
+        &self.keys[op.key_offset..op.key_offset + op.key_len as usize]
+    }
+}
+
+impl OpLog {
+    pub fn read(path: &Path) -> Result<Self> {
+        let mut r = open(path)?;
+        let spec = read_spec(&mut r, path)?;
+        let thread_count = read_u32(&mut r)?;
+        let mut threads = Vec::with_capacity(thread_count as usize);
+        for _ in 0..thread_count {
+            let count = read_u64(&mut r)?;
+            let mut keys = vec![];
+            let mut ops = Vec::with_capacity(count as usize);
+            let mut at_nanos = 0u64;
+            for _ in 0..count {
+                let kind = match read_u8(&mut r)? {
+                    0 => OpKind::Read,
+                    1 => OpKind::Write,
+                    2 => OpKind::ReadModifyWrite,
+                    3 => OpKind::Delete,
+                    4 => OpKind::Scan,
+                    5 => OpKind::Insert,
+                    other => bail!("{}: unknown operation kind {other}", path.display()),
+                };
+                at_nanos += read_varint(&mut r)?;
+                let key_len = read_varint(&mut r)? as usize;
+                let key_offset = keys.len();
+                keys.resize(key_offset + key_len, 0);
+                r.read_exact(&mut keys[key_offset..])?;
+                let value_len = match kind.has_value() {
+                    true => read_varint(&mut r)? as u32,
+                    false => 0,
+                };
+                let scan_length = match kind {
+                    OpKind::Scan => read_varint(&mut r)? as u32,
+                    _ => 0,
+                };
+                ops.push(ReplayOp {
+                    kind,
+                    at_nanos,
+                    key_offset,
+                    key_len: key_len as u32,
+                    value_len,
+                    scan_length,
+                });
+            }
+            threads.push(ReplayThread { keys, ops });
+        }
+        Ok(OpLog { spec, threads })
+    }
+
+    /// Only the recorded workload, without reading the operations
+    pub fn read_spec(path: &Path) -> Result<WorkloadSpec> {
+        read_spec(&mut open(path)?, path)
+    }
+
+    /// Length of the largest value written by any operation
+    pub fn max_value_len(&self) -> usize {
+        self.threads
+            .iter()
+            .flat_map(|t| &t.ops)
+            .map(|op| op.value_len as usize)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+fn open(path: &Path) -> Result<BufReader<File>> {
+    let file = File::open(path)
+        .with_context(|| format!("failed to open operation log {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
+
+fn read_spec(r: &mut impl Read, path: &Path) -> Result<WorkloadSpec> {
+    let mut magic = [0u8; 8];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        bail!("{} is not a kvbencher operation log", path.display());
+    }
+    let mut spec = vec![0u8; read_u32(r)? as usize];
+    r.read_exact(&mut spec)?;
+    serde_json::from_slice(&spec)
+        .with_context(|| format!("{}: invalid recorded workload", path.display()))
+}
+
+fn put_varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+fn read_varint(r: &mut impl Read) -> Result<u64> {
+    let mut v = 0u64;
+    for shift in (0..64).step_by(7) {
+        let b = read_u8(r)?;
+        v |= ((b & 0x7f) as u64) << shift;
+        if b & 0x80 == 0 {
+            return Ok(v);
+        }
+    }
+    bail!("varint longer than 64 bits")
+}
+
+fn read_u8(r: &mut impl Read) -> Result<u8> {
+    let mut b = [0u8; 1];
+    r.read_exact(&mut b)?;
+    Ok(b[0])
+}
+
+fn read_u32(r: &mut impl Read) -> Result<u32> {
+    let mut b = [0u8; 4];
+    r.read_exact(&mut b)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_u64(r: &mut impl Read) -> Result<u64> {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b)?;
+    Ok(u64::from_le_bytes(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(dir: &Path) -> std::path::PathBuf {
+        let epoch = Instant::now();
+        let mut first = ThreadLog::new(epoch);
+        first.push(OpKind::Read, b"key-0", 0);
+        first.push(OpKind::Write, b"key-1", 100);
+        first.push(OpKind::Scan, b"key-2", 1_000);
+        let mut second = ThreadLog::new(epoch);
+        second.push(OpKind::Insert, &[0xff; 300], 70_000);
+        second.push(OpKind::Delete, b"", 0);
+        let path = dir.join("ops.bin");
+        let spec = WorkloadSpec {
+            record_count: 42,
+            ..Default::default()
+        };
+        write(&path, &spec, &[first, second]).unwrap();
+        path
+    }
+
+    #[test]
+    fn written_log_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = OpLog::read(&recorded(dir.path())).unwrap();
+        assert_eq!(log.spec.record_count, 42);
+        assert_eq!(log.threads.len(), 2);
+        let ops = |t: &ReplayThread| {
+            t.ops()
+                .iter()
+                .map(|op| (op.kind, t.key(op).to_vec(), op.value_len, op.scan_length))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ops(&log.threads[0]),
+            [
+                (OpKind::Read, b"key-0".to_vec(), 0, 0),
+                (OpKind::Write, b"key-1".to_vec(), 100, 0),
+                (OpKind::Scan, b"key-2".to_vec(), 0, 1_000),
+            ]
+        );
+        assert_eq!(
+            ops(&log.threads[1]),
+            [
+                (OpKind::Insert, vec![0xff; 300], 70_000, 0),
+                (OpKind::Delete, vec![], 0, 0),
+            ]
+        );
+        let at = log.threads[0].ops().iter().map(|op| op.at_nanos);
+        assert!(at.clone().zip(at.skip(1)).all(|(a, b)| a <= b));
+        assert_eq!(log.max_value_len(), 70_000);
+    }
+
+    #[test]
+    fn truncated_log_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = recorded(dir.path());
+        let bytes = std::fs::read(&path).unwrap();
+        for len in [4, bytes.len() / 2, bytes.len() - 1] {
+            std::fs::write(&path, &bytes[..len]).unwrap();
+            assert!(
+                OpLog::read(&path).is_err(),
+                "read {len} of {} bytes",
+                bytes.len()
+            );
+        }
+    }
+
+    #[test]
+    fn varints_round_trip() {
+        for v in [0, 1, 0x7f, 0x80, 300, u32::MAX as u64, u64::MAX] {
+            let mut buf = vec![];
+            put_varint(&mut buf, v);
+            assert_eq!(read_varint(&mut buf.as_slice()).unwrap(), v);
+        }
+    }
+}
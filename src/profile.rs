@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+/// Samples per second taken while profiling
+#[cfg(unix)]
+const FREQUENCY: i32 = 999;
+
+/// How to profile the phases of a run
+#[derive(Clone, Debug)]
+pub struct ProfileOptions {
+    /// Directory the flamegraphs are written to, one `flamegraph-<phase>.svg` per phase
+    pub dir: PathBuf,
+}
+
+impl ProfileOptions {
+    /// Where the flamegraph of `phase` is written
+    pub fn path(&self, phase: &str) -> PathBuf {
+        self.dir.join(format!("flamegraph-{phase}.svg"))
+    }
+}
+
+/// A sampling profiler running for one phase, stopped by `finish`
+pub struct PhaseProfiler {
+    #[cfg(unix)]
+    guard: pprof::ProfilerGuard<'static>,
+    path: PathBuf,
+}
+
+impl PhaseProfiler {
+    /// Starts sampling every thread of the process for `phase` (e.g. `load`)
+    pub fn start(opts: &ProfileOptions, phase: &str) -> Result<Self> {
+        let path = opts.path(phase);
+        #[cfg(unix)]
+        {
+            let guard = pprof::ProfilerGuardBuilder::default()
+                .frequency(FREQUENCY)
+                .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+                .build()
+                .context("failed to start profiler")?;
+            Ok(PhaseProfiler { guard, path })
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = path;
+            anyhow::bail!("--profile is only supported on unix")
+        }
+    }
+
+    /// Stops sampling and writes the flamegraph, returning its path
+    pub fn finish(self) -> Result<PathBuf> {
+        #[cfg(unix)]
+        {
+            let report = self
+                .guard
+                .report()
+                .build()
+                .context("failed to build profile report")?;
+            write_flamegraph(&report, &self.path)?;
+        }
+        Ok(self.path)
+    }
+}
+
+#[cfg(unix)]
+fn write_flamegraph(report: &pprof::Report, path: &std::path::Path) -> Result<()> {
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    report
+        .flamegraph(file)
+        .with_context(|| format!("failed to write flamegraph {}", path.display()))
+}
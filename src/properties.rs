@@ -0,0 +1,46 @@
+use anyhow::{Result, bail};
+use std::collections::BTreeMap;
+
+const DB_PREFIX: &str = "db.";
+
+/// `key=value` pairs passed on the command line via `-p`
+#[derive(Debug, Default, Clone)]
+pub struct Properties {
+    values: BTreeMap<String, String>,
+}
+
+impl Properties {
+    pub fn parse(args: &[String]) -> Result<Self> {
+        let mut values = BTreeMap::new();
+        for arg in args {
+            let Some((k, v)) = arg.split_once('=') else {
+                bail!("invalid property `{arg}`, expected `key=value`");
+            };
+            let k = k.trim();
+            if k.is_empty() {
+                bail!("invalid property `{arg}`, key cannot be empty");
+            }
+            values.insert(k.to_string(), v.trim().to_string());
+        }
+        Ok(Properties { values })
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// Properties that override fields of the workload spec
+    pub fn workload(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.values
+            .iter()
+            .filter(|(k, _)| !k.starts_with(DB_PREFIX))
+    }
+
+    /// Properties meant for the database adapter (`db.<name>=value`), with the prefix removed
+    pub fn db(&self) -> BTreeMap<String, String> {
+        self.values
+            .iter()
+            .filter_map(|(k, v)| Some((k.strip_prefix(DB_PREFIX)?.to_string(), v.clone())))
+            .collect()
+    }
+}
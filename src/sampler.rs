@@ -0,0 +1,271 @@
+use crate::cpufreq::CpuSample;
+use crate::database::{Database, EngineActivity, PipelinedOp};
+use crate::diskstats::{DiskCounters, DiskSample};
+use crate::environment;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// System activity during one reporting interval of the run phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntervalSample {
+    /// End of the interval, relative to the start of the run phase
+    pub elapsed_secs: f64,
+    /// Device holding the data dir, absent for in-memory databases
+    #[serde(default)]
+    pub disk: Option<DiskSample>,
+    /// Absent where the kernel exposes neither cpufreq nor thermal throttling counters
+    #[serde(default)]
+    pub cpu: Option<CpuSample>,
+    /// Background work reported by the engine at the end of the interval
+    #[serde(default)]
+    pub engine: Option<EngineActivity>,
+    /// Operations completed in the interval, recorded for engines reporting background work
+    #[serde(default)]
+    pub latency: Option<IntervalLatency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntervalLatency {
+    pub ops: u64,
+    pub mean_micro_sec: f64,
+    pub max_micro_sec: u64,
+}
+
+/// Background thread taking one `IntervalSample` per reporting interval
+pub struct Sampler {
+    stop: Sender<()>,
+    handle: JoinHandle<Vec<IntervalSample>>,
+    probe: Option<Arc<LatencyProbe>>,
+}
+
+impl Sampler {
+    /// `threads` is the number of run threads, whose cpus' frequency is sampled
+    pub fn start(interval: Duration, db: Arc<dyn Database>, threads: usize) -> Self {
+        let device = db.data_dir().and_then(environment::block_device);
+        // per-interval latency costs a few atomics per operation, so it is only recorded
+        // where there is engine activity to line it up with
+        let probe = db
+            .engine_activity()
+            .map(|_| Arc::new(LatencyProbe::default()));
+        let thread_probe = probe.clone();
+        let (stop, stopped) = mpsc::channel();
+        let handle = std::thread::spawn(move || {
+            let start = Instant::now();
+            let mut samples = vec![];
+            let mut last = Instant::now();
+            let mut disk = device.as_deref().and_then(|d| DiskCounters::read(d).ok());
+            loop {
+                let done = !matches!(
+                    stopped.recv_timeout(interval),
+                    Err(RecvTimeoutError::Timeout)
+                );
+                let now = Instant::now();
+                let elapsed = now - last;
+                let disk_now = device.as_deref().and_then(|d| DiskCounters::read(d).ok());
+                samples.push(IntervalSample {
+                    elapsed_secs: (now - start).as_secs_f64(),
+                    disk: disk
+                        .zip(disk_now)
+                        .map(|(before, after)| after.since(&before, elapsed)),
+                    cpu: CpuSample::read(threads),
+                    engine: db.engine_activity(),
+                    latency: thread_probe.as_ref().map(|p| p.take()),
+                });
+                (last, disk) = (now, disk_now);
+                if done {
+                    return samples;
+                }
+            }
+        });
+        Sampler {
+            stop,
+            handle,
+            probe,
+        }
+    }
+
+    /// `db` with its operations timed into the current interval, when the sampler needs that
+    pub fn probe(&self, db: Arc<dyn Database>) -> Arc<dyn Database> {
+        match &self.probe {
+            Some(probe) => Arc::new(ProbedDatabase {
+                inner: db,
+                probe: probe.clone(),
+            }),
+            None => db,
+        }
+    }
+
+    /// Stops sampling, the partial interval up to now becomes the last sample
+    pub fn finish(self) -> Vec<IntervalSample> {
+        let _ = self.stop.send(());
+        self.handle.join().unwrap_or_default()
+    }
+}
+
+#[derive(Default)]
+struct LatencyProbe {
+    ops: AtomicU64,
+    total_micro_sec: AtomicU64,
+    max_micro_sec: AtomicU64,
+}
+
+impl LatencyProbe {
+    fn record(&self, d: Duration) {
+        self.record_pipeline(d, 1);
+    }
+
+    /// `ops` operations that completed together after `d`
+    fn record_pipeline(&self, d: Duration, ops: u64) {
+        let micros = d.as_micros() as u64;
+        self.ops.fetch_add(ops, Ordering::Relaxed);
+        self.total_micro_sec
+            .fetch_add(micros * ops, Ordering::Relaxed);
+        self.max_micro_sec.fetch_max(micros, Ordering::Relaxed);
+    }
+
+    /// Latency since the previous call
+    fn take(&self) -> IntervalLatency {
+        let ops = self.ops.swap(0, Ordering::Relaxed);
+        let total = self.total_micro_sec.swap(0, Ordering::Relaxed);
+        IntervalLatency {
+            ops,
+            mean_micro_sec: if ops == 0 {
+                0.0
+            } else {
+                total as f64 / ops as f64
+            },
+            max_micro_sec: self.max_micro_sec.swap(0, Ordering::Relaxed),
+        }
+    }
+}
+
+struct ProbedDatabase {
+    inner: Arc<dyn Database>,
+    probe: Arc<LatencyProbe>,
+}
+
+impl ProbedDatabase {
+    fn timed<T>(&self, op: impl FnOnce() -> Result<T>) -> Result<T> {
+        let start = Instant::now();
+        let result = op();
+        self.probe.record(start.elapsed());
+        result
+    }
+}
+
+impl Database for ProbedDatabase {
+    fn init(&self) -> Result<()> {
+        self.inner.init()
+    }
+
+    fn get(&self, key: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.get(key))
+    }
+
+    fn set(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.set(key, value))
+    }
+
+    fn delete(&self, key: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.delete(key))
+    }
+
+    fn scan(&self, start: &[u8], count: usize) -> Result<usize> {
+        self.timed(|| self.inner.scan(start, count))
+    }
+
+    fn read_modify_write(&self, key: &[u8], value: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.read_modify_write(key, value))
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        self.inner.data_dir()
+    }
+
+    fn engine_activity(&self) -> Option<EngineActivity> {
+        self.inner.engine_activity()
+    }
+
+    fn read_misses(&self) -> Option<u64> {
+        self.inner.read_misses()
+    }
+
+    fn supports_tables(&self) -> bool {
+        self.inner.supports_tables()
+    }
+
+    fn create_tables(&self, count: u32) -> Result<()> {
+        self.inner.create_tables(count)
+    }
+
+    fn get_in(&self, table: u32, key: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.get_in(table, key))
+    }
+
+    fn set_in(&self, table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.set_in(table, key, value))
+    }
+
+    fn delete_in(&self, table: u32, key: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.delete_in(table, key))
+    }
+
+    fn scan_in(&self, table: u32, start: &[u8], count: usize) -> Result<usize> {
+        self.timed(|| self.inner.scan_in(table, start, count))
+    }
+
+    fn read_modify_write_in(&self, table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+        self.timed(|| self.inner.read_modify_write_in(table, key, value))
+    }
+
+    fn pipeline(&self, ops: &[PipelinedOp]) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.pipeline(ops);
+        self.probe
+            .record_pipeline(start.elapsed(), ops.len() as u64);
+        result
+    }
+}
+
+/// Mean and max latency of the intervals with and without background work, `None` unless
+/// the engine reported running flushes/compactions or stalls in at least one interval
+pub fn background_work_impact(series: &[IntervalSample]) -> Option<String> {
+    let mut busy = (0, 0.0, 0);
+    let mut idle = (0, 0.0, 0);
+    for s in series {
+        let (Some(engine), Some(latency)) = (&s.engine, &s.latency) else {
+            continue;
+        };
+        let group = if engine.is_busy() {
+            &mut busy
+        } else {
+            &mut idle
+        };
+        group.0 += 1;
+        group.1 += latency.mean_micro_sec;
+        group.2 = group.2.max(latency.max_micro_sec);
+    }
+    if busy.0 == 0 {
+        return None;
+    }
+    let fmt = |(n, mean_sum, max): (u32, f64, u64)| {
+        if n == 0 {
+            "-".to_string()
+        } else {
+            format!("mean {:.0} µs, max {max} µs", mean_sum / n as f64)
+        }
+    };
+    Some(format!(
+        "{} of {} intervals with background work: {} | without: {}",
+        busy.0,
+        busy.0 + idle.0,
+        fmt(busy),
+        fmt(idle)
+    ))
+}
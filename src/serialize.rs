@@ -0,0 +1,43 @@
+//! serde helpers for the types stored in result files
+
+/// `Duration` as fractional seconds
+pub mod duration_secs {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(d: &Duration, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_f64(d.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+        let secs = f64::deserialize(d)?;
+        Duration::try_from_secs_f64(secs).map_err(serde::de::Error::custom)
+    }
+}
+
+/// `Histogram` as base64 of the compressed HdrHistogram V2 encoding, readable by other
+/// HdrHistogram implementations
+pub mod histogram_base64 {
+    use base64::Engine;
+    use base64::engine::general_purpose::STANDARD;
+    use hdrhistogram::Histogram;
+    use hdrhistogram::serialization::V2DeflateSerializer;
+    use hdrhistogram::serialization::{Deserializer as HistDeserializer, Serializer as _};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(h: &Histogram<u64>, s: S) -> Result<S::Ok, S::Error> {
+        let mut buf = vec![];
+        V2DeflateSerializer::new()
+            .serialize(h, &mut buf)
+            .map_err(|e| serde::ser::Error::custom(format!("{e:?}")))?;
+        s.serialize_str(&STANDARD.encode(buf))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Histogram<u64>, D::Error> {
+        let encoded = String::deserialize(d)?;
+        let buf = STANDARD.decode(encoded).map_err(serde::de::Error::custom)?;
+        HistDeserializer::new()
+            .deserialize(&mut buf.as_slice())
+            .map_err(|e| serde::de::Error::custom(format!("{e:?}")))
+    }
+}
@@ -0,0 +1,200 @@
+use crate::history::RunRecord;
+use anyhow::{Context, Result};
+use rusqlite::types::Value;
+use rusqlite::{Connection, params};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// SQLite database holding every stored run, one row per run
+pub struct Store {
+    conn: Connection,
+}
+
+/// Which runs `Store::query` returns, unset fields match everything
+#[derive(Debug, Default)]
+pub struct Filter {
+    /// Database name, case-insensitive (e.g. `redb`)
+    pub database: Option<String>,
+    /// Workload name, case-insensitive (e.g. `ReadHeavy`)
+    pub workload: Option<String>,
+    /// Runs on or after this date, `YYYY-MM-DD`
+    pub since: Option<String>,
+    /// Runs before this date, `YYYY-MM-DD`
+    pub until: Option<String>,
+    /// Runs carrying every one of these labels with exactly this value
+    pub labels: Vec<(String, String)>,
+}
+
+/// One stored run as returned by `Store::query`
+#[derive(Debug)]
+pub struct RunRow {
+    pub id: i64,
+    /// `YYYY-MM-DD HH:MM:SS` in UTC
+    pub date: String,
+    pub database: String,
+    pub workload: String,
+    pub engine_revision: Option<String>,
+    pub labels: BTreeMap<String, String>,
+    pub run_ops_per_sec: f64,
+    pub read_p99_micro_sec: Option<f64>,
+    pub write_p99_micro_sec: Option<f64>,
+}
+
+/// Runs of one database and workload combination, aggregated
+#[derive(Debug)]
+pub struct AggregateRow {
+    pub database: String,
+    pub workload: String,
+    pub runs: i64,
+    pub min_ops_per_sec: f64,
+    pub avg_ops_per_sec: f64,
+    pub max_ops_per_sec: f64,
+    pub avg_read_p99_micro_sec: Option<f64>,
+    pub avg_write_p99_micro_sec: Option<f64>,
+}
+
+impl Store {
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("failed to open result store {}", path.display()))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS runs (
+                id INTEGER PRIMARY KEY,
+                schema_version INTEGER NOT NULL,
+                timestamp INTEGER NOT NULL,
+                database TEXT NOT NULL,
+                workload TEXT NOT NULL,
+                engine_revision TEXT,
+                load_ops_per_sec REAL NOT NULL,
+                run_ops_per_sec REAL NOT NULL,
+                read_p50_us REAL,
+                read_p99_us REAL,
+                write_p50_us REAL,
+                write_p99_us REAL,
+                record TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS runs_by_engine ON runs (database, workload, timestamp);",
+        )?;
+        Ok(Store { conn })
+    }
+
+    /// Stores the headline metrics as columns and the full record as json
+    pub fn insert(&self, record: &RunRecord) -> Result<i64> {
+        let h = &record.headline;
+        self.conn.execute(
+            "INSERT INTO runs (schema_version, timestamp, database, workload, engine_revision,
+                load_ops_per_sec, run_ops_per_sec, read_p50_us, read_p99_us, write_p50_us,
+                write_p99_us, record)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.schema_version,
+                record.timestamp as i64,
+                record.database,
+                record.workload.name,
+                record.engine_revision,
+                h.load_ops_per_sec,
+                h.run_ops_per_sec,
+                h.read_p50_micro_sec,
+                h.read_p99_micro_sec,
+                h.write_p50_micro_sec,
+                h.write_p99_micro_sec,
+                serde_json::to_string(record)?,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn query(&self, filter: &Filter) -> Result<Vec<RunRow>> {
+        let (clause, values) = filter.to_sql();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT id, datetime(timestamp, 'unixepoch'), database, workload, engine_revision,
+                coalesce(json_extract(record, '$.labels'), '{{}}'), run_ops_per_sec, read_p99_us,
+                write_p99_us
+             FROM runs {clause} ORDER BY timestamp, id"
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |r| {
+            Ok(RunRow {
+                id: r.get(0)?,
+                date: r.get(1)?,
+                database: r.get(2)?,
+                workload: r.get(3)?,
+                engine_revision: r.get(4)?,
+                labels: serde_json::from_str(&r.get::<_, String>(5)?).unwrap_or_default(),
+                run_ops_per_sec: r.get(6)?,
+                read_p99_micro_sec: r.get(7)?,
+                write_p99_micro_sec: r.get(8)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    pub fn aggregate(&self, filter: &Filter) -> Result<Vec<AggregateRow>> {
+        let (clause, values) = filter.to_sql();
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT database, workload, count(*), min(run_ops_per_sec), avg(run_ops_per_sec),
+                max(run_ops_per_sec), avg(read_p99_us), avg(write_p99_us)
+             FROM runs {clause} GROUP BY database, workload ORDER BY database, workload"
+        ))?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(values), |r| {
+            Ok(AggregateRow {
+                database: r.get(0)?,
+                workload: r.get(1)?,
+                runs: r.get(2)?,
+                min_ops_per_sec: r.get(3)?,
+                avg_ops_per_sec: r.get(4)?,
+                max_ops_per_sec: r.get(5)?,
+                avg_read_p99_micro_sec: r.get(6)?,
+                avg_write_p99_micro_sec: r.get(7)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+}
+
+impl Filter {
+    fn to_sql(&self) -> (String, Vec<Value>) {
+        let mut conditions = vec![];
+        let mut values = vec![];
+        let mut add = |condition: &str, value: &Option<String>| {
+            if let Some(v) = value {
+                conditions.push(condition.to_string());
+                values.push(Value::Text(v.clone()));
+            }
+        };
+        add("lower(database) = lower(?)", &self.database);
+        add("lower(workload) = lower(?)", &self.workload);
+        add(
+            "timestamp >= CAST(strftime('%s', ?) AS INTEGER)",
+            &self.since,
+        );
+        add(
+            "timestamp < CAST(strftime('%s', ?) AS INTEGER)",
+            &self.until,
+        );
+        for (name, value) in &self.labels {
+            conditions.push("json_extract(record, ?) = ?".to_string());
+            // quoted so names with dots are taken as a single key
+            values.push(Value::Text(format!("$.labels.\"{name}\"")));
+            values.push(Value::Text(value.clone()));
+        }
+        if conditions.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!("WHERE {}", conditions.join(" AND ")), values)
+        }
+    }
+}
+
+/// Accepts dates in `YYYY-MM-DD` form, as understood by SQLite's date functions
+pub fn parse_date(s: &str) -> Result<String, String> {
+    let valid = s.len() == 10
+        && s.char_indices().all(|(i, c)| match i {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+    if valid {
+        Ok(s.to_string())
+    } else {
+        Err(format!("expected a date as YYYY-MM-DD, got `{s}`"))
+    }
+}
@@ -0,0 +1,111 @@
+use crate::compare::{self, MetricDelta};
+use crate::history::{self, RunRecord};
+use std::fmt::Write;
+
+/// A baseline to judge a run against, and the change beyond which a metric regressed
+pub struct Verdict<'a> {
+    pub baseline: &'a RunRecord,
+    pub threshold_percent: f64,
+}
+
+/// Compact Markdown for CI job summaries and PR comments: the workload configuration, the
+/// headline metrics and, given a baseline, per-metric regression verdicts
+pub fn markdown(record: &RunRecord, verdict: Option<&Verdict>) -> String {
+    let mut md = String::new();
+    let _ = writeln!(
+        md,
+        "## kvbencher: {} / {}\n",
+        record.database, record.workload.name
+    );
+    write_config(&mut md, record);
+    md.push('\n');
+    match verdict {
+        None => write_metrics(&mut md, record),
+        Some(v) => write_deltas(
+            &mut md,
+            v,
+            &compare::compare(&v.baseline.stats, &record.stats),
+        ),
+    }
+    for warning in &record.stats.warnings {
+        let _ = writeln!(md, "\n> **Warning:** {warning}");
+    }
+    md
+}
+
+fn write_config(md: &mut String, record: &RunRecord) {
+    let _ = writeln!(md, "| parameter | value |\n|---|---|");
+    if let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(&record.workload) {
+        for (k, v) in fields.iter().filter(|(k, _)| *k != "name") {
+            let v = v.as_str().map_or_else(|| v.to_string(), str::to_string);
+            let _ = writeln!(md, "| {k} | {v} |");
+        }
+    }
+    if let Some(rev) = &record.engine_revision {
+        let _ = writeln!(md, "| engine revision | {rev} |");
+    }
+    for (k, v) in &record.labels {
+        let _ = writeln!(md, "| label `{k}` | {v} |");
+    }
+}
+
+fn write_metrics(md: &mut String, record: &RunRecord) {
+    let _ = writeln!(md, "| metric | value |\n|---|---:|");
+    for (name, unit, _, value) in compare::metrics(&record.stats) {
+        let _ = writeln!(
+            md,
+            "| {name} ({unit}) | {} |",
+            compare::format_value(unit, value)
+        );
+    }
+}
+
+fn write_deltas(md: &mut String, verdict: &Verdict, deltas: &[MetricDelta]) {
+    if !verdict.baseline.labels.is_empty() {
+        let _ = writeln!(
+            md,
+            "Baseline labels: {}\n",
+            history::format_labels(&verdict.baseline.labels)
+        );
+    }
+    let _ = writeln!(
+        md,
+        "| metric | baseline | candidate | change | |\n|---|---:|---:|---:|---|"
+    );
+    let mut regressions = 0;
+    for d in deltas {
+        let regressed = d.is_regression(verdict.threshold_percent);
+        regressions += usize::from(regressed);
+        let _ = writeln!(
+            md,
+            "| {} ({}) | {} | {} | {} | {} |",
+            d.name,
+            d.unit,
+            compare::format_value(d.unit, d.baseline),
+            compare::format_value(d.unit, d.candidate),
+            d.change_percent()
+                .map_or("-".to_string(), |c| format!("{c:+.1}%")),
+            if regressed { "❌ regression" } else { "✅" }
+        );
+    }
+    let _ = writeln!(md);
+    if regressions == 0 {
+        let _ = writeln!(
+            md,
+            "**No regressions** beyond {}% against {} ({}).",
+            verdict.threshold_percent,
+            verdict.baseline.database,
+            verdict
+                .baseline
+                .engine_revision
+                .as_deref()
+                .unwrap_or("baseline")
+        );
+    } else {
+        let _ = writeln!(
+            md,
+            "**{regressions} metric(s) regressed** by more than {}%.",
+            verdict.threshold_percent
+        );
+    }
+}
@@ -0,0 +1,249 @@
+/// Where and how often to export per-operation spans during the run phase
+#[derive(Clone, Debug)]
+pub struct TraceOptions {
+    /// OTLP/HTTP collector, e.g. `http://localhost:4318`
+    pub endpoint: String,
+    /// Fraction of operations exported as spans, between 0 and 1
+    pub sample_rate: f64,
+    /// Reported as the `db.system.name` attribute of every span
+    pub database: String,
+}
+
+#[cfg(feature = "otlp")]
+pub use otlp::Tracing;
+
+#[cfg(not(feature = "otlp"))]
+pub use disabled::Tracing;
+
+#[cfg(not(feature = "otlp"))]
+mod disabled {
+    use super::TraceOptions;
+    use crate::database::Database;
+    use anyhow::{Result, bail};
+    use std::sync::Arc;
+
+    pub struct Tracing;
+
+    impl Tracing {
+        pub fn start(_opts: &TraceOptions) -> Result<Self> {
+            bail!("kvbencher was built without OTLP support, rebuild with `--features otlp`")
+        }
+
+        pub fn wrap(&self, db: Arc<dyn Database>) -> Arc<dyn Database> {
+            db
+        }
+
+        pub fn shutdown(self) -> Result<()> {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "otlp")]
+mod otlp {
+    use super::TraceOptions;
+    use crate::database::{Database, EngineActivity, PipelinedOp};
+    use anyhow::{Context, Result, anyhow};
+    use opentelemetry::KeyValue;
+    use opentelemetry::trace::{Span, SpanKind, Status, Tracer, TracerProvider};
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::Resource;
+    use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+    use rand::Rng;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    /// An OTLP exporter for the duration of a run phase
+    pub struct Tracing {
+        provider: SdkTracerProvider,
+        opts: TraceOptions,
+    }
+
+    impl Tracing {
+        pub fn start(opts: &TraceOptions) -> Result<Self> {
+            let endpoint = if opts.endpoint.ends_with("/v1/traces") {
+                opts.endpoint.clone()
+            } else {
+                format!("{}/v1/traces", opts.endpoint.trim_end_matches('/'))
+            };
+            let exporter = SpanExporter::builder()
+                .with_http()
+                .with_endpoint(endpoint)
+                .build()
+                .context("failed to create OTLP exporter")?;
+            let provider = SdkTracerProvider::builder()
+                .with_resource(Resource::builder().with_service_name("kvbencher").build())
+                .with_batch_exporter(exporter)
+                .build();
+            Ok(Tracing {
+                provider,
+                opts: opts.clone(),
+            })
+        }
+
+        /// `db` with a sample of its operations exported as client spans
+        pub fn wrap(&self, db: Arc<dyn Database>) -> Arc<dyn Database> {
+            Arc::new(TracedDatabase {
+                inner: db,
+                tracer: self.provider.tracer("kvbencher"),
+                sample_rate: self.opts.sample_rate,
+                database: self.opts.database.clone(),
+            })
+        }
+
+        /// Flushes the spans still queued for export
+        pub fn shutdown(self) -> Result<()> {
+            self.provider
+                .shutdown()
+                .map_err(|e| anyhow!("failed to export traces: {e}"))
+        }
+    }
+
+    struct TracedDatabase {
+        inner: Arc<dyn Database>,
+        tracer: SdkTracer,
+        sample_rate: f64,
+        database: String,
+    }
+
+    impl TracedDatabase {
+        /// Runs `op`, exported as a span carrying `sizes` as integer attributes when sampled
+        fn traced<T>(
+            &self,
+            operation: &'static str,
+            sizes: &[(&'static str, usize)],
+            op: impl FnOnce() -> Result<T>,
+        ) -> Result<T> {
+            if !rand::rng().random_bool(self.sample_rate) {
+                return op();
+            }
+            let start = SystemTime::now();
+            let result = op();
+            let end = SystemTime::now();
+
+            let mut attributes = vec![
+                KeyValue::new("db.system.name", self.database.clone()),
+                KeyValue::new("db.operation.name", operation),
+            ];
+            for &(name, size) in sizes {
+                attributes.push(KeyValue::new(name, size as i64));
+            }
+            let mut span = self
+                .tracer
+                .span_builder(operation)
+                .with_kind(SpanKind::Client)
+                .with_start_time(start)
+                .with_attributes(attributes)
+                .start(&self.tracer);
+            if let Err(e) = &result {
+                span.set_status(Status::error(format!("{e:#}")));
+            }
+            span.end_with_timestamp(end);
+            result
+        }
+    }
+
+    impl Database for TracedDatabase {
+        fn init(&self) -> Result<()> {
+            self.inner.init()
+        }
+
+        fn get(&self, key: &[u8]) -> Result<()> {
+            let sizes = [("kvbencher.key_size", key.len())];
+            self.traced("get", &sizes, || self.inner.get(key))
+        }
+
+        fn set(&self, key: &[u8], value: &[u8]) -> Result<()> {
+            let sizes = [
+                ("kvbencher.key_size", key.len()),
+                ("kvbencher.value_size", value.len()),
+            ];
+            self.traced("set", &sizes, || self.inner.set(key, value))
+        }
+
+        fn delete(&self, key: &[u8]) -> Result<()> {
+            let sizes = [("kvbencher.key_size", key.len())];
+            self.traced("delete", &sizes, || self.inner.delete(key))
+        }
+
+        fn scan(&self, start: &[u8], count: usize) -> Result<usize> {
+            let sizes = [
+                ("kvbencher.key_size", start.len()),
+                ("kvbencher.scan_length", count),
+            ];
+            self.traced("scan", &sizes, || self.inner.scan(start, count))
+        }
+
+        fn read_modify_write(&self, key: &[u8], value: &[u8]) -> Result<()> {
+            let sizes = [
+                ("kvbencher.key_size", key.len()),
+                ("kvbencher.value_size", value.len()),
+            ];
+            self.traced("read_modify_write", &sizes, || {
+                self.inner.read_modify_write(key, value)
+            })
+        }
+
+        fn data_dir(&self) -> Option<&Path> {
+            self.inner.data_dir()
+        }
+
+        fn engine_activity(&self) -> Option<EngineActivity> {
+            self.inner.engine_activity()
+        }
+
+        fn read_misses(&self) -> Option<u64> {
+            self.inner.read_misses()
+        }
+
+        fn supports_tables(&self) -> bool {
+            self.inner.supports_tables()
+        }
+
+        fn create_tables(&self, count: u32) -> Result<()> {
+            self.inner.create_tables(count)
+        }
+
+        fn get_in(&self, table: u32, key: &[u8]) -> Result<()> {
+            let sizes = [("kvbencher.key_size", key.len())];
+            self.traced("get", &sizes, || self.inner.get_in(table, key))
+        }
+
+        fn set_in(&self, table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+            let sizes = [
+                ("kvbencher.key_size", key.len()),
+                ("kvbencher.value_size", value.len()),
+            ];
+            self.traced("set", &sizes, || self.inner.set_in(table, key, value))
+        }
+
+        fn delete_in(&self, table: u32, key: &[u8]) -> Result<()> {
+            let sizes = [("kvbencher.key_size", key.len())];
+            self.traced("delete", &sizes, || self.inner.delete_in(table, key))
+        }
+
+        fn scan_in(&self, table: u32, start: &[u8], count: usize) -> Result<usize> {
+            let sizes = [
+                ("kvbencher.key_size", start.len()),
+                ("kvbencher.scan_length", count),
+            ];
+            self.traced("scan", &sizes, || self.inner.scan_in(table, start, count))
+        }
+
+        fn read_modify_write_in(&self, table: u32, key: &[u8], value: &[u8]) -> Result<()> {
+            let sizes = [
+                ("kvbencher.key_size", key.len()),
+                ("kvbencher.value_size", value.len()),
+            ];
+            self.traced("read_modify_write", &sizes, || {
+                self.inner.read_modify_write_in(table, key, value)
+            })
+        }
+
+        fn pipeline(&self, ops: &[PipelinedOp]) -> Result<()> {
+            let sizes = [("kvbencher.pipeline_depth", ops.len())];
+            self.traced("pipeline", &sizes, || self.inner.pipeline(ops))
+        }
+    }
+}
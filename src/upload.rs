@@ -0,0 +1,107 @@
+use crate::history::RunRecord;
+use anyhow::{Context, Result, bail};
+use hdrhistogram::Histogram;
+use hdrhistogram::serialization::{Serializer, V2DeflateSerializer};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Object storage a run's artifacts are copied to, using the provider's own CLI
+#[derive(Clone, Debug)]
+pub enum Destination {
+    /// `s3://bucket/prefix`, uploaded with `aws s3 cp`
+    S3(String),
+    /// `gs://bucket/prefix`, uploaded with `gcloud storage cp`
+    Gcs(String),
+}
+
+impl std::str::FromStr for Destination {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let url = s.trim_end_matches('/').to_string();
+        if url.starts_with("s3://") {
+            Ok(Destination::S3(url))
+        } else if url.starts_with("gs://") {
+            Ok(Destination::Gcs(url))
+        } else {
+            Err(format!("expected an s3:// or gs:// url, got `{s}`"))
+        }
+    }
+}
+
+impl Destination {
+    /// Writes the artifacts of `record` to a temp dir and uploads them below
+    /// `<prefix>/<timestamp>-<database>-<workload>/`, returning that url
+    pub fn upload(&self, record: &RunRecord) -> Result<String> {
+        let dir = tempfile::TempDir::with_prefix("kvbencher-upload-")
+            .context("failed to create temp dir")?;
+        let files = write_artifacts(dir.path(), record)?;
+
+        let run = format!(
+            "{}-{}-{}",
+            record.timestamp,
+            record.database.to_lowercase(),
+            record.workload.name.to_lowercase()
+        );
+        let (base, program, args): (_, _, &[&str]) = match self {
+            Destination::S3(url) => (url, "aws", &["s3", "cp", "--only-show-errors"]),
+            Destination::Gcs(url) => (url, "gcloud", &["storage", "cp", "--quiet"]),
+        };
+        let target = format!("{base}/{run}");
+        for file in &files {
+            let name = file
+                .file_name()
+                .with_context(|| format!("{} is not a file", file.display()))?
+                .to_string_lossy();
+            let out = Command::new(program)
+                .args(args)
+                .arg(file)
+                .arg(format!("{target}/{name}"))
+                .output()
+                .with_context(|| {
+                    format!("failed to run {program}, is it installed and on PATH?")
+                })?;
+            if !out.status.success() {
+                bail!(
+                    "failed to upload {} to {target}: {}",
+                    file.display(),
+                    String::from_utf8_lossy(&out.stderr).trim()
+                );
+            }
+        }
+        Ok(target)
+    }
+}
+
+/// The full record as json and the run phase nanosecond histograms as compressed HdrHistogram
+/// V2 files
+fn write_artifacts(dir: &Path, record: &RunRecord) -> Result<Vec<PathBuf>> {
+    let json = dir.join("record.json");
+    std::fs::write(&json, serde_json::to_string_pretty(record)?)?;
+    let mut files = vec![json];
+    let extra = record
+        .stats
+        .run_extra_ops()
+        .into_iter()
+        .filter_map(|(kind, s)| Some((kind, &s?.hist_nano_sec)));
+    for (kind, h) in [
+        ("read", &record.stats.run_read_hist_nano_sec),
+        ("write", &record.stats.run_write_hist_nano_sec),
+    ]
+    .into_iter()
+    .chain(extra)
+    {
+        let path = dir.join(format!("{kind}.hdr"));
+        std::fs::write(&path, encode(h)?)?;
+        files.push(path);
+    }
+    Ok(files)
+}
+
+fn encode(h: &Histogram<u64>) -> Result<Vec<u8>> {
+    let mut buf = vec![];
+    V2DeflateSerializer::new()
+        .serialize(h, &mut buf)
+        .map_err(|e| anyhow::anyhow!("failed to serialize histogram: {e:?}"))?;
+    Ok(buf)
+}
@@ -1,29 +1,156 @@
-pub mod read_heavy;
-pub mod read_only;
-pub mod read_write;
+pub mod preset;
+pub mod spec;
 
-use crate::database::Database;
-use crate::generator::{ByteGen, KVSizeGen};
-use anyhow::Result;
+use crate::corpus::{Corpus, Op, OpKind};
+use crate::database::{self, Database, Dispatch, PipelinedOp};
+use crate::diskstats::DiskSample;
+use crate::generator::{
+    self, ByteGen, DeleteCounter, HotKeys, InsertCounter, KVSizeGen, KeyChooser, KeyFormat,
+    RecordGen, ScanLengths, ValuePool,
+};
+use crate::oplog::{self, OpLog, ThreadLog};
+use crate::sampler::{self, IntervalSample};
+use crate::serialize::{duration_secs, histogram_base64};
+use crate::workload::spec::WorkloadSpec;
+use anyhow::{Result, anyhow};
 use hdrhistogram::Histogram;
 use rand::prelude::SmallRng;
-use rand::{Rng, RngCore, SeedableRng, random};
+use rand::{Rng, SeedableRng, random};
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thousands::Separable;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct WorkloadStats {
-    load_time: Duration,
-    load_ops: u64,
-    run_wall_time: Duration,
-    run_read_time: Duration,
-    run_read_ops: u64,
-    run_read_hist_micro_sec: Histogram<u64>,
-    run_write_time: Duration,
-    run_write_ops: u64,
-    run_write_hist_micro_sec: Histogram<u64>,
+    #[serde(with = "duration_secs")]
+    pub load_time: Duration,
+    pub load_ops: u64,
+    #[serde(with = "duration_secs")]
+    pub run_wall_time: Duration,
+    #[serde(with = "duration_secs")]
+    pub run_read_time: Duration,
+    pub run_read_ops: u64,
+    #[serde(with = "histogram_base64", alias = "run_read_hist_micro_sec")]
+    pub run_read_hist_nano_sec: Histogram<u64>,
+    /// Run phase reads that found no value, for backends counting them
+    #[serde(default)]
+    pub run_read_misses: Option<u64>,
+    #[serde(with = "duration_secs")]
+    pub run_write_time: Duration,
+    pub run_write_ops: u64,
+    #[serde(with = "histogram_base64", alias = "run_write_hist_micro_sec")]
+    pub run_write_hist_nano_sec: Histogram<u64>,
+    /// `None` for runs without inserts of new keys
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_insert: Option<OpStats>,
+    /// `None` for runs without read-modify-writes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_read_modify_write: Option<OpStats>,
+    /// `None` for runs without deletes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_delete: Option<OpStats>,
+    /// `None` for runs without scans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_scan: Option<OpStats>,
+    /// Records read by the run phase scans, together
+    #[serde(default)]
+    pub run_scan_records: u64,
+    /// Run phase operations of each tenant by tenant id, empty with a single tenant
+    #[serde(default)]
+    pub run_tenant_ops: Vec<u64>,
+    /// Energy used by the cpu packages during each phase, where RAPL is readable
+    #[serde(default)]
+    pub load_energy_joules: Option<f64>,
+    #[serde(default)]
+    pub run_energy_joules: Option<f64>,
+    /// One sample per reporting interval of the run phase
+    #[serde(default)]
+    pub run_time_series: Vec<IntervalSample>,
+    /// Seed the loaded records and run operations were generated from, setting it as the
+    /// workload's `seed` reproduces them
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Where each run thread's share of the run phase went
+    #[serde(default)]
+    pub run_thread_times: Vec<ThreadTime>,
+    /// Conditions that may have skewed the run, such as cpu throttling
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Time spent in, count and latencies of one kind of run phase operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpStats {
+    #[serde(with = "duration_secs")]
+    pub time: Duration,
+    pub ops: u64,
+    #[serde(with = "histogram_base64", alias = "hist_micro_sec")]
+    pub hist_nano_sec: Histogram<u64>,
+}
+
+impl OpStats {
+    pub fn new() -> Result<Self> {
+        Ok(OpStats {
+            time: Duration::ZERO,
+            ops: 0,
+            hist_nano_sec: latency_histogram()?,
+        })
+    }
+
+    fn add(&mut self, other: &OpStats) -> Result<()> {
+        self.time += other.time;
+        self.ops += other.ops;
+        self.hist_nano_sec.add(&other.hist_nano_sec)?;
+        Ok(())
+    }
+}
+
+/// Nanosecond latencies at 3 significant digits, growing to fit the slowest operation
+/// instead of saturating at a fixed upper bound
+pub fn latency_histogram() -> Result<Histogram<u64>> {
+    Ok(Histogram::new(3)?)
+}
+
+/// The latency at quantile `q` of a nanosecond histogram, in microseconds
+pub fn quantile_micros(h: &Histogram<u64>, q: f64) -> f64 {
+    h.value_at_quantile(q) as f64 / 1_000.0
+}
+
+/// A microsecond latency with decimals below 10 µs, where whole microseconds would hide the
+/// differences between fast backends
+pub fn format_micros(micros: f64) -> String {
+    if micros < 10.0 {
+        format!("{micros:.2}")
+    } else {
+        (micros.round() as u64).separate_with_underscores()
+    }
+}
+
+/// `h` with every recorded value multiplied by `factor`, for histograms of records written in
+/// a coarser unit
+fn rescaled(h: &Histogram<u64>, factor: u64) -> Result<Histogram<u64>> {
+    let mut scaled = latency_histogram()?;
+    for v in h.iter_recorded() {
+        scaled.record_n(v.value_iterated_to() * factor, v.count_at_value())?;
+    }
+    Ok(scaled)
+}
+
+/// One run thread's share of the run phase's wall time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadTime {
+    /// Inside the database's get and set calls
+    #[serde(with = "duration_secs")]
+    pub database: Duration,
+    /// In the harness between calls, choosing and generating keys, values and operations
+    #[serde(with = "duration_secs")]
+    pub harness: Duration,
+    /// Not running, before the thread started, between bursts or after it finished its
+    /// operations
+    #[serde(with = "duration_secs")]
+    pub idle: Duration,
 }
 
 impl WorkloadStats {
@@ -34,12 +161,78 @@ impl WorkloadStats {
             run_wall_time: Duration::ZERO,
             run_read_time: Duration::ZERO,
             run_read_ops: 0,
-            run_read_hist_micro_sec: Histogram::new_with_bounds(1, 10_000_000, 3)?,
+            run_read_hist_nano_sec: latency_histogram()?,
+            run_read_misses: None,
             run_write_time: Duration::ZERO,
             run_write_ops: 0,
-            run_write_hist_micro_sec: Histogram::new_with_bounds(1, 10_000_000, 3)?,
+            run_write_hist_nano_sec: latency_histogram()?,
+            run_insert: None,
+            run_read_modify_write: None,
+            run_delete: None,
+            run_scan: None,
+            run_scan_records: 0,
+            run_tenant_ops: vec![],
+            load_energy_joules: None,
+            run_energy_joules: None,
+            run_time_series: vec![],
+            seed: None,
+            run_thread_times: vec![],
+            warnings: vec![],
         })
     }
+
+    /// Run phase operations of every kind
+    pub fn run_ops(&self) -> u64 {
+        let extra: u64 = self
+            .run_extra_ops()
+            .iter()
+            .filter_map(|(_, s)| s.map(|s| s.ops))
+            .sum();
+        self.run_read_ops + self.run_write_ops + extra
+    }
+
+    /// Stats of the kinds of run phase operations beyond reads and writes, by name, `None`
+    /// for the ones the run had none of
+    pub fn run_extra_ops(&self) -> [(&'static str, Option<&OpStats>); 4] {
+        [
+            ("insert", self.run_insert.as_ref()),
+            ("read-modify-write", self.run_read_modify_write.as_ref()),
+            ("delete", self.run_delete.as_ref()),
+            ("scan", self.run_scan.as_ref()),
+        ]
+    }
+
+    /// Fractions of the run threads' combined time spent in the database, in the harness and
+    /// idle, `None` before a run
+    pub fn run_utilization(&self) -> Option<(f64, f64, f64)> {
+        let sum = |f: fn(&ThreadTime) -> Duration| {
+            self.run_thread_times
+                .iter()
+                .map(|t| f(t).as_secs_f64())
+                .sum::<f64>()
+        };
+        let (database, harness, idle) = (sum(|t| t.database), sum(|t| t.harness), sum(|t| t.idle));
+        let total = database + harness + idle;
+        (total > 0.0).then(|| (database / total, harness / total, idle / total))
+    }
+
+    /// Converts the run phase histograms of a record written before they held nanoseconds
+    pub(crate) fn micros_to_nanos(&mut self) -> Result<()> {
+        self.run_read_hist_nano_sec = rescaled(&self.run_read_hist_nano_sec, 1_000)?;
+        self.run_write_hist_nano_sec = rescaled(&self.run_write_hist_nano_sec, 1_000)?;
+        for s in [
+            &mut self.run_insert,
+            &mut self.run_read_modify_write,
+            &mut self.run_delete,
+            &mut self.run_scan,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            s.hist_nano_sec = rescaled(&s.hist_nano_sec, 1_000)?;
+        }
+        Ok(())
+    }
 }
 
 impl Display for WorkloadStats {
@@ -55,21 +248,21 @@ impl Display for WorkloadStats {
             if h.is_empty() {
                 "-".into()
             } else {
-                h.value_at_quantile(q).separate_with_underscores()
+                format_micros(quantile_micros(h, q))
             }
         };
-
-        // reads
-        let r_p50 = percentile(&self.run_read_hist_micro_sec, 0.50);
-        let r_p95 = percentile(&self.run_read_hist_micro_sec, 0.95);
-        let r_p99 = percentile(&self.run_read_hist_micro_sec, 0.99);
-        let r_p999 = percentile(&self.run_read_hist_micro_sec, 0.999);
-
-        // writes
-        let w_p50 = percentile(&self.run_write_hist_micro_sec, 0.50);
-        let w_p95 = percentile(&self.run_write_hist_micro_sec, 0.95);
-        let w_p99 = percentile(&self.run_write_hist_micro_sec, 0.99);
-        let w_p999 = percentile(&self.run_write_hist_micro_sec, 0.999);
+        let run_line = |ops: u64, time: Duration, h: &Histogram<u64>| {
+            format!(
+                "ops: {} | time: {:.1?} | throughput: {} ops/s | p50: {} µs | p95: {} µs | p99: {} µs | p99.9: {} µs",
+                ops.separate_with_underscores(),
+                self.run_wall_time,
+                (throughput(ops, time) as u64).separate_with_underscores(),
+                percentile(h, 0.50),
+                percentile(h, 0.95),
+                percentile(h, 0.99),
+                percentile(h, 0.999)
+            )
+        };
 
         writeln!(f, "=== LOAD ===")?;
         writeln!(
@@ -81,55 +274,155 @@ impl Display for WorkloadStats {
         )?;
 
         writeln!(f, "=== RUN READ ===")?;
-        writeln!(
+        write!(
             f,
-            "ops: {} | time: {:.1?} | throughput: {} ops/s | p50: {} µs | p95: {} µs | p99: {} µs | p99.9: {} µs",
-            self.run_read_ops.separate_with_underscores(),
-            self.run_wall_time,
-            (throughput(self.run_read_ops, self.run_read_time) as u64).separate_with_underscores(),
-            r_p50,
-            r_p95,
-            r_p99,
-            r_p999
+            "{}",
+            run_line(
+                self.run_read_ops,
+                self.run_read_time,
+                &self.run_read_hist_nano_sec
+            )
         )?;
+        match self.run_read_misses {
+            Some(misses) => writeln!(f, " | misses: {}", misses.separate_with_underscores())?,
+            None => writeln!(f)?,
+        }
 
         writeln!(f, "=== RUN WRITE ===")?;
         write!(
             f,
-            "ops: {} | time: {:.1?} | throughput: {} ops/s | p50: {} µs | p95: {} µs | p99: {} µs | p99.9: {} µs",
-            self.run_write_ops.separate_with_underscores(),
-            self.run_wall_time,
-            (throughput(self.run_write_ops, self.run_write_time) as u64)
-                .separate_with_underscores(),
-            w_p50,
-            w_p95,
-            w_p99,
-            w_p999
-        )
+            "{}",
+            run_line(
+                self.run_write_ops,
+                self.run_write_time,
+                &self.run_write_hist_nano_sec
+            )
+        )?;
+        for (kind, stats) in self.run_extra_ops() {
+            if let Some(s) = stats {
+                writeln!(f)?;
+                writeln!(f, "=== RUN {} ===", kind.to_uppercase())?;
+                write!(f, "{}", run_line(s.ops, s.time, &s.hist_nano_sec))?;
+                if kind == "scan" {
+                    write!(
+                        f,
+                        " | records: {} | {} records/s",
+                        self.run_scan_records.separate_with_underscores(),
+                        (throughput(self.run_scan_records, s.time) as u64)
+                            .separate_with_underscores()
+                    )?;
+                }
+            }
+        }
+
+        if !self.run_tenant_ops.is_empty() {
+            writeln!(f)?;
+            write!(f, "=== RUN TENANTS ===")?;
+            for (tenant, &ops) in self.run_tenant_ops.iter().enumerate() {
+                writeln!(f)?;
+                write!(
+                    f,
+                    "tenant {tenant}: ops: {} | throughput: {} ops/s",
+                    ops.separate_with_underscores(),
+                    (throughput(ops, self.run_wall_time) as u64).separate_with_underscores()
+                )?;
+            }
+        }
+
+        let disk: Vec<_> = self
+            .run_time_series
+            .iter()
+            .filter_map(|s| s.disk.as_ref())
+            .collect();
+        if !disk.is_empty() {
+            let avg = |f: fn(&DiskSample) -> f64| {
+                disk.iter().map(|d| f(d)).sum::<f64>() / disk.len() as f64
+            };
+            let max = |f: fn(&DiskSample) -> f64| disk.iter().map(|d| f(d)).fold(0.0, f64::max);
+            writeln!(f)?;
+            writeln!(f, "=== RUN DISK (avg / max per interval) ===")?;
+            write!(
+                f,
+                "read: {:.0} / {:.0} iops, {:.1} / {:.1} MB/s | write: {:.0} / {:.0} iops, {:.1} / {:.1} MB/s | queue depth: {:.1} / {:.1}",
+                avg(|d| d.read_iops),
+                max(|d| d.read_iops),
+                avg(|d| d.read_mb_per_sec),
+                max(|d| d.read_mb_per_sec),
+                avg(|d| d.write_iops),
+                max(|d| d.write_iops),
+                avg(|d| d.write_mb_per_sec),
+                max(|d| d.write_mb_per_sec),
+                avg(|d| d.queue_depth),
+                max(|d| d.queue_depth),
+            )?;
+        }
+        if let Some((database, harness, idle)) = self.run_utilization() {
+            writeln!(f)?;
+            writeln!(f, "=== RUN THREAD TIME ===")?;
+            write!(
+                f,
+                "database: {:.1}% | harness: {:.1}% | idle: {:.1}% (of {} threads)",
+                database * 100.0,
+                harness * 100.0,
+                idle * 100.0,
+                self.run_thread_times.len()
+            )?;
+            if harness > database {
+                write!(f, "\nthe harness, not the database, was the bottleneck")?;
+            }
+        }
+        if let Some(impact) = sampler::background_work_impact(&self.run_time_series) {
+            writeln!(f)?;
+            writeln!(f, "=== RUN BACKGROUND WORK ===")?;
+            write!(f, "{impact}")?;
+        }
+        if self.load_energy_joules.is_some() || self.run_energy_joules.is_some() {
+            let energy = |joules: Option<f64>, ops: u64| {
+                joules.map_or("-".to_string(), |j| {
+                    format!("{j:.1} J ({:.0} ops/J)", ops as f64 / j)
+                })
+            };
+            writeln!(f)?;
+            writeln!(f, "=== ENERGY ===")?;
+            write!(
+                f,
+                "load: {} | run: {}",
+                energy(self.load_energy_joules, self.load_ops),
+                energy(self.run_energy_joules, self.run_ops())
+            )?;
+        }
+        for warning in &self.warnings {
+            writeln!(f)?;
+            write!(f, "warning: {warning}")?;
+        }
+        Ok(())
     }
 }
 
-trait WorkloadConfig: Sync {
-    fn get_name(&self) -> String;
-    /// How many records to insert during load phase
-    fn get_load_phase_insert_count(&self) -> u64;
-    /// How many operations to execute in run phase
-    fn get_operation_count(&self) -> u64;
-    /// Of all the operations executed in run phase, what percentage are reads
-    fn get_read_percent(&self) -> f64;
-    fn get_write_percent(&self) -> f64;
+/// A workload parameter that failed validation, naming the offending property
+#[derive(Debug)]
+pub struct ConfigError {
+    property: String,
+    message: String,
+}
 
-    /// key size
-    fn get_key_size(&self) -> u64;
-    /// Range of value sizes
-    fn get_value_size_range(&self) -> std::ops::Range<u64>;
+impl ConfigError {
+    pub fn new(property: impl Into<String>, message: impl Into<String>) -> Self {
+        ConfigError {
+            property: property.into(),
+            message: message.into(),
+        }
+    }
+}
 
-    // add read mod write, -> tx
-    // add scancount?
-    /// How many threads to execute this workload on (total ops = thread_count*get_operation_count())
-    fn get_thread_count(&self) -> u32;
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid `{}`: {}", self.property, self.message)
+    }
 }
 
+impl std::error::Error for ConfigError {}
+
 pub trait Workload {
     fn init_stats(&self) -> Result<WorkloadStats> {
         WorkloadStats::new()
@@ -140,165 +433,804 @@ pub trait Workload {
     fn get_name(&self) -> String;
 }
 
-impl<T: WorkloadConfig + Sync> Workload for T {
+impl Workload for WorkloadSpec {
     fn exec_load(&self, db: Arc<dyn Database>, stats: &mut WorkloadStats) -> Result<()> {
-        validate_config(self);
-        db.init()?;
-        let time = load(&db, self)?;
+        self.prepare(&db, stats)?;
+        // a replayed log refers to the records of the workload it was recorded from
+        let recorded;
+        let records = match &self.replay_ops {
+            Some(path) => {
+                recorded = OpLog::read_spec(path)?;
+                &recorded
+            }
+            None => self,
+        };
+        let seed = self.resolve_seed(stats);
+        let time = database::dispatch(
+            &db,
+            Load {
+                config: records,
+                seed,
+            },
+        )?;
         stats.load_time = time;
-        stats.load_ops = self.get_load_phase_insert_count();
+        stats.load_ops = records.record_count;
         Ok(())
     }
 
     fn exec_run(&self, db: Arc<dyn Database>, stats: &mut WorkloadStats) -> Result<()> {
-        let mut read_duration = Duration::ZERO;
-        let mut read_ops = 0;
-        let mut read_hist = Histogram::<u64>::new_with_bounds(1, 10_000_000, 3)?;
-        let mut write_duration = Duration::ZERO;
-        let mut write_ops = 0;
-        let mut write_hist = Histogram::<u64>::new_with_bounds(1, 10_000_000, 3)?;
-        std::thread::scope(|s| {
+        let seed = self.resolve_seed(stats);
+        let source = if let Some(path) = &self.replay_ops {
+            let log = OpLog::read(path)?;
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let values = ValuePool::new(log.max_value_len(), &mut rng);
+            Source::Replay(log, values)
+        } else if self.pregenerate {
+            Source::Corpus(Corpus::generate(self, seed)?)
+        } else {
+            // shared by the run threads
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let values = ValuePool::new(self.value_size_max as usize, &mut rng);
+            let inserts = Arc::new(InsertCounter::new(self.record_count));
+            let keys = self.key_chooser(&inserts)?;
+            let hot_keys = Arc::new(HotKeys::new(
+                &keys,
+                self.record_count,
+                self.key_size as usize,
+                self.key_format(),
+            ));
+            Source::Generated {
+                values,
+                keys,
+                hot_keys,
+                inserts,
+                deletes: DeleteCounter::default(),
+                scan_lengths: ScanLengths::new(
+                    self.scan_length_range(),
+                    self.scan_length_distribution,
+                )?,
+            }
+        };
+        let thread_count = match &source {
+            Source::Replay(log, _) => log.threads.len(),
+            _ => self.thread_count as usize,
+        };
+        let key_format = match &source {
+            Source::Replay(log, _) => log.spec.key_format(),
+            _ => self.key_format(),
+        };
+
+        let mut total = Recorder::new(self.timing_batch)?;
+        let mut logs = vec![];
+        std::thread::scope(|s| -> Result<()> {
             let mut handles = vec![];
             let start_time = Instant::now();
-            for _ in 0..self.get_thread_count() {
-                let h = s.spawn(|| run(&db, self));
+            for thread in 0..thread_count {
+                let mut recorder = Recorder::new(self.timing_batch)?;
+                if self.record_ops.is_some() {
+                    recorder = recorder.logging(start_time);
+                }
+                if let Some(bursts) = self.bursts(start_time) {
+                    recorder = recorder.bursting(bursts);
+                }
+                if key_format.tenant_count > 1 {
+                    recorder = recorder.per_tenant(key_format);
+                }
+                let (db, source) = (&db, &source);
+                let h = s.spawn(move || {
+                    let start = Instant::now();
+                    let recorder = match source {
+                        Source::Corpus(corpus) => database::dispatch(
+                            db,
+                            RunOps {
+                                config: self,
+                                ops: corpus.ops(thread),
+                                recorder,
+                            },
+                        ),
+                        Source::Generated {
+                            values,
+                            keys,
+                            hot_keys,
+                            inserts,
+                            deletes,
+                            scan_lengths,
+                        } => database::dispatch(
+                            db,
+                            Run {
+                                config: self,
+                                values,
+                                keys,
+                                hot_keys,
+                                inserts,
+                                deletes,
+                                scan_lengths,
+                                seed: generator::thread_seed(seed, thread as u64),
+                                recorder,
+                            },
+                        ),
+                        Source::Replay(log, values) => {
+                            let mut rng = SmallRng::seed_from_u64(generator::thread_seed(
+                                seed,
+                                thread as u64,
+                            ));
+                            let ops = &log.threads[thread];
+                            let ops = ops.ops().iter().map(move |op| Op {
+                                kind: op.kind,
+                                key: ops.key(op),
+                                value: if op.kind.has_value() {
+                                    values.next(op.value_len as usize, &mut rng)
+                                } else {
+                                    &[]
+                                },
+                                scan_length: op.scan_length as usize,
+                            });
+                            database::dispatch(
+                                db,
+                                RunOps {
+                                    config: self,
+                                    ops,
+                                    recorder,
+                                },
+                            )
+                        }
+                    }?;
+                    Ok::<_, anyhow::Error>((recorder, start.elapsed()))
+                });
                 handles.push(h);
             }
 
-            handles.into_iter().for_each(|h| {
-                let d = h.join().unwrap().unwrap();
-                read_duration += d.read_duration;
-                write_duration += d.write_duration;
-                read_ops += d.read_ops;
-                write_ops += d.write_ops;
-                read_hist.add(d.read_hist).unwrap();
-                write_hist.add(d.write_hist).unwrap();
-            });
+            let mut threads = vec![];
+            for h in handles {
+                let (mut r, wall) = h.join().map_err(|_| anyhow!("run thread panicked"))??;
+                r.flush()?;
+                threads.push((r.tallies.iter().map(|t| t.time).sum(), r.paused, wall));
+                logs.extend(r.log.take());
+                total.add(r)?;
+            }
             stats.run_wall_time = start_time.elapsed();
-        });
-
-        stats.run_read_ops = read_ops;
-        stats.run_write_ops = write_ops;
-        stats.run_read_time = read_duration;
-        stats.run_write_time = write_duration;
-        stats.run_read_hist_micro_sec = read_hist;
-        stats.run_write_hist_micro_sec = write_hist;
+            stats.run_thread_times = threads
+                .into_iter()
+                .map(|(database, paused, wall)| ThreadTime {
+                    database,
+                    harness: wall.saturating_sub(database + paused),
+                    idle: stats.run_wall_time.saturating_sub(wall) + paused,
+                })
+                .collect();
+            Ok(())
+        })?;
+
+        if let Some(path) = &self.record_ops {
+            let records = match &source {
+                Source::Replay(log, _) => &log.spec,
+                _ => self,
+            };
+            oplog::write(path, records, &logs)?;
+        }
+        let [read, write, insert, rmw, delete, scan] = total.tallies;
+        stats.run_read_ops = read.ops;
+        stats.run_read_time = read.time;
+        stats.run_read_hist_nano_sec = read.hist_nano_sec;
+        stats.run_write_ops = write.ops;
+        stats.run_write_time = write.time;
+        stats.run_write_hist_nano_sec = write.hist_nano_sec;
+        stats.run_insert = (insert.ops > 0).then_some(insert);
+        stats.run_read_modify_write = (rmw.ops > 0).then_some(rmw);
+        stats.run_delete = (delete.ops > 0).then_some(delete);
+        stats.run_scan = (scan.ops > 0).then_some(scan);
+        stats.run_scan_records = total.scanned_records;
+        stats.run_tenant_ops = total.tenant_ops;
         Ok(())
     }
 
     fn get_name(&self) -> String {
-        self.get_name()
+        self.name.clone()
     }
 }
 
-fn load(db: &Arc<dyn Database>, config: &impl WorkloadConfig) -> Result<Duration> {
-    let mut time = Duration::ZERO;
-    let v_r = config.get_value_size_range();
-    let mut value_size_gen = KVSizeGen::new(v_r.end - v_r.start, random())?;
+/// Where the run threads take their operations from
+enum Source {
+    Corpus(Corpus),
+    Generated {
+        values: ValuePool,
+        keys: KeyChooser,
+        hot_keys: Arc<HotKeys>,
+        inserts: Arc<InsertCounter>,
+        deletes: DeleteCounter,
+        scan_lengths: ScanLengths,
+    },
+    /// A recorded log, each recorded thread replayed by its own thread
+    Replay(OpLog, ValuePool),
+}
 
-    let key_size = config.get_key_size();
-    let mut key_bytes = vec![0u8; key_size as usize];
+impl WorkloadSpec {
+    /// Validates the spec and initializes `db` with its tables, before the load phase or
+    /// instead of it when the run reuses loaded data
+    pub fn prepare(&self, db: &Arc<dyn Database>, stats: &mut WorkloadStats) -> Result<()> {
+        self.validate()?;
+        db.init()?;
+        if self.table_count > 1 {
+            db.create_tables(self.table_count)?;
+            if !db.supports_tables() {
+                stats.warnings.push(format!(
+                    "the database has no tables, the keys of all {} tables share one keyspace",
+                    self.table_count
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The configured seed, or one picked for this run and kept in `stats` for both phases
+    fn resolve_seed(&self, stats: &mut WorkloadStats) -> u64 {
+        *stats
+            .seed
+            .get_or_insert_with(|| self.seed.unwrap_or_else(random))
+    }
+
+    /// The run phase's bursts starting at `start`, `None` when it runs in a closed loop
+    fn bursts(&self, start: Instant) -> Option<Bursts> {
+        (self.burst_off_ms > 0).then(|| Bursts {
+            start,
+            on: Duration::from_millis(self.burst_on_ms),
+            period: Duration::from_millis(self.burst_on_ms + self.burst_off_ms),
+        })
+    }
+}
+
+struct Load<'a> {
+    config: &'a WorkloadSpec,
+    seed: u64,
+}
 
-    for i in 0..config.get_load_phase_insert_count() {
-        let value_size = value_size_gen.get_size() + v_r.start;
-        let mut value_bytes = vec![0u8; value_size as usize];
+impl Dispatch for Load<'_> {
+    type Output = Result<Duration>;
 
-        let mut rng = SmallRng::seed_from_u64(i);
-        rng.fill_bytes(&mut key_bytes);
-        rng.fill_bytes(&mut value_bytes);
+    fn call<D: Database + ?Sized>(self, db: &D) -> Self::Output {
+        load(db, self.config, self.seed)
+    }
+}
 
+fn load<D: Database + ?Sized>(db: &D, config: &WorkloadSpec, seed: u64) -> Result<Duration> {
+    let mut time = Duration::ZERO;
+    let mut records = RecordGen::new(
+        config.key_size as usize,
+        config.key_format(),
+        config.value_size_range(),
+        seed,
+    )?;
+    for i in 0..config.record_count {
+        let (key, value) = records.record(i);
         let s = Instant::now();
-        db.set(key_bytes.as_slice(), value_bytes.as_slice())?;
+        set(db, config.table_count, key, value)?;
         time += s.elapsed()
     }
-
     Ok(time)
 }
 
-struct RunDuration {
-    read_duration: Duration,
-    read_ops: u64,
-    read_hist: Histogram<u64>,
-    write_duration: Duration,
-    write_ops: u64,
-    write_hist: Histogram<u64>,
+/// The table `key` belongs to out of `tables`, from its trailing bytes, which
+/// `generator::record_key` mixes well or counts up with ordered keys
+#[inline]
+fn table_of(key: &[u8], tables: u32) -> u32 {
+    let mut suffix = [0; 8];
+    let len = key.len().min(8);
+    suffix[8 - len..].copy_from_slice(&key[key.len() - len..]);
+    (u64::from_be_bytes(suffix) % tables as u64) as u32
+}
+
+/// `Database::get`, through the key's table when the workload has several
+#[inline]
+fn get<D: Database + ?Sized>(db: &D, tables: u32, key: &[u8]) -> Result<()> {
+    if tables > 1 {
+        db.get_in(table_of(key, tables), key)
+    } else {
+        db.get(key)
+    }
+}
+
+/// `Database::set`, through the key's table when the workload has several
+#[inline]
+fn set<D: Database + ?Sized>(db: &D, tables: u32, key: &[u8], value: &[u8]) -> Result<()> {
+    if tables > 1 {
+        db.set_in(table_of(key, tables), key, value)
+    } else {
+        db.set(key, value)
+    }
+}
+
+/// `Database::read_modify_write`, through the key's table when the workload has several
+#[inline]
+fn read_modify_write<D: Database + ?Sized>(
+    db: &D,
+    tables: u32,
+    key: &[u8],
+    value: &[u8],
+) -> Result<()> {
+    if tables > 1 {
+        db.read_modify_write_in(table_of(key, tables), key, value)
+    } else {
+        db.read_modify_write(key, value)
+    }
 }
 
-fn run(db: &Arc<dyn Database>, config: &impl WorkloadConfig) -> Result<RunDuration> {
-    let mut read_duration = Duration::ZERO;
-    let mut read_ops = 0;
-    let mut read_hist = Histogram::<u64>::new_with_bounds(1, 10_000_000, 3)?;
+/// `Database::delete`, through the key's table when the workload has several
+#[inline]
+fn delete<D: Database + ?Sized>(db: &D, tables: u32, key: &[u8]) -> Result<()> {
+    if tables > 1 {
+        db.delete_in(table_of(key, tables), key)
+    } else {
+        db.delete(key)
+    }
+}
 
-    let mut write_duration = Duration::ZERO;
-    let mut write_ops = 0;
-    let mut write_hist = Histogram::<u64>::new_with_bounds(1, 10_000_000, 3)?;
+/// `Database::scan`, through the start key's table when the workload has several
+#[inline]
+fn scan<D: Database + ?Sized>(db: &D, tables: u32, start: &[u8], count: usize) -> Result<usize> {
+    if tables > 1 {
+        db.scan_in(table_of(start, tables), start, count)
+    } else {
+        db.scan(start, count)
+    }
+}
 
-    let v_r = config.get_value_size_range();
-    let mut value_size_gen = KVSizeGen::new(v_r.end - v_r.start, random())?;
-    let mut bytes_gen = ByteGen::new(config.get_load_phase_insert_count(), random())?;
-    let mut rng = rand::rng();
+/// Issues `op` with the function of its kind, returning the records a scan read and 0 for
+/// the other kinds
+#[inline]
+fn execute<D: Database + ?Sized>(db: &D, tables: u32, op: Op) -> Result<usize> {
+    match op.kind {
+        OpKind::Read => get(db, tables, op.key)?,
+        OpKind::Write | OpKind::Insert => set(db, tables, op.key, op.value)?,
+        OpKind::ReadModifyWrite => read_modify_write(db, tables, op.key, op.value)?,
+        OpKind::Delete => delete(db, tables, op.key)?,
+        OpKind::Scan => return scan(db, tables, op.key, op.scan_length),
+    }
+    Ok(0)
+}
 
-    let key_size = config.get_key_size();
+/// `op` as an entry of a pipeline
+fn pipelined(op: Op) -> Result<PipelinedOp> {
+    Ok(match op.kind {
+        OpKind::Read => PipelinedOp::Get(op.key),
+        OpKind::Write | OpKind::Insert => PipelinedOp::Set(op.key, op.value),
+        OpKind::Delete => PipelinedOp::Delete(op.key),
+        // a replayed log may hold them even though validation keeps them out
+        OpKind::ReadModifyWrite => anyhow::bail!(
+            "the write of a read-modify-write waits for its read and cannot be pipelined, leave pipeline_depth at 1"
+        ),
+        OpKind::Scan => anyhow::bail!(
+            "a scan returns its records rather than a status and cannot be pipelined, leave pipeline_depth at 1"
+        ),
+    })
+}
 
-    for _ in 0..config.get_operation_count() {
-        let x: f64 = rng.random();
-        let key_bytes = bytes_gen.get_key_bytes(key_size);
-        if x < config.get_read_percent() {
-            let start = Instant::now();
-            db.get(key_bytes.as_slice())?;
-            let mirco_sec = start.elapsed();
-            read_duration += start.elapsed();
-            read_hist.record(mirco_sec.as_micros() as u64)?;
-            read_ops += 1;
-        } else if x < config.get_read_percent() + config.get_write_percent() {
-            let value_size = value_size_gen.get_size() + v_r.start;
-            let value_bytes = bytes_gen.get_value_bytes(value_size);
+/// Empties `ops` and hands its allocation back for operations borrowing other keys, the
+/// collect reuses the buffer in place since the element type only differs in lifetime
+fn recycle<'b>(mut ops: Vec<PipelinedOp<'_>>) -> Vec<PipelinedOp<'b>> {
+    ops.clear();
+    ops.into_iter().map(|_| unreachable!()).collect()
+}
+
+/// Latencies of one run thread, or of all of them once merged
+struct Recorder {
+    /// One per `OpKind`, indexed by it
+    tallies: [OpStats; OpKind::COUNT],
+    /// Operations timed together, see `WorkloadSpec::timing_batch`
+    batch: u64,
+    batch_start: Instant,
+    /// Operations of each kind in the current batch
+    batch_ops: [u64; OpKind::COUNT],
+    /// Records read by scans
+    scanned_records: u64,
+    /// Every operation issued, kept when `WorkloadSpec::record_ops` is set
+    log: Option<ThreadLog>,
+    /// Pauses between bursts, see `WorkloadSpec::burst_on_ms`
+    bursts: Option<Bursts>,
+    /// Time spent waiting for the next burst
+    paused: Duration,
+    /// Format of the keys to find their tenant in, with several tenants
+    tenants: Option<KeyFormat>,
+    /// Operations issued to each tenant
+    tenant_ops: Vec<u64>,
+}
+
+impl Recorder {
+    fn new(batch: u64) -> Result<Self> {
+        Ok(Recorder {
+            tallies: [
+                OpStats::new()?,
+                OpStats::new()?,
+                OpStats::new()?,
+                OpStats::new()?,
+                OpStats::new()?,
+                OpStats::new()?,
+            ],
+            batch,
+            batch_start: Instant::now(),
+            batch_ops: [0; OpKind::COUNT],
+            scanned_records: 0,
+            log: None,
+            bursts: None,
+            paused: Duration::ZERO,
+            tenants: None,
+            tenant_ops: vec![],
+        })
+    }
+
+    /// Also logs every operation, with timestamps relative to `epoch`
+    fn logging(mut self, epoch: Instant) -> Self {
+        self.log = Some(ThreadLog::new(epoch));
+        self
+    }
+
+    /// Only issues operations during bursts, see `pace`
+    fn bursting(mut self, bursts: Bursts) -> Self {
+        self.bursts = Some(bursts);
+        self
+    }
+
+    /// Also counts the operations of each tenant of `format`
+    fn per_tenant(mut self, format: KeyFormat) -> Self {
+        self.tenants = Some(format);
+        self.tenant_ops = vec![0; format.tenant_count as usize];
+        self
+    }
+
+    /// Call before generating each operation or pipeline, waits for the next burst when
+    /// the current one is over
+    #[inline]
+    fn pace(&mut self) -> Result<()> {
+        let Some(bursts) = &self.bursts else {
+            return Ok(());
+        };
+        let wait = bursts.until_next();
+        if !wait.is_zero() {
+            // a batch spanning the pause would be timed with it
+            self.flush()?;
             let start = Instant::now();
-            db.set(key_bytes.as_slice(), value_bytes.as_slice())?;
-            let mirco_sec = start.elapsed();
-            write_duration += start.elapsed();
-            write_hist.record(mirco_sec.as_micros() as u64)?;
-            write_ops += 1;
+            std::thread::sleep(wait);
+            self.paused += start.elapsed();
+        }
+        Ok(())
+    }
+
+    /// Call with each operation about to be issued, `len` being the length of its value or
+    /// the records it scans
+    #[inline]
+    fn log(&mut self, kind: OpKind, key: &[u8], len: usize) {
+        if let Some(log) = &mut self.log {
+            log.push(kind, key, len);
+        }
+        if let Some(tenant) = self.tenants.and_then(|f| f.tenant(key))
+            && let Some(ops) = self.tenant_ops.get_mut(tenant)
+        {
+            *ops += 1;
+        }
+    }
+
+    /// Call right before issuing an operation
+    #[inline]
+    fn before(&mut self) {
+        if self.batch_ops.iter().sum::<u64>() == 0 {
+            self.batch_start = Instant::now();
+        }
+    }
+
+    /// Call right after an operation completed, one clock read serves duration and histogram
+    #[inline]
+    fn after(&mut self, kind: OpKind) -> Result<()> {
+        self.batch_ops[kind as usize] += 1;
+        if self.batch_ops.iter().sum::<u64>() == self.batch {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Records the operations of the current batch, each at the batch's average latency
+    fn flush(&mut self) -> Result<()> {
+        let ops = self.batch_ops.iter().sum::<u64>();
+        if ops == 0 {
+            return Ok(());
+        }
+        let per_op = self.batch_start.elapsed() / ops as u32;
+        self.record(per_op, per_op, self.batch_ops)?;
+        self.batch_ops = [0; OpKind::COUNT];
+        Ok(())
+    }
+
+    /// Records a pipeline of `counts` operations of each kind that completed after
+    /// `elapsed`. Each of its operations waited for the whole pipeline, but they only add
+    /// their share of it to the busy time.
+    fn pipeline(&mut self, elapsed: Duration, counts: [u64; OpKind::COUNT]) -> Result<()> {
+        let ops = counts.iter().sum::<u64>();
+        if ops == 0 {
+            return Ok(());
+        }
+        self.record(elapsed / ops as u32, elapsed, counts)
+    }
+
+    fn record(
+        &mut self,
+        share: Duration,
+        latency: Duration,
+        counts: [u64; OpKind::COUNT],
+    ) -> Result<()> {
+        let nano_sec = latency.as_nanos() as u64;
+        for (tally, n) in self.tallies.iter_mut().zip(counts) {
+            if n > 0 {
+                tally.time += share * n as u32;
+                tally.hist_nano_sec.record_n(nano_sec, n)?;
+                tally.ops += n;
+            }
+        }
+        Ok(())
+    }
+
+    fn add(&mut self, mut other: Recorder) -> Result<()> {
+        other.flush()?;
+        for (tally, other) in self.tallies.iter_mut().zip(&other.tallies) {
+            tally.add(other)?;
+        }
+        self.scanned_records += other.scanned_records;
+        if self.tenant_ops.len() < other.tenant_ops.len() {
+            self.tenant_ops.resize(other.tenant_ops.len(), 0);
+        }
+        for (ops, other) in self.tenant_ops.iter_mut().zip(&other.tenant_ops) {
+            *ops += other;
+        }
+        Ok(())
+    }
+}
+
+/// Bursts of operations at full rate separated by idle pauses, on the same clock for every
+/// run thread
+struct Bursts {
+    start: Instant,
+    on: Duration,
+    period: Duration,
+}
+
+impl Bursts {
+    /// Zero during a burst, otherwise the time left until the next one starts
+    #[inline]
+    fn until_next(&self) -> Duration {
+        let into = self.start.elapsed().as_nanos() % self.period.as_nanos();
+        let into = Duration::from_nanos(into as u64);
+        if into < self.on {
+            Duration::ZERO
         } else {
-            unreachable!("Should not get here");
-        };
+            self.period - into
+        }
     }
+}
 
-    Ok(RunDuration {
-        read_duration,
-        read_ops,
-        read_hist,
-        write_duration,
-        write_ops,
-        write_hist,
-    })
+/// One run thread generating its operations as it goes
+struct Run<'a> {
+    config: &'a WorkloadSpec,
+    values: &'a ValuePool,
+    keys: &'a KeyChooser,
+    hot_keys: &'a Arc<HotKeys>,
+    inserts: &'a InsertCounter,
+    deletes: &'a DeleteCounter,
+    scan_lengths: &'a ScanLengths,
+    /// This thread's seed, see `generator::thread_seed`
+    seed: u64,
+    recorder: Recorder,
+}
+
+impl Dispatch for Run<'_> {
+    type Output = Result<Recorder>;
+
+    fn call<D: Database + ?Sized>(self, db: &D) -> Self::Output {
+        if self.config.pipeline_depth > 1 {
+            run_pipelined(db, self)
+        } else {
+            run(db, self)
+        }
+    }
+}
+
+fn run<D: Database + ?Sized>(db: &D, job: Run) -> Result<Recorder> {
+    let Run {
+        config,
+        values,
+        keys,
+        hot_keys,
+        inserts,
+        deletes,
+        scan_lengths,
+        seed,
+        mut recorder,
+    } = job;
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let v_r = config.value_size_range();
+    let mut value_size_gen = KVSizeGen::new(v_r.end() - v_r.start() + 1, rng.random())?;
+    let mut bytes_gen = ByteGen::new(keys.clone(), hot_keys.clone(), rng.random());
+    let mix = config.op_mix();
+
+    for _ in 0..config.operation_count {
+        recorder.pace()?;
+        match mix.choose(rng.random()) {
+            OpKind::Read => {
+                let key = bytes_gen.next_key();
+                recorder.log(OpKind::Read, key, 0);
+                recorder.before();
+                get(db, config.table_count, key)?;
+                recorder.after(OpKind::Read)?;
+            }
+            kind @ (OpKind::Write | OpKind::Insert) => {
+                let value_size = value_size_gen.get_size() + v_r.start();
+                let value_bytes = values.next(value_size as usize, &mut rng);
+                let key = if kind == OpKind::Write {
+                    bytes_gen.next_key()
+                } else {
+                    bytes_gen.key_of(inserts.next())
+                };
+                recorder.log(kind, key, value_bytes.len());
+                recorder.before();
+                set(db, config.table_count, key, value_bytes)?;
+                recorder.after(kind)?;
+            }
+            OpKind::ReadModifyWrite => {
+                let value_size = value_size_gen.get_size() + v_r.start();
+                let value_bytes = values.next(value_size as usize, &mut rng);
+                let key = bytes_gen.next_key();
+                recorder.log(OpKind::ReadModifyWrite, key, value_bytes.len());
+                recorder.before();
+                read_modify_write(db, config.table_count, key, value_bytes)?;
+                recorder.after(OpKind::ReadModifyWrite)?;
+            }
+            OpKind::Delete => {
+                let key = if config.delete_oldest {
+                    bytes_gen.key_of(deletes.next())
+                } else {
+                    bytes_gen.next_key()
+                };
+                recorder.log(OpKind::Delete, key, 0);
+                recorder.before();
+                delete(db, config.table_count, key)?;
+                recorder.after(OpKind::Delete)?;
+            }
+            OpKind::Scan => {
+                let len = scan_lengths.next(&mut rng) as usize;
+                let key = bytes_gen.next_scan_key(len as u64);
+                recorder.log(OpKind::Scan, key, len);
+                recorder.before();
+                let records = scan(db, config.table_count, key, len)?;
+                recorder.after(OpKind::Scan)?;
+                recorder.scanned_records += records as u64;
+            }
+        }
+    }
+
+    Ok(recorder)
+}
+
+/// `run` submitting `pipeline_depth` operations at a time through `Database::pipeline`,
+/// every operation recorded with the latency of its whole pipeline
+fn run_pipelined<D: Database + ?Sized>(db: &D, job: Run) -> Result<Recorder> {
+    let Run {
+        config,
+        values,
+        keys,
+        hot_keys,
+        inserts,
+        deletes,
+        seed,
+        mut recorder,
+        ..
+    } = job;
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let v_r = config.value_size_range();
+    let mut value_size_gen = KVSizeGen::new(v_r.end() - v_r.start() + 1, rng.random())?;
+    let mut bytes_gen = ByteGen::new(keys.clone(), hot_keys.clone(), rng.random());
+    let mix = config.op_mix();
+
+    let depth = config.pipeline_depth as usize;
+    let key_size = config.key_size as usize;
+    let mut key_buf = vec![0u8; depth * key_size];
+    // the kind of each operation in the pipeline and its value, empty for reads and deletes
+    let mut slots: Vec<(OpKind, &[u8])> = vec![(OpKind::Read, &[]); depth];
+    let mut spare = Vec::with_capacity(depth);
+    let mut remaining = config.operation_count;
+    while remaining > 0 {
+        let n = remaining.min(depth as u64) as usize;
+        recorder.pace()?;
+        let mut counts = [0; OpKind::COUNT];
+        for (i, slot) in slots[..n].iter_mut().enumerate() {
+            // validation keeps read-modify-writes and scans out of pipelines, `pipelined`
+            // refuses them
+            let kind = mix.choose(rng.random());
+            *slot = if kind.has_value() {
+                let value_size = value_size_gen.get_size() + v_r.start();
+                (kind, values.next(value_size as usize, &mut rng))
+            } else {
+                (kind, &[])
+            };
+            let key = if slot.0 == OpKind::Insert {
+                bytes_gen.key_of(inserts.next())
+            } else if slot.0 == OpKind::Delete && config.delete_oldest {
+                bytes_gen.key_of(deletes.next())
+            } else {
+                bytes_gen.next_key()
+            };
+            recorder.log(slot.0, key, slot.1.len());
+            counts[slot.0 as usize] += 1;
+            key_buf[i * key_size..(i + 1) * key_size].copy_from_slice(key);
+        }
+        let mut ops = recycle(spare);
+        for (i, &(kind, value)) in slots[..n].iter().enumerate() {
+            let key = &key_buf[i * key_size..(i + 1) * key_size];
+            ops.push(pipelined(Op {
+                kind,
+                key,
+                value,
+                scan_length: 0,
+            })?);
+        }
+
+        let start = Instant::now();
+        db.pipeline(&ops)?;
+        recorder.pipeline(start.elapsed(), counts)?;
+        spare = recycle(ops);
+        remaining -= n as u64;
+    }
+
+    Ok(recorder)
+}
+
+/// One run thread executing a fixed sequence of operations, taken from a pregenerated
+/// corpus or a replayed operation log so the measured loop only reads memory
+struct RunOps<'a, I> {
+    config: &'a WorkloadSpec,
+    ops: I,
+    recorder: Recorder,
+}
+
+impl<'a, I: Iterator<Item = Op<'a>>> Dispatch for RunOps<'a, I> {
+    type Output = Result<Recorder>;
+
+    fn call<D: Database + ?Sized>(self, db: &D) -> Self::Output {
+        run_ops(db, self)
+    }
 }
 
-fn validate_config(config: &impl WorkloadConfig) {
-    assert!(
-        config.get_read_percent() >= 0.0,
-        "Read percent must be larger than or equal to 0"
-    );
-    assert!(
-        config.get_read_percent() <= 1.0,
-        "Read percent must be less than or equal to 1"
-    );
-
-    assert!(
-        config.get_write_percent() >= 0.0,
-        "Write percent must be larger than or equal to 0"
-    );
-    assert!(
-        config.get_write_percent() <= 1.0,
-        "Write percent must be less than or equal to 1"
-    );
-
-    assert!(
-        config.get_read_percent() + config.get_write_percent() > 0.0,
-        "Read and write both cannot be zero percent"
-    );
-    assert!(
-        config.get_read_percent() + config.get_write_percent() <= 1.0,
-        "Read and write cannot not combine to above 1"
-    );
+fn run_ops<'a, D: Database + ?Sized>(
+    db: &D,
+    job: RunOps<'a, impl Iterator<Item = Op<'a>>>,
+) -> Result<Recorder> {
+    let RunOps {
+        config,
+        mut ops,
+        mut recorder,
+    } = job;
+    if config.pipeline_depth > 1 {
+        let depth = config.pipeline_depth as usize;
+        let mut batch = Vec::with_capacity(depth);
+        loop {
+            batch.clear();
+            recorder.pace()?;
+            let mut counts = [0; OpKind::COUNT];
+            for op in ops.by_ref().take(depth) {
+                recorder.log(op.kind, op.key, op.value.len().max(op.scan_length));
+                counts[op.kind as usize] += 1;
+                batch.push(pipelined(op)?);
+            }
+            if batch.is_empty() {
+                return Ok(recorder);
+            }
+            let start = Instant::now();
+            db.pipeline(&batch)?;
+            recorder.pipeline(start.elapsed(), counts)?;
+        }
+    }
+
+    for op in ops {
+        recorder.pace()?;
+        recorder.log(op.kind, op.key, op.value.len().max(op.scan_length));
+        recorder.before();
+        let records = execute(db, config.table_count, op)?;
+        recorder.after(op.kind)?;
+        recorder.scanned_records += records as u64;
+    }
+    Ok(recorder)
 }
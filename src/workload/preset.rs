@@ -0,0 +1,237 @@
+use crate::WorkloadType;
+use crate::generator::{KeyDistribution, KeyOrder};
+use crate::workload::spec::WorkloadSpec;
+use crate::ycsb;
+use anyhow::Result;
+use std::path::PathBuf;
+
+pub fn get(wl: WorkloadType) -> Result<WorkloadSpec> {
+    match wl {
+        WorkloadType::ReadWrite => Ok(read_write()),
+        WorkloadType::ReadHeavy => Ok(read_heavy()),
+        WorkloadType::ReadOnly => Ok(read_only()),
+        WorkloadType::InsertOnly => Ok(insert_only()),
+        WorkloadType::SequentialInsert => Ok(sequential_insert()),
+        WorkloadType::ReadModifyWrite => Ok(read_modify_write()),
+        WorkloadType::Churn => Ok(churn()),
+        WorkloadType::FifoChurn => Ok(fifo_churn()),
+        WorkloadType::Hotspot => Ok(hotspot()),
+        WorkloadType::TinyCache => Ok(tiny_cache()),
+        WorkloadType::Contention => Ok(contention()),
+        WorkloadType::MultiTenant => Ok(multi_tenant()),
+        WorkloadType::Bursty => Ok(bursty()),
+        WorkloadType::ReadLatest => Ok(read_latest()),
+        WorkloadType::RangeScan => Ok(range_scan()),
+        WorkloadType::TimeSeries => Ok(time_series()),
+        WorkloadType::Replay => Ok(replay()),
+        WorkloadType::Custom => Ok(WorkloadSpec::default()),
+        WorkloadType::YcsbA => ycsb::core_workload("workloada"),
+        WorkloadType::YcsbB => ycsb::core_workload("workloadb"),
+        WorkloadType::YcsbC => ycsb::core_workload("workloadc"),
+        WorkloadType::YcsbD => ycsb::core_workload("workloadd"),
+        WorkloadType::YcsbE => ycsb::core_workload("workloade"),
+        WorkloadType::YcsbF => ycsb::core_workload("workloadf"),
+    }
+}
+
+pub fn read_write() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "ReadWrite".to_string(),
+        read_percent: 0.5,
+        write_percent: 0.5,
+        ..Default::default()
+    }
+}
+
+pub fn read_heavy() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "ReadHeavy".to_string(),
+        read_percent: 0.95,
+        write_percent: 0.05,
+        ..Default::default()
+    }
+}
+
+pub fn read_only() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "ReadOnly".to_string(),
+        read_percent: 1.0,
+        write_percent: 0.0,
+        ..Default::default()
+    }
+}
+
+pub fn insert_only() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "InsertOnly".to_string(),
+        read_percent: 0.0,
+        write_percent: 0.0,
+        insert_percent: 1.0,
+        ..Default::default()
+    }
+}
+
+pub fn sequential_insert() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "SequentialInsert".to_string(),
+        read_percent: 0.0,
+        write_percent: 0.0,
+        insert_percent: 1.0,
+        key_order: KeyOrder::Ordered,
+        ..Default::default()
+    }
+}
+
+pub fn read_modify_write() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "ReadModifyWrite".to_string(),
+        read_percent: 0.5,
+        write_percent: 0.0,
+        read_modify_write_percent: 0.5,
+        ..Default::default()
+    }
+}
+
+pub fn churn() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "Churn".to_string(),
+        // large enough that few deletes hit a record deleted before
+        record_count: 100_000,
+        read_percent: 0.5,
+        write_percent: 0.0,
+        insert_percent: 0.25,
+        delete_percent: 0.25,
+        request_distribution: KeyDistribution::Uniform,
+        ..Default::default()
+    }
+}
+
+pub fn fifo_churn() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "FifoChurn".to_string(),
+        // the loaded records are replaced about three times over
+        record_count: 100_000,
+        operation_count: 50_000,
+        read_percent: 0.2,
+        write_percent: 0.0,
+        insert_percent: 0.4,
+        delete_percent: 0.4,
+        delete_oldest: true,
+        request_distribution: KeyDistribution::Latest,
+        ..Default::default()
+    }
+}
+
+pub fn hotspot() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "Hotspot".to_string(),
+        read_percent: 0.5,
+        write_percent: 0.5,
+        request_distribution: KeyDistribution::Hotspot,
+        hotspot_data_fraction: 0.1,
+        hotspot_ops_fraction: 0.9,
+        ..Default::default()
+    }
+}
+
+pub fn tiny_cache() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "TinyCache".to_string(),
+        record_count: 100_000,
+        operation_count: 500_000,
+        read_percent: 0.9,
+        write_percent: 0.1,
+        key_size: 16,
+        value_size_min: 32,
+        value_size_max: 128,
+        ..Default::default()
+    }
+}
+
+pub fn contention() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "Contention".to_string(),
+        read_percent: 0.5,
+        write_percent: 0.5,
+        // 4 of the 10_000 loaded records, which stay loaded so the tree keeps its size
+        request_distribution: KeyDistribution::Hotspot,
+        hotspot_data_fraction: 0.0004,
+        hotspot_ops_fraction: 1.0,
+        ..Default::default()
+    }
+}
+
+pub fn multi_tenant() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "MultiTenant".to_string(),
+        read_percent: 0.8,
+        write_percent: 0.2,
+        tenant_count: 8,
+        tenant_distribution: KeyDistribution::Zipfian,
+        ..Default::default()
+    }
+}
+
+pub fn bursty() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "Bursty".to_string(),
+        read_percent: 0.5,
+        write_percent: 0.5,
+        burst_on_ms: 50,
+        burst_off_ms: 200,
+        ..Default::default()
+    }
+}
+
+pub fn read_latest() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "ReadLatest".to_string(),
+        read_percent: 0.95,
+        write_percent: 0.0,
+        insert_percent: 0.05,
+        request_distribution: KeyDistribution::Latest,
+        ..Default::default()
+    }
+}
+
+pub fn range_scan() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "RangeScan".to_string(),
+        read_percent: 0.05,
+        write_percent: 0.0,
+        scan_percent: 0.95,
+        scan_length_min: 1,
+        scan_length_max: 100,
+        scan_length_distribution: KeyDistribution::Uniform,
+        ..Default::default()
+    }
+}
+
+pub fn time_series() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "TimeSeries".to_string(),
+        read_percent: 0.0,
+        write_percent: 0.0,
+        insert_percent: 0.9,
+        scan_percent: 0.1,
+        scan_length_min: 10,
+        scan_length_max: 100,
+        scan_length_distribution: KeyDistribution::Uniform,
+        request_distribution: KeyDistribution::Latest,
+        key_order: KeyOrder::TimeSeries,
+        series_count: 100,
+        key_size: 16,
+        value_size_min: 64,
+        value_size_max: 256,
+        ..Default::default()
+    }
+}
+
+pub fn replay() -> WorkloadSpec {
+    WorkloadSpec {
+        name: "Replay".to_string(),
+        // an empty path fails validation until `replay_ops` is set
+        replay_ops: Some(PathBuf::new()),
+        ..Default::default()
+    }
+}
@@ -0,0 +1,507 @@
+use crate::corpus::OpMix;
+use crate::generator::{InsertCounter, KeyChooser, KeyDistribution, KeyFormat, KeyOrder};
+use crate::properties::Properties;
+use crate::workload::ConfigError;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Property naming a TOML file of workload fields, applied before the other properties
+pub const FILE_PROPERTY: &str = "file";
+
+/// All parameters describing a workload. Presets construct one, properties override fields.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WorkloadSpec {
+    pub name: String,
+    /// How many records to insert during load phase
+    pub record_count: u64,
+    /// How many operations each thread executes in run phase
+    pub operation_count: u64,
+    /// Of all the operations executed in run phase, what percentage are reads. This and the
+    /// other `_percent` fields are shares of the operations relative to their sum, like
+    /// YCSB's proportions, plain fractions when they add up to 1.
+    pub read_percent: f64,
+    /// Of all run phase operations, the fraction overwriting a loaded record, as against
+    /// `insert_percent`
+    pub write_percent: f64,
+    /// Of all run phase operations, the fraction inserting records that were not loaded, each
+    /// under a new key following the loaded ones. Recorded apart from the writes, which
+    /// overwrite loaded records.
+    pub insert_percent: f64,
+    /// Of all run phase operations, the fraction reading a loaded record and writing a new
+    /// value back, see `Database::read_modify_write`
+    pub read_modify_write_percent: f64,
+    /// Of all run phase operations, the fraction deleting a loaded record, chosen like the
+    /// records read and updated. Reads of deleted records miss.
+    pub delete_percent: f64,
+    /// Deletes take the oldest record still there instead, in the order records were loaded
+    /// and inserted. With as many inserts as deletes the live records stay about as many as
+    /// were loaded, the newest ones, which the latest distribution reads.
+    pub delete_oldest: bool,
+    /// Of all run phase operations, the fraction scanning records in key order from a
+    /// chosen key on, see `Database::scan`. Unless keys are ordered, a scan reads records
+    /// that were loaded far apart. Time series scans end at the chosen key, reading the
+    /// window of its series up to it.
+    pub scan_percent: f64,
+    /// Range of records each scan reads (inclusive)
+    pub scan_length_min: u64,
+    pub scan_length_max: u64,
+    /// How scan lengths are picked from their range, zipfian favouring the shortest
+    pub scan_length_distribution: KeyDistribution,
+    /// How run phase keys are picked from the loaded records
+    pub request_distribution: KeyDistribution,
+    /// With the hotspot distribution, the fraction of the loaded records that are hot
+    pub hotspot_data_fraction: f64,
+    /// With the hotspot distribution, the fraction of operations on the hot records
+    pub hotspot_ops_fraction: f64,
+    /// key size
+    pub key_size: u64,
+    /// How loaded and inserted records' indexes become keys, ordered ones increasing with
+    /// every record
+    pub key_order: KeyOrder,
+    /// With time series keys, how many series the records are spread over
+    pub series_count: u32,
+    /// Tenants the records are spread over evenly, each key starting with its tenant's id,
+    /// see `KeyFormat`. Above 1 the run phase picks a tenant by `tenant_distribution`, then
+    /// one of its records by `request_distribution`, and counts each tenant's operations.
+    /// Inserts go to the tenants in turn.
+    pub tenant_count: u32,
+    pub tenant_distribution: KeyDistribution,
+    /// Range of value sizes (inclusive)
+    pub value_size_min: u64,
+    pub value_size_max: u64,
+    /// How many threads to execute this workload on (total ops = thread_count*operation_count)
+    pub thread_count: u32,
+    /// Generate every run phase key, value and operation before measuring, see `Corpus`
+    pub pregenerate: bool,
+    /// Seed of the generated values and operations, random when unset. Each run thread
+    /// derives its own seed from it, so the same seed replays the same streams.
+    pub seed: Option<u64>,
+    /// Time this many consecutive operations together and record their average latency,
+    /// for backends too fast for per-operation clock reads. Above 1 the time spent
+    /// generating keys and values between operations is included, unless `pregenerate` is set.
+    pub timing_batch: u64,
+    /// Operations each run thread submits at once through `Database::pipeline`, keeping that
+    /// many requests in flight on backends that pipeline over their connection
+    pub pipeline_depth: u32,
+    /// With `burst_off_ms` above 0, the run threads issue operations in bursts of this many
+    /// milliseconds at full rate, all idle for `burst_off_ms` in between. 0 for both runs a
+    /// closed loop.
+    pub burst_on_ms: u64,
+    pub burst_off_ms: u64,
+    /// Tables the records are spread over, each key always going to the same one. Backends
+    /// with tables (redb, sled, RocksDB) create one namespace per table, see
+    /// `Database::create_tables`.
+    pub table_count: u32,
+    /// Write every run phase operation to this file, see `oplog`
+    pub record_ops: Option<PathBuf>,
+    /// Run the operations of a log written by `record_ops` instead of generating them, one
+    /// thread per recorded thread and as fast as the database allows. The load phase loads
+    /// the records of the workload the log was recorded from.
+    pub replay_ops: Option<PathBuf>,
+}
+
+impl Default for WorkloadSpec {
+    fn default() -> Self {
+        WorkloadSpec {
+            name: "Custom".to_string(),
+            record_count: 10_000,
+            operation_count: 8_000,
+            read_percent: 0.5,
+            write_percent: 0.5,
+            insert_percent: 0.0,
+            read_modify_write_percent: 0.0,
+            delete_percent: 0.0,
+            delete_oldest: false,
+            scan_percent: 0.0,
+            scan_length_min: 1,
+            scan_length_max: 100,
+            scan_length_distribution: KeyDistribution::Uniform,
+            request_distribution: KeyDistribution::Zipfian,
+            // YCSB's defaults
+            hotspot_data_fraction: 0.2,
+            hotspot_ops_fraction: 0.8,
+            key_size: 128,
+            key_order: KeyOrder::Hashed,
+            series_count: 100,
+            tenant_count: 1,
+            tenant_distribution: KeyDistribution::Zipfian,
+            value_size_min: 512,
+            value_size_max: 1024,
+            thread_count: 16,
+            pregenerate: false,
+            seed: None,
+            timing_batch: 1,
+            pipeline_depth: 1,
+            burst_on_ms: 0,
+            burst_off_ms: 0,
+            table_count: 1,
+            record_ops: None,
+            replay_ops: None,
+        }
+    }
+}
+
+/// Multiplier applied to a workload's record and operation counts
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Scale(pub f64);
+
+impl FromStr for Scale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let factor = match s {
+            "smoke" => 0.01,
+            "small" => 1.0,
+            "medium" => 10.0,
+            "large" => 100.0,
+            other => other.parse().map_err(|_| {
+                format!("expected smoke, small, medium, large or a number, got `{other}`")
+            })?,
+        };
+        if factor > 0.0 && f64::is_finite(factor) {
+            Ok(Scale(factor))
+        } else {
+            Err(format!("scale must be a positive number, got `{s}`"))
+        }
+    }
+}
+
+impl WorkloadSpec {
+    /// Returns a copy with record and operation counts multiplied by `scale`, keeping at least one of each
+    pub fn scaled(&self, scale: Scale) -> Self {
+        let apply = |n: u64| ((n as f64 * scale.0).round() as u64).max(1);
+        WorkloadSpec {
+            record_count: apply(self.record_count),
+            operation_count: apply(self.operation_count),
+            ..self.clone()
+        }
+    }
+
+    pub fn value_size_range(&self) -> RangeInclusive<u64> {
+        self.value_size_min..=self.value_size_max
+    }
+
+    pub fn scan_length_range(&self) -> RangeInclusive<u64> {
+        self.scan_length_min..=self.scan_length_max
+    }
+
+    /// Picks the run phase's keys among the loaded records, or the newest ones `inserts`
+    /// handed out. With several tenants, picks a tenant and then one of its records.
+    pub fn key_chooser(&self, inserts: &Arc<InsertCounter>) -> Result<KeyChooser> {
+        if self.tenant_count > 1 {
+            let count = u64::from(self.tenant_count);
+            return Ok(KeyChooser::tenants(
+                KeyChooser::new(count, self.tenant_distribution)?,
+                self.record_chooser(self.record_count / count, inserts)?,
+                count,
+            ));
+        }
+        self.record_chooser(self.record_count, inserts)
+    }
+
+    /// Picks one of `range` records following `request_distribution`
+    fn record_chooser(&self, range: u64, inserts: &Arc<InsertCounter>) -> Result<KeyChooser> {
+        match self.request_distribution {
+            KeyDistribution::Latest => KeyChooser::latest(range, inserts.clone()),
+            KeyDistribution::Hotspot => Ok(KeyChooser::hotspot(
+                range,
+                self.hotspot_data_fraction,
+                self.hotspot_ops_fraction,
+            )),
+            distribution => KeyChooser::new(range, distribution),
+        }
+    }
+
+    /// Picks run phase operation kinds by the `_percent` fields' shares
+    pub fn op_mix(&self) -> OpMix {
+        OpMix::new([
+            self.read_percent,
+            self.write_percent,
+            self.insert_percent,
+            self.read_modify_write_percent,
+            self.delete_percent,
+            self.scan_percent,
+        ])
+    }
+
+    pub fn key_format(&self) -> KeyFormat {
+        KeyFormat {
+            order: self.key_order,
+            series_count: self.series_count,
+            tenant_count: self.tenant_count,
+        }
+    }
+
+    /// Expected size of the loaded keys and values, without any engine overhead
+    pub fn data_size_bytes(&self) -> u64 {
+        let avg_value = (self.value_size_min + self.value_size_max) / 2;
+        self.record_count * (self.key_size + avg_value)
+    }
+
+    /// Returns a copy of this spec with every workload property applied on top, after the
+    /// fields of the `file` property's TOML file
+    pub fn with_overrides(&self, props: &Properties) -> Result<Self> {
+        let spec = match props.get(FILE_PROPERTY) {
+            Some(path) => self.with_file(Path::new(path))?,
+            None => self.clone(),
+        };
+        let fields = props
+            .workload()
+            .filter(|(k, _)| *k != FILE_PROPERTY)
+            .map(|(k, v)| {
+                // numbers and bools parse as json, everything else is taken as a string
+                let v = serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.clone()));
+                (k.clone(), v)
+            });
+        spec.with_fields(fields)
+            .context("invalid workload property")
+    }
+
+    /// Returns a copy with the fields of a TOML file applied on top, named after the file
+    /// unless it sets `name`
+    pub fn with_file(&self, path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read workload file {}", path.display()))?;
+        let table: toml::Table = toml::from_str(&content)
+            .with_context(|| format!("invalid workload file {}", path.display()))?;
+        let named = table.contains_key("name");
+        let fields = table
+            .into_iter()
+            .map(|(k, v)| Ok((k, serde_json::to_value(v)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut spec = self
+            .with_fields(fields)
+            .with_context(|| format!("invalid workload file {}", path.display()))?;
+        if let (false, Some(stem)) = (named, path.file_stem()) {
+            spec.name = stem.to_string_lossy().into_owned();
+        }
+        Ok(spec)
+    }
+
+    fn with_fields(&self, fields: impl IntoIterator<Item = (String, Value)>) -> Result<Self> {
+        let mut value = serde_json::to_value(self)?;
+        value
+            .as_object_mut()
+            .expect("WorkloadSpec serializes to a map")
+            .extend(fields);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let read = self.read_percent;
+        let write = self.write_percent;
+        let insert = self.insert_percent;
+        let rmw = self.read_modify_write_percent;
+        let delete = self.delete_percent;
+        let scan = self.scan_percent;
+        if !(read >= 0.0 && read.is_finite()) {
+            return Err(ConfigError::new(
+                "read_percent",
+                format!("must be at least 0, got {read}"),
+            ));
+        }
+        if !(write >= 0.0 && write.is_finite()) {
+            return Err(ConfigError::new(
+                "write_percent",
+                format!("must be at least 0, got {write}"),
+            ));
+        }
+        if !(insert >= 0.0 && insert.is_finite()) {
+            return Err(ConfigError::new(
+                "insert_percent",
+                format!("must be at least 0, got {insert}"),
+            ));
+        }
+        if !(rmw >= 0.0 && rmw.is_finite()) {
+            return Err(ConfigError::new(
+                "read_modify_write_percent",
+                format!("must be at least 0, got {rmw}"),
+            ));
+        }
+        if !(delete >= 0.0 && delete.is_finite()) {
+            return Err(ConfigError::new(
+                "delete_percent",
+                format!("must be at least 0, got {delete}"),
+            ));
+        }
+        if !(scan >= 0.0 && scan.is_finite()) {
+            return Err(ConfigError::new(
+                "scan_percent",
+                format!("must be at least 0, got {scan}"),
+            ));
+        }
+        if read + write + insert + rmw + delete + scan <= 0.0 {
+            return Err(ConfigError::new(
+                "read_percent",
+                "read_percent, write_percent, insert_percent, read_modify_write_percent, delete_percent and scan_percent cannot all be 0",
+            ));
+        }
+        if !matches!(
+            self.scan_length_distribution,
+            KeyDistribution::Zipfian | KeyDistribution::Uniform
+        ) {
+            return Err(ConfigError::new(
+                "scan_length_distribution",
+                "must be `zipfian` or `uniform`",
+            ));
+        }
+        if !(self.hotspot_data_fraction > 0.0 && self.hotspot_data_fraction <= 1.0) {
+            return Err(ConfigError::new(
+                "hotspot_data_fraction",
+                format!(
+                    "must be above 0 and at most 1, got {}",
+                    self.hotspot_data_fraction
+                ),
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.hotspot_ops_fraction) {
+            return Err(ConfigError::new(
+                "hotspot_ops_fraction",
+                format!("must be between 0 and 1, got {}", self.hotspot_ops_fraction),
+            ));
+        }
+        if self.scan_length_min == 0 {
+            return Err(ConfigError::new("scan_length_min", "must be at least 1"));
+        }
+        if self.scan_length_min > self.scan_length_max {
+            return Err(ConfigError::new(
+                "scan_length_max",
+                format!(
+                    "must be at least scan_length_min ({}), got {}",
+                    self.scan_length_min, self.scan_length_max
+                ),
+            ));
+        }
+
+        if self.value_size_min > self.value_size_max {
+            return Err(ConfigError::new(
+                "value_size_max",
+                format!(
+                    "must be at least value_size_min ({}), got {}",
+                    self.value_size_min, self.value_size_max
+                ),
+            ));
+        }
+        if self.timing_batch == 0 {
+            return Err(ConfigError::new("timing_batch", "must be at least 1"));
+        }
+        if self
+            .replay_ops
+            .as_ref()
+            .is_some_and(|p| p.as_os_str().is_empty())
+        {
+            return Err(ConfigError::new(
+                "replay_ops",
+                "set the operation log to replay, e.g. `-p replay_ops=ops.bin`",
+            ));
+        }
+        if self.pipeline_depth == 0 {
+            return Err(ConfigError::new("pipeline_depth", "must be at least 1"));
+        }
+        if self.pipeline_depth > 1 && self.timing_batch > 1 {
+            return Err(ConfigError::new(
+                "timing_batch",
+                "pipelined operations are already timed per pipeline, leave it at 1",
+            ));
+        }
+        if self.pipeline_depth > 1 && rmw > 0.0 {
+            return Err(ConfigError::new(
+                "read_modify_write_percent",
+                "the write of a read-modify-write waits for its read and cannot be pipelined, leave pipeline_depth at 1",
+            ));
+        }
+        if self.pipeline_depth > 1 && scan > 0.0 {
+            return Err(ConfigError::new(
+                "scan_percent",
+                "a scan returns its records rather than a status and cannot be pipelined, leave pipeline_depth at 1",
+            ));
+        }
+        if self.series_count == 0 {
+            return Err(ConfigError::new("series_count", "must be at least 1"));
+        }
+        if self.key_order == KeyOrder::TimeSeries && self.key_size < 12 {
+            return Err(ConfigError::new(
+                "key_size",
+                format!(
+                    "time series keys hold a 4 byte series id and an 8 byte point, must be at least 12, got {}",
+                    self.key_size
+                ),
+            ));
+        }
+        if self.tenant_count == 0 {
+            return Err(ConfigError::new("tenant_count", "must be at least 1"));
+        }
+        if self.tenant_count > 1 {
+            if u64::from(self.tenant_count) > self.record_count {
+                return Err(ConfigError::new(
+                    "tenant_count",
+                    format!(
+                        "must be at most record_count ({}), got {}",
+                        self.record_count, self.tenant_count
+                    ),
+                ));
+            }
+            if !matches!(
+                self.tenant_distribution,
+                KeyDistribution::Zipfian | KeyDistribution::Uniform
+            ) {
+                return Err(ConfigError::new(
+                    "tenant_distribution",
+                    "must be `zipfian` or `uniform`",
+                ));
+            }
+            if self.request_distribution == KeyDistribution::Latest {
+                return Err(ConfigError::new(
+                    "request_distribution",
+                    "inserts do not follow the tenants' skew, `latest` cannot be combined with tenant_count",
+                ));
+            }
+            if self.key_order == KeyOrder::TimeSeries {
+                return Err(ConfigError::new(
+                    "key_order",
+                    "time series keys start with their series id, `time_series` cannot be combined with tenant_count",
+                ));
+            }
+            if self.key_size < 12 {
+                return Err(ConfigError::new(
+                    "key_size",
+                    format!(
+                        "tenant keys hold a 4 byte tenant id ahead of at least 8 bytes of the record, must be at least 12, got {}",
+                        self.key_size
+                    ),
+                ));
+            }
+        }
+        if self.burst_off_ms > 0 && self.burst_on_ms == 0 {
+            return Err(ConfigError::new(
+                "burst_on_ms",
+                "must be above 0 with burst_off_ms, or no operation would run",
+            ));
+        }
+        if self.table_count == 0 {
+            return Err(ConfigError::new("table_count", "must be at least 1"));
+        }
+        if self.table_count > 1 && self.pipeline_depth > 1 {
+            return Err(ConfigError::new(
+                "table_count",
+                "pipelined operations do not name a table, leave it at 1 with pipeline_depth",
+            ));
+        }
+        if self.thread_count == 0 {
+            return Err(ConfigError::new("thread_count", "must be at least 1"));
+        }
+        if self.record_count == 0 {
+            return Err(ConfigError::new(
+                "record_count",
+                "must be at least 1, the run phase reads keys inserted during load",
+            ));
+        }
+        Ok(())
+    }
+}
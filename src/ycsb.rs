@@ -0,0 +1,336 @@
+use crate::generator::{KeyDistribution, KeyOrder};
+use crate::workload::spec::WorkloadSpec;
+use crate::workload::{self, ConfigError, WorkloadStats};
+use anyhow::{Context, Result};
+use hdrhistogram::Histogram;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// Applies a YCSB workload file (e.g. `workloads/workloada`) on top of `spec`.
+///
+/// YCSB's `operationcount` is the total across all threads, so it is split evenly over
+/// `threadcount` (or the spec's thread count when the file does not set one).
+pub fn apply_properties_file(spec: &WorkloadSpec, path: &Path) -> Result<WorkloadSpec> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read YCSB properties {}", path.display()))?;
+    let props = parse_properties(&content);
+    let mut spec = apply_properties(spec, &props)
+        .with_context(|| format!("unsupported YCSB properties in {}", path.display()))?;
+    if let Some(stem) = path.file_stem() {
+        spec.name = stem.to_string_lossy().into_owned();
+    }
+    Ok(spec)
+}
+
+/// YCSB's core workloads as its `workloads/workload[a-f]` files define them
+const CORE_WORKLOADS: [(&str, &str); 6] = [
+    (
+        "workloada",
+        "recordcount=1000
+operationcount=1000
+readproportion=0.5
+updateproportion=0.5
+scanproportion=0
+insertproportion=0
+requestdistribution=zipfian",
+    ),
+    (
+        "workloadb",
+        "recordcount=1000
+operationcount=1000
+readproportion=0.95
+updateproportion=0.05
+scanproportion=0
+insertproportion=0
+requestdistribution=zipfian",
+    ),
+    (
+        "workloadc",
+        "recordcount=1000
+operationcount=1000
+readproportion=1
+updateproportion=0
+scanproportion=0
+insertproportion=0
+requestdistribution=zipfian",
+    ),
+    (
+        "workloadd",
+        "recordcount=1000
+operationcount=1000
+readproportion=0.95
+updateproportion=0
+scanproportion=0
+insertproportion=0.05
+requestdistribution=latest",
+    ),
+    (
+        "workloade",
+        "recordcount=1000
+operationcount=1000
+readproportion=0
+updateproportion=0
+scanproportion=0.95
+insertproportion=0.05
+requestdistribution=zipfian
+maxscanlength=100
+scanlengthdistribution=uniform",
+    ),
+    (
+        "workloadf",
+        "recordcount=1000
+operationcount=1000
+readproportion=0.5
+updateproportion=0
+scanproportion=0
+insertproportion=0
+readmodifywriteproportion=0.5
+requestdistribution=zipfian",
+    ),
+];
+
+/// One of YCSB's core workloads, `name` being its file name (e.g. `workloada`), with the
+/// CoreWorkload defaults the files leave unset: ten 100 byte fields per record and keys of
+/// `user` followed by a 19 digit hash
+pub fn core_workload(name: &str) -> Result<WorkloadSpec> {
+    let (_, content) = CORE_WORKLOADS
+        .iter()
+        .find(|(n, _)| *n == name)
+        .with_context(|| format!("no YCSB core workload `{name}`"))?;
+    let spec = WorkloadSpec {
+        name: name.to_string(),
+        key_size: 23,
+        value_size_min: 1000,
+        value_size_max: 1000,
+        ..Default::default()
+    };
+    apply_properties(&spec, &parse_properties(content))
+        .with_context(|| format!("YCSB {name} is not supported yet"))
+}
+
+/// Parses the subset of the java `.properties` format that YCSB workload files use
+fn parse_properties(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('!'))
+        .map(|l| {
+            let (k, v) = l
+                .split_once(['=', ':'])
+                .or_else(|| l.split_once(char::is_whitespace))
+                .unwrap_or((l, ""));
+            (k.trim().to_string(), v.trim().to_string())
+        })
+        .collect()
+}
+
+fn apply_properties(
+    spec: &WorkloadSpec,
+    props: &BTreeMap<String, String>,
+) -> Result<WorkloadSpec, ConfigError> {
+    let mut spec = spec.clone();
+    let get = |key: &'static str| -> Option<&str> { props.get(key).map(String::as_str) };
+
+    if let Some(v) = get("threadcount") {
+        spec.thread_count = parse(v, "threadcount")?;
+    }
+    if let Some(v) = get("recordcount") {
+        spec.record_count = parse(v, "recordcount")?;
+    }
+    if let Some(v) = get("operationcount") {
+        let total: u64 = parse(v, "operationcount")?;
+        spec.operation_count = total.div_ceil(u64::from(spec.thread_count.max(1)));
+    }
+
+    let read = get("readproportion")
+        .map(|v| parse(v, "readproportion"))
+        .transpose()?;
+    let update = get("updateproportion")
+        .map(|v| parse::<f64>(v, "updateproportion"))
+        .transpose()?;
+    let insert = get("insertproportion")
+        .map(|v| parse::<f64>(v, "insertproportion"))
+        .transpose()?;
+    let rmw = get("readmodifywriteproportion")
+        .map(|v| parse::<f64>(v, "readmodifywriteproportion"))
+        .transpose()?;
+    let scan = get("scanproportion")
+        .map(|v| parse::<f64>(v, "scanproportion"))
+        .transpose()?;
+    if let Some(read) = read {
+        spec.read_percent = read;
+    }
+    if update.is_some() || insert.is_some() || rmw.is_some() || scan.is_some() {
+        spec.write_percent = update.unwrap_or(0.0);
+        spec.insert_percent = insert.unwrap_or(0.0);
+        spec.read_modify_write_percent = rmw.unwrap_or(0.0);
+        spec.scan_percent = scan.unwrap_or(0.0);
+    } else if read.is_some() {
+        spec.write_percent = 0.0;
+        spec.insert_percent = 0.0;
+        spec.read_modify_write_percent = 0.0;
+        spec.scan_percent = 0.0;
+    }
+
+    if let Some(v) = get("minscanlength") {
+        spec.scan_length_min = parse(v, "minscanlength")?;
+    }
+    if let Some(v) = get("maxscanlength") {
+        spec.scan_length_max = parse(v, "maxscanlength")?;
+    }
+    if let Some(v) = get("scanlengthdistribution") {
+        spec.scan_length_distribution = match v {
+            "zipfian" => KeyDistribution::Zipfian,
+            "uniform" => KeyDistribution::Uniform,
+            other => {
+                return Err(ConfigError::new(
+                    "scanlengthdistribution",
+                    format!("`{other}` is not supported, expected `zipfian` or `uniform`"),
+                ));
+            }
+        };
+    }
+
+    if let Some(v) = get("requestdistribution") {
+        spec.request_distribution = match v {
+            "zipfian" => KeyDistribution::Zipfian,
+            "uniform" => KeyDistribution::Uniform,
+            "hotspot" => KeyDistribution::Hotspot,
+            "latest" => KeyDistribution::Latest,
+            other => {
+                return Err(ConfigError::new(
+                    "requestdistribution",
+                    format!(
+                        "`{other}` is not supported, expected `zipfian`, `uniform`, `hotspot` or `latest`"
+                    ),
+                ));
+            }
+        };
+    }
+    if let Some(v) = get("hotspotdatafraction") {
+        spec.hotspot_data_fraction = parse(v, "hotspotdatafraction")?;
+    }
+    if let Some(v) = get("hotspotopnfraction") {
+        spec.hotspot_ops_fraction = parse(v, "hotspotopnfraction")?;
+    }
+    if let Some(v) = get("insertorder") {
+        spec.key_order = match v {
+            "hashed" => KeyOrder::Hashed,
+            "ordered" => KeyOrder::Ordered,
+            other => {
+                return Err(ConfigError::new(
+                    "insertorder",
+                    format!("`{other}` is not supported, expected `hashed` or `ordered`"),
+                ));
+            }
+        };
+    }
+
+    // YCSB values are `fieldcount` fields of `fieldlength` bytes each
+    if get("fieldcount").is_some() || get("fieldlength").is_some() {
+        let field_count: u64 = get("fieldcount")
+            .map(|v| parse(v, "fieldcount"))
+            .transpose()?
+            .unwrap_or(10);
+        let field_length: u64 = get("fieldlength")
+            .map(|v| parse(v, "fieldlength"))
+            .transpose()?
+            .unwrap_or(100);
+        match get("fieldlengthdistribution").unwrap_or("constant") {
+            "constant" => spec.value_size_min = field_count * field_length,
+            "uniform" | "zipfian" => spec.value_size_min = field_count,
+            other => {
+                return Err(ConfigError::new(
+                    "fieldlengthdistribution",
+                    format!("`{other}` is not supported"),
+                ));
+            }
+        }
+        spec.value_size_max = field_count * field_length;
+    }
+
+    Ok(spec)
+}
+
+fn parse<T: std::str::FromStr>(value: &str, key: &'static str) -> Result<T, ConfigError> {
+    value
+        .parse()
+        .map_err(|_| ConfigError::new(key, format!("cannot parse `{value}`")))
+}
+
+/// Formats run phase stats the way YCSB's text measurement exporter does
+pub struct YcsbReport<'a>(pub &'a WorkloadStats);
+
+impl Display for YcsbReport<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let stats = self.0;
+        let total_ops = stats.run_ops();
+        let wall = stats.run_wall_time.as_secs_f64();
+        let throughput = if wall > 0.0 {
+            total_ops as f64 / wall
+        } else {
+            0.0
+        };
+        writeln!(
+            f,
+            "[OVERALL], RunTime(ms), {}",
+            stats.run_wall_time.as_millis()
+        )?;
+        write!(f, "[OVERALL], Throughput(ops/sec), {throughput}")?;
+        write_op(
+            f,
+            "READ",
+            stats.run_read_ops,
+            stats.run_read_misses.unwrap_or(0),
+            &stats.run_read_hist_nano_sec,
+        )?;
+        write_op(
+            f,
+            "UPDATE",
+            stats.run_write_ops,
+            0,
+            &stats.run_write_hist_nano_sec,
+        )?;
+        for (kind, s) in stats.run_extra_ops() {
+            if let Some(s) = s {
+                write_op(f, &kind.to_uppercase(), s.ops, 0, &s.hist_nano_sec)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_op(
+    f: &mut Formatter<'_>,
+    op: &str,
+    ops: u64,
+    misses: u64,
+    hist: &Histogram<u64>,
+) -> std::fmt::Result {
+    // YCSB omits operation types that never ran
+    if ops == 0 {
+        return Ok(());
+    }
+    writeln!(f)?;
+    writeln!(f, "[{op}], Operations, {ops}")?;
+    // the histograms hold nanoseconds, YCSB reports microseconds
+    writeln!(f, "[{op}], AverageLatency(us), {}", hist.mean() / 1_000.0)?;
+    writeln!(f, "[{op}], MinLatency(us), {}", hist.min() as f64 / 1_000.0)?;
+    writeln!(f, "[{op}], MaxLatency(us), {}", hist.max() as f64 / 1_000.0)?;
+    writeln!(
+        f,
+        "[{op}], 95thPercentileLatency(us), {}",
+        workload::quantile_micros(hist, 0.95)
+    )?;
+    writeln!(
+        f,
+        "[{op}], 99thPercentileLatency(us), {}",
+        workload::quantile_micros(hist, 0.99)
+    )?;
+    write!(f, "[{op}], Return=OK, {}", ops - misses)?;
+    if misses > 0 {
+        write!(f, "\n[{op}], Return=NOT_FOUND, {misses}")?;
+    }
+    Ok(())
+}
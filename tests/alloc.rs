@@ -0,0 +1,86 @@
+//! Checks that the measured loops do not allocate per operation, with an allocation counting
+//! global allocator that only this test binary installs
+
+use kvbencher::DatabaseType;
+use kvbencher::database::{DbOptions, get_db};
+use kvbencher::workload::Workload;
+use kvbencher::workload::spec::WorkloadSpec;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+// SAFETY: forwards every call unchanged to the system allocator
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// allocations that do not grow with the operation count are setup (threads, histograms,
+// generators), anything beyond this slack is a per-operation allocation
+const SLACK: u64 = 16;
+
+// the counter is process wide, so the tests take turns rather than count each other's allocations
+static SERIAL: Mutex<()> = Mutex::new(());
+
+/// Allocations of the load and the run phase of `spec` against the null database
+fn allocations(spec: &WorkloadSpec) -> (u64, u64) {
+    let db = get_db(DatabaseType::Null, &DbOptions::default()).unwrap();
+    let mut stats = spec.init_stats().unwrap();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    spec.exec_load(db.clone(), &mut stats).unwrap();
+    let load = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    spec.exec_run(db, &mut stats).unwrap();
+    (load, ALLOCATIONS.load(Ordering::Relaxed) - before)
+}
+
+fn assert_no_per_op_allocations(spec: WorkloadSpec) {
+    let _serial = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    let scaled = |ops: u64| WorkloadSpec {
+        record_count: ops,
+        operation_count: ops,
+        thread_count: 1,
+        ..spec.clone()
+    };
+    let (small_load, small_run) = allocations(&scaled(1_000));
+    let (large_load, large_run) = allocations(&scaled(100_000));
+    assert!(
+        large_load.saturating_sub(small_load) <= SLACK,
+        "load: {small_load} allocations for 1_000 ops, {large_load} for 100_000 ops"
+    );
+    assert!(
+        large_run.saturating_sub(small_run) <= SLACK,
+        "run: {small_run} allocations for 1_000 ops, {large_run} for 100_000 ops"
+    );
+}
+
+#[test]
+fn measured_loop_does_not_allocate_per_operation() {
+    assert_no_per_op_allocations(WorkloadSpec::default());
+}
+
+#[test]
+fn pipelined_loop_does_not_allocate_per_operation() {
+    assert_no_per_op_allocations(WorkloadSpec {
+        pipeline_depth: 8,
+        ..WorkloadSpec::default()
+    });
+}